@@ -0,0 +1,1537 @@
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::core::geometry::{Point3, Vec2, Vec3, Vector};
+use crate::image::{DisplayTransform, ImageFormat, ToneMap, TransferFunction};
+use crate::import::MeshFormat;
+use crate::scene::camera::{
+    ApertureMask, ApertureShape, Bokeh, Convergence, CubeFace, Eye, FisheyeMapping, Focus,
+    Projection, RealisticLens, StereoLayout, StereoRig,
+};
+use crate::scene::object::{BvhOptions, SplitMethod};
+use crate::scene::{Background, Shutter, ShutterCurve};
+use crate::scenes::{Animation, AnimationOptions, ImageSettings, View, SCENE_NAMES};
+
+pub const USAGE: &str = "\
+Usage: raytracing-rs [OPTIONS]
+
+Image (built-in scenes default to 1280x720, 500 samples and 50 bounces):
+    -w, --width <PIXELS>          Image width [default: from the scene]
+        --height <PIXELS>         Image height [default: keeps the aspect ratio of the scene]
+    -s, --samples <COUNT>         Samples per pixel [default: from the scene]
+    -d, --depth <COUNT>           Maximum number of ray bounces [default: from the scene]
+    -o, --output <PATH>           Output file, '-' writes to stdout [default: -]
+    -f, --format <FORMAT>         Output format: png, ppm, ppm-ascii, hdr, pfm, exr
+                                  [default: from the output extension, ppm-ascii for stdout]
+        --seed <NUMBER>           Seed of the random number generator
+    -j, --threads <COUNT>         Number of render threads [default: all cores]
+        --tile-size <PIXELS>      Size of the square tiles given to each thread [default: 16]
+
+Display (8-bit formats only):
+        --exposure <STOPS>        Exposure adjustment [default: 0]
+        --tonemap <OPERATOR>      Tone mapping operator: clamp, reinhard, reinhard-extended,
+                                  aces, hable [default: clamp]
+        --white <VALUE>           Smallest value mapped to white by reinhard-extended [default: 4]
+        --transfer <FUNCTION>     Transfer function: srgb, linear, gamma<VALUE> (e.g. gamma2.2)
+                                  [default: srgb]
+
+Scene:
+        --scene <NAME>            Scene to render: random, two-spheres, simple-light,
+                                  cornell-box [default: random]
+        --scene-file <PATH>       Scene to render, from a JSON scene file
+        --background <COLOR>      Color of the rays leaving the scene: sky or R,G,B
+                                  [default: from the scene]
+        --mesh <PATH>             Add the meshes of an OBJ, PLY or STL file to the scene
+                                  (repeatable)
+        --bvh <METHOD>            Split method of the mesh hierarchies: sah, median, random
+                                  [default: sah]
+        --leaf-size <COUNT>       Largest number of triangles in a hierarchy leaf [default: 4]
+        --export-scene <PATH>     Write the scene as a JSON scene file instead of rendering
+
+Animation (renders numbered frames, e.g. frame_0001.png, named after --output):
+        --frames <FIRST..LAST>    Frames to render, or a single frame [default: from the scene]
+        --fps <RATE>              Frames per second [default: from the scene, or 24]
+        --shutter-angle <DEGREES> Fraction of each frame the shutter stays open, in degrees
+                                  up to 360 [default: from the scene, or 180]
+        --shutter <CURVE>         Opening of the shutter over each exposure: box, triangle,
+                                  trapezoid (opens and closes over a quarter each),
+                                  trapezoid:<OPEN>,<CLOSE> (fractions of the exposure), or
+                                  evenly spaced openings such as 0,1,1,0.5
+                                  [default: from the scene, or box]
+        --rolling-shutter <READOUT>
+                                  Expose the rows one after the other from the top, spending
+                                  this fraction of the exposure on the readout
+                                  [default: from the scene, or 0]
+
+Camera (defaults come from the scene):
+        --camera <PROJECTION>     Projection: perspective, orthographic[:HEIGHT],
+                                  fisheye[:MAPPING[,FOV]] with equidistant or equisolid mapping,
+                                  equirectangular, cubemap[:FACE] with px, nx, py, ny, pz or nz
+                                  (all six side by side without a face), or realistic[:DIAGONAL]
+                                  for a 50 mm double Gauss lens on a film DIAGONAL mm across,
+                                  focused at --focus-distance, for scenes in meters
+        --stereo <LAYOUT>         Render both eyes of a stereo rig, side-by-side, over-under
+                                  (each eye gets the image size) or to separate files named
+                                  after --output, e.g. frame_left.png [default: side-by-side
+                                  if the scene has a rig]
+        --interaxial <DIST>       Distance between the eyes [default: from the scene, or 0.065]
+        --convergence <MODE>      Convergence of the eyes: parallel, toed-in, or ods for
+                                  equirectangular cameras [default: from the scene, or ods for
+                                  equirectangular cameras and parallel otherwise]
+        --look-from <X,Y,Z>       Camera position
+        --look-at <X,Y,Z>         Point the camera looks at
+        --up <X,Y,Z>              Camera up vector
+        --fov <DEGREES>           Vertical field of view
+        --aperture <SIZE>         Lens aperture
+        --focus-distance <DIST>   Distance to the focus plane
+        --focus-on <X,Y,Z|auto>   Focus on a point, or on the first object along the axis of the
+                                  camera with auto, in every frame
+        --tilt <DEGREES>          Tilt of the focus plane around the horizontal axis, bringing
+                                  it closer at the bottom for positive angles [default: 0]
+        --shift <X,Y>             Lens shift, in image widths and heights, e.g. 0,0.2 to frame
+                                  a tall building with a level camera [default: 0,0]
+        --blades <COUNT[,DEGREES]>
+                                  Polygonal aperture with this many blades, turned from a
+                                  corner pointing up [default: from the scene, or circular]
+        --aperture-mask <PATH>    Aperture drawn as a .png or .ppm image, brighter pixels
+                                  letting more light through
+        --cat-eye <AMOUNT>        Vignetting of the lens barrel, from 0 to 1 where the corners
+                                  get no light [default: from the scene, or 0]
+
+    -h, --help                    Print this message
+";
+
+pub struct Settings {
+    pub image_width: Option<u32>,
+    pub image_height: Option<u32>,
+    pub samples_per_pixel: Option<u32>,
+    pub max_depth: Option<u32>,
+    pub output: Option<PathBuf>,
+    pub format: ImageFormat,
+    pub display: DisplayTransform,
+    pub seed: Option<u64>,
+    pub threads: Option<usize>,
+    pub tile_size: u32,
+
+    pub scene: String,
+    pub scene_file: Option<PathBuf>,
+    pub background: Option<Background>,
+    pub meshes: Vec<PathBuf>,
+    pub bvh: BvhOptions,
+    pub export_scene: Option<PathBuf>,
+
+    pub frames: Option<(u32, u32)>,
+    pub fps: Option<f64>,
+    pub shutter_angle: Option<f64>,
+    pub shutter_curve: Option<ShutterCurve>,
+    pub readout: Option<f64>,
+
+    pub projection: Option<Projection>,
+    pub stereo_layout: Option<StereoLayout>,
+    pub interaxial: Option<f64>,
+    pub convergence: Option<Convergence>,
+    pub look_from: Option<Point3>,
+    pub look_at: Option<Point3>,
+    pub up: Option<Vec3>,
+    pub vertical_fov: Option<f64>,
+    pub aperture: Option<f64>,
+    pub focus_distance: Option<f64>,
+    pub focus: Option<Focus>,
+    pub tilt: Option<f64>,
+    pub shift: Option<Vec2>,
+    pub blades: Option<(u32, f64)>,
+    pub aperture_mask: Option<PathBuf>,
+    pub cat_eye: Option<f64>,
+}
+
+impl Settings {
+    /// Applies the image options over the `default` settings of the scene.
+    /// Giving only the width keeps the aspect ratio of the scene, or the one
+    /// of the projection given by --camera.
+    pub fn image(&self, default: ImageSettings) -> ImageSettings {
+        let ratio = self
+            .projection
+            .as_ref()
+            .and_then(|projection| projection.aspect_ratio());
+
+        let image = match (self.image_width, self.image_height) {
+            (width, None) if ratio.is_some() => {
+                let width = width.unwrap_or(default.width);
+                let height = (width as f64 / ratio.unwrap()).round().max(2.0) as u32;
+
+                default.with_size(width, Some(height))
+            }
+            (Some(width), height) => default.with_size(width, height),
+            (None, Some(height)) => ImageSettings { height, ..default },
+            (None, None) => default,
+        };
+
+        ImageSettings {
+            samples_per_pixel: self.samples_per_pixel.unwrap_or(image.samples_per_pixel),
+            max_depth: self.max_depth.unwrap_or(image.max_depth),
+            ..image
+        }
+    }
+
+    /// Options overriding the animation of the scene.
+    pub const fn animation_options(&self) -> AnimationOptions {
+        AnimationOptions {
+            frames: self.frames,
+            fps: self.fps,
+            shutter_angle: self.shutter_angle,
+        }
+    }
+
+    /// Applies the animation options over the `default` animation of the
+    /// scene. Without frames, from either side, a single image is rendered.
+    pub fn animation(&self, default: Option<Animation>) -> Result<Option<Animation>, CliError> {
+        if self.frames.is_none()
+            && default.is_none()
+            && (self.fps.is_some() || self.shutter_angle.is_some())
+        {
+            return Err(CliError::Inconsistent(
+                "--fps and --shutter-angle need frames, from --frames or the scene".to_string(),
+            ));
+        }
+
+        Ok(self.animation_options().apply(default))
+    }
+
+    /// Path of a frame of an animation: the output path, `frame.png` by
+    /// default, with the frame number appended to its name.
+    pub fn image_path(&self, frame: Option<u32>, eye: Option<Eye>) -> PathBuf {
+        let output = self
+            .output
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(format!("frame.{}", self.format.extension())));
+
+        let mut name = output
+            .file_stem()
+            .map_or_else(|| "frame".to_string(), |stem| stem.to_string_lossy().into());
+
+        if let Some(eye) = eye {
+            name = format!("{}_{}", name, eye.name());
+        }
+
+        if let Some(frame) = frame {
+            name = format!("{}_{:04}", name, frame);
+        }
+
+        if let Some(extension) = output.extension() {
+            name = format!("{}.{}", name, extension.to_string_lossy());
+        }
+
+        output.with_file_name(name)
+    }
+
+    /// Applies the shutter options over the `default` shutter of the scene.
+    pub fn shutter(&self, default: Shutter) -> Shutter {
+        let shutter = match &self.shutter_curve {
+            Some(curve) => Shutter::new(curve.clone())
+                .expect("Shutter curve was validated")
+                .with_readout(default.readout()),
+            None => default,
+        };
+
+        match self.readout {
+            Some(readout) => shutter.with_readout(readout),
+            None => shutter,
+        }
+    }
+
+    /// Applies the stereo options over the `default` rig of the scene, for a
+    /// camera with the given `projection`. Without a rig, from either side,
+    /// a single view is rendered.
+    pub fn stereo(
+        &self,
+        default: Option<StereoRig>,
+        projection: &Projection,
+    ) -> Result<Option<(StereoRig, StereoLayout)>, CliError> {
+        let options_given =
+            self.stereo_layout.is_some() || self.interaxial.is_some() || self.convergence.is_some();
+
+        let rig = match default {
+            Some(rig) => rig,
+            None if options_given => StereoRig::for_projection(projection),
+            None => return Ok(None),
+        };
+
+        let rig = StereoRig {
+            interaxial: self.interaxial.unwrap_or(rig.interaxial),
+            convergence: self.convergence.unwrap_or(rig.convergence),
+        };
+
+        rig.validate(projection)
+            .map_err(|reason| CliError::Inconsistent(reason.to_string()))?;
+
+        Ok(Some((
+            rig,
+            self.stereo_layout.unwrap_or(StereoLayout::SideBySide),
+        )))
+    }
+
+    /// Applies the aperture options over the `default` bokeh of the scene,
+    /// with the `mask` read from --aperture-mask.
+    pub fn bokeh(&self, default: Bokeh, mask: Option<ApertureMask>) -> Bokeh {
+        let shape = match (mask, self.blades) {
+            (Some(mask), _) => ApertureShape::Mask(Arc::new(mask)),
+            (None, Some((blades, rotation))) => ApertureShape::Polygon { blades, rotation },
+            (None, None) => default.shape().clone(),
+        };
+
+        Bokeh::new(shape)
+            .expect("Aperture shape was validated")
+            .with_cat_eye(self.cat_eye.unwrap_or(default.cat_eye()))
+    }
+
+    /// Applies the focus options over the `default` focus of the scene,
+    /// which --focus-distance turns off.
+    pub fn focus(&self, default: Option<Focus>) -> Option<Focus> {
+        match (self.focus, self.focus_distance) {
+            (Some(focus), _) => Some(focus),
+            (None, Some(_)) => None,
+            (None, None) => default,
+        }
+    }
+
+    /// Applies the camera options over the `default` view of the scene.
+    pub fn view(&self, default: View) -> Result<View, CliError> {
+        let view = View {
+            look_from: self.look_from.unwrap_or(default.look_from),
+            look_at: self.look_at.unwrap_or(default.look_at),
+            up: self.up.unwrap_or(default.up),
+            vertical_fov: self.vertical_fov.unwrap_or(default.vertical_fov),
+            aperture: self.aperture.unwrap_or(default.aperture),
+            focus_distance: self.focus_distance.unwrap_or(default.focus_distance),
+            tilt: self.tilt.unwrap_or(default.tilt),
+            shift: self.shift.unwrap_or(default.shift),
+            projection: self.projection.clone().unwrap_or(default.projection),
+        };
+
+        let direction = view.look_at - view.look_from;
+
+        if direction.sq_length() == 0.0 {
+            return Err(CliError::Inconsistent(
+                "camera position and look-at point must be different".to_string(),
+            ));
+        }
+
+        if direction.cross(view.up).sq_length() == 0.0 {
+            return Err(CliError::Inconsistent(
+                "camera up vector must not be parallel to the view direction".to_string(),
+            ));
+        }
+
+        Ok(view)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            image_width: None,
+            image_height: None,
+            samples_per_pixel: None,
+            max_depth: None,
+            output: None,
+            format: ImageFormat::PlainPpm,
+            display: DisplayTransform::default(),
+            seed: None,
+            threads: None,
+            tile_size: 16,
+            scene: String::from("random"),
+            scene_file: None,
+            background: None,
+            meshes: Vec::new(),
+            bvh: BvhOptions::default(),
+            export_scene: None,
+            frames: None,
+            fps: None,
+            shutter_angle: None,
+            shutter_curve: None,
+            readout: None,
+            projection: None,
+            stereo_layout: None,
+            interaxial: None,
+            convergence: None,
+            look_from: None,
+            look_at: None,
+            up: None,
+            vertical_fov: None,
+            aperture: None,
+            focus_distance: None,
+            focus: None,
+            tilt: None,
+            shift: None,
+            blades: None,
+            aperture_mask: None,
+            cat_eye: None,
+        }
+    }
+}
+
+pub enum Command {
+    Render(Box<Settings>),
+    Help,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum CliError {
+    UnknownFlag(String),
+    MissingValue(String),
+    InvalidValue {
+        flag: String,
+        value: String,
+        reason: String,
+    },
+    UnknownScene(String),
+    UnknownFormat(String),
+    Inconsistent(String),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFlag(flag) => write!(f, "unknown option '{}'", flag),
+            Self::MissingValue(flag) => write!(f, "option '{}' requires a value", flag),
+            Self::InvalidValue {
+                flag,
+                value,
+                reason,
+            } => write!(f, "invalid value '{}' for '{}': {}", value, flag, reason),
+            Self::UnknownScene(name) => write!(
+                f,
+                "unknown scene '{}' (available: {})",
+                name,
+                SCENE_NAMES.join(", ")
+            ),
+            Self::UnknownFormat(name) => write!(
+                f,
+                "unknown image format '{}' (available: {})",
+                name,
+                ImageFormat::NAMES.join(", ")
+            ),
+            Self::Inconsistent(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl Error for CliError {}
+
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+    let mut settings = Settings::default();
+    let mut scene_given = false;
+    let mut format = None;
+    let mut tone_map = String::from("clamp");
+    let mut white = 4.0;
+
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.find('=') {
+            Some(index) if arg.starts_with("--") => {
+                (arg[..index].to_string(), Some(arg[index + 1..].to_string()))
+            }
+            _ => (arg, None),
+        };
+
+        if flag == "-h" || flag == "--help" {
+            return Ok(Command::Help);
+        }
+
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::MissingValue(flag.clone()))
+        };
+
+        match flag.as_str() {
+            "-w" | "--width" => settings.image_width = Some(parse_dimension(&flag, &value()?)?),
+            "--height" => settings.image_height = Some(parse_dimension(&flag, &value()?)?),
+            "-s" | "--samples" => {
+                settings.samples_per_pixel = Some(parse_count(&flag, &value()?)?);
+            }
+            "-d" | "--depth" => settings.max_depth = Some(parse_count(&flag, &value()?)?),
+            "-o" | "--output" => {
+                let path = value()?;
+
+                settings.output = if path == "-" {
+                    None
+                } else {
+                    Some(PathBuf::from(path))
+                };
+            }
+            "-f" | "--format" => {
+                let name = value()?;
+
+                format = Some(ImageFormat::from_name(&name).ok_or(CliError::UnknownFormat(name))?);
+            }
+            "--exposure" => settings.display.exposure = parse_number(&flag, &value()?)?,
+            "--tonemap" => {
+                let name = value()?;
+
+                tone_map = check(
+                    &flag,
+                    name.clone(),
+                    ToneMap::NAMES.contains(&name.as_str()),
+                    || format!("expected one of {}", ToneMap::NAMES.join(", ")),
+                )?;
+            }
+            "--white" => {
+                let value = parse_number(&flag, &value()?)?;
+
+                white = check(&flag, value, value > 0.0, || {
+                    "must be greater than zero".to_string()
+                })?;
+            }
+            "--transfer" => {
+                let name = value()?;
+
+                settings.display.transfer =
+                    TransferFunction::from_name(&name).ok_or_else(|| CliError::InvalidValue {
+                        flag: flag.clone(),
+                        value: name,
+                        reason: "expected srgb, linear or gamma followed by a positive number"
+                            .to_string(),
+                    })?;
+            }
+            "--seed" => settings.seed = Some(parse_number(&flag, &value()?)?),
+            "-j" | "--threads" => {
+                let threads = parse_number(&flag, &value()?)?;
+
+                settings.threads = Some(check(&flag, threads, threads > 0, || {
+                    "must be at least 1".to_string()
+                })?);
+            }
+            "--tile-size" => settings.tile_size = parse_count(&flag, &value()?)?,
+            "--scene" => {
+                let name = value()?;
+
+                if !SCENE_NAMES.contains(&name.as_str()) {
+                    return Err(CliError::UnknownScene(name));
+                }
+
+                settings.scene = name;
+                scene_given = true;
+            }
+            "--scene-file" => settings.scene_file = Some(PathBuf::from(value()?)),
+            "--background" => {
+                let value = value()?;
+
+                settings.background = Some(if value == "sky" {
+                    Background::sky()
+                } else {
+                    Background::Solid(parse_vector(&flag, &value)?)
+                });
+            }
+            "--mesh" => {
+                let path = PathBuf::from(value()?);
+
+                if MeshFormat::from_path(&path).is_none() {
+                    return Err(CliError::InvalidValue {
+                        flag,
+                        value: path.display().to_string(),
+                        reason: "expected an .obj, .ply or .stl file".to_string(),
+                    });
+                }
+
+                settings.meshes.push(path);
+            }
+            "--bvh" => {
+                let name = value()?;
+
+                settings.bvh.split =
+                    SplitMethod::from_name(&name).ok_or_else(|| CliError::InvalidValue {
+                        flag,
+                        value: name,
+                        reason: format!("expected one of {}", SplitMethod::NAMES.join(", ")),
+                    })?;
+            }
+            "--leaf-size" => {
+                settings.bvh.max_leaf_size = parse_count(&flag, &value()?)? as usize;
+            }
+            "--export-scene" => settings.export_scene = Some(PathBuf::from(value()?)),
+            "--frames" => settings.frames = Some(parse_frames(&flag, &value()?)?),
+            "--fps" => {
+                let fps = parse_number(&flag, &value()?)?;
+
+                settings.fps = Some(check(&flag, fps, fps > 0.0, || {
+                    "must be greater than zero".to_string()
+                })?);
+            }
+            "--shutter-angle" => {
+                let angle = parse_number(&flag, &value()?)?;
+
+                settings.shutter_angle =
+                    Some(check(&flag, angle, angle > 0.0 && angle <= 360.0, || {
+                        "must be above 0 and up to 360 degrees".to_string()
+                    })?);
+            }
+            "--shutter" => settings.shutter_curve = Some(parse_shutter(&flag, &value()?)?),
+            "--rolling-shutter" => {
+                let readout = parse_number(&flag, &value()?)?;
+
+                settings.readout = Some(check(
+                    &flag,
+                    readout,
+                    (0.0..1.0).contains(&readout),
+                    || "must be a fraction from 0 to below 1".to_string(),
+                )?);
+            }
+            "--camera" => settings.projection = Some(parse_projection(&flag, &value()?)?),
+            "--stereo" => {
+                let name = value()?;
+
+                settings.stereo_layout =
+                    Some(
+                        StereoLayout::from_name(&name).ok_or_else(|| CliError::InvalidValue {
+                            flag,
+                            value: name,
+                            reason: format!("expected one of {}", StereoLayout::NAMES.join(", ")),
+                        })?,
+                    );
+            }
+            "--interaxial" => {
+                let distance = parse_number(&flag, &value()?)?;
+
+                settings.interaxial = Some(check(&flag, distance, distance >= 0.0, || {
+                    "must not be negative".to_string()
+                })?);
+            }
+            "--convergence" => {
+                let name = value()?;
+
+                settings.convergence =
+                    Some(
+                        Convergence::from_name(&name).ok_or_else(|| CliError::InvalidValue {
+                            flag,
+                            value: name,
+                            reason: format!("expected one of {}", Convergence::NAMES.join(", ")),
+                        })?,
+                    );
+            }
+            "--look-from" => settings.look_from = Some(parse_vector(&flag, &value()?)?),
+            "--look-at" => settings.look_at = Some(parse_vector(&flag, &value()?)?),
+            "--up" => settings.up = Some(parse_vector(&flag, &value()?)?),
+            "--fov" => {
+                let fov = parse_number(&flag, &value()?)?;
+
+                settings.vertical_fov = Some(check(&flag, fov, fov > 0.0 && fov < 180.0, || {
+                    "must be between 0 and 180 degrees".to_string()
+                })?);
+            }
+            "--aperture" => {
+                let aperture = parse_number(&flag, &value()?)?;
+
+                settings.aperture = Some(check(&flag, aperture, aperture >= 0.0, || {
+                    "must not be negative".to_string()
+                })?);
+            }
+            "--focus-distance" => {
+                let distance = parse_number(&flag, &value()?)?;
+
+                settings.focus_distance = Some(check(&flag, distance, distance > 0.0, || {
+                    "must be greater than zero".to_string()
+                })?);
+            }
+            "--focus-on" => {
+                let value = value()?;
+
+                settings.focus = Some(if value == "auto" {
+                    Focus::Auto
+                } else {
+                    Focus::Point(parse_vector(&flag, &value)?)
+                });
+            }
+            "--tilt" => {
+                let tilt = parse_number(&flag, &value()?)?;
+
+                settings.tilt = Some(check(&flag, tilt, tilt > -90.0 && tilt < 90.0, || {
+                    "must be between -90 and 90 degrees".to_string()
+                })?);
+            }
+            "--shift" => settings.shift = Some(parse_shift(&flag, &value()?)?),
+            "--blades" => settings.blades = Some(parse_blades(&flag, &value()?)?),
+            "--aperture-mask" => {
+                let path = PathBuf::from(value()?);
+
+                if !matches!(
+                    ImageFormat::from_path(&path),
+                    Some(ImageFormat::Png | ImageFormat::Ppm | ImageFormat::PlainPpm)
+                ) {
+                    return Err(CliError::InvalidValue {
+                        flag,
+                        value: path.display().to_string(),
+                        reason: "expected a .png or .ppm image".to_string(),
+                    });
+                }
+
+                settings.aperture_mask = Some(path);
+            }
+            "--cat-eye" => {
+                let amount = parse_number(&flag, &value()?)?;
+
+                settings.cat_eye =
+                    Some(check(&flag, amount, (0.0..=1.0).contains(&amount), || {
+                        "must be a fraction from 0 to 1".to_string()
+                    })?);
+            }
+            _ => return Err(CliError::UnknownFlag(flag)),
+        }
+    }
+
+    if settings.focus.is_some() && settings.focus_distance.is_some() {
+        return Err(CliError::Inconsistent(
+            "--focus-on and --focus-distance cannot be used together".to_string(),
+        ));
+    }
+
+    if settings.blades.is_some() && settings.aperture_mask.is_some() {
+        return Err(CliError::Inconsistent(
+            "--blades and --aperture-mask cannot be used together".to_string(),
+        ));
+    }
+
+    if scene_given && settings.scene_file.is_some() {
+        return Err(CliError::Inconsistent(
+            "--scene and --scene-file cannot be used together".to_string(),
+        ));
+    }
+
+    settings.display.tone_map =
+        ToneMap::from_name(&tone_map, white).expect("Tone mapping name was validated");
+
+    settings.format = match (format, &settings.output) {
+        (Some(format), _) => format,
+        (None, Some(path)) => ImageFormat::from_path(path).ok_or_else(|| {
+            CliError::Inconsistent(format!(
+                "cannot guess the image format of '{}', use --format",
+                path.display()
+            ))
+        })?,
+        // Frames and separate eyes are written to files
+        (None, None)
+            if settings.frames.is_some()
+                || settings.stereo_layout == Some(StereoLayout::Separate) =>
+        {
+            ImageFormat::Png
+        }
+        (None, None) => ImageFormat::PlainPpm,
+    };
+
+    Ok(Command::Render(Box::new(settings)))
+}
+
+fn check<T>(
+    flag: &str,
+    value: T,
+    condition: bool,
+    reason: impl FnOnce() -> String,
+) -> Result<T, CliError>
+where
+    T: ToString,
+{
+    if condition {
+        Ok(value)
+    } else {
+        Err(CliError::InvalidValue {
+            flag: flag.to_string(),
+            value: value.to_string(),
+            reason: reason(),
+        })
+    }
+}
+
+fn parse_number<T>(flag: &str, value: &str) -> Result<T, CliError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value
+        .trim()
+        .parse()
+        .map_err(|error: T::Err| CliError::InvalidValue {
+            flag: flag.to_string(),
+            value: value.to_string(),
+            reason: error.to_string(),
+        })
+}
+
+fn parse_count(flag: &str, value: &str) -> Result<u32, CliError> {
+    let count = parse_number(flag, value)?;
+
+    check(flag, count, count > 0, || "must be at least 1".to_string())
+}
+
+fn parse_dimension(flag: &str, value: &str) -> Result<u32, CliError> {
+    let pixels = parse_number(flag, value)?;
+
+    check(flag, pixels, pixels > 1, || {
+        "must be at least 2".to_string()
+    })
+}
+
+/// Parses a range of frames, `FIRST..LAST` with both ends included, or a
+/// single frame.
+fn parse_frames(flag: &str, value: &str) -> Result<(u32, u32), CliError> {
+    let (first, last) = match value.find("..") {
+        Some(index) => (
+            parse_number(flag, &value[..index])?,
+            parse_number(flag, &value[index + 2..])?,
+        ),
+        None => {
+            let frame = parse_number(flag, value)?;
+
+            (frame, frame)
+        }
+    };
+
+    if first <= last {
+        Ok((first, last))
+    } else {
+        Err(CliError::InvalidValue {
+            flag: flag.to_string(),
+            value: value.to_string(),
+            reason: "the last frame must not come before the first one".to_string(),
+        })
+    }
+}
+
+fn parse_shutter(flag: &str, value: &str) -> Result<ShutterCurve, CliError> {
+    let invalid = |reason: &str| CliError::InvalidValue {
+        flag: flag.to_string(),
+        value: value.to_string(),
+        reason: reason.to_string(),
+    };
+
+    let numbers = |list: &str| {
+        list.split(',')
+            .map(|number| parse_number::<f64>(flag, number))
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    let curve = match value {
+        "box" => ShutterCurve::Box,
+        "triangle" => ShutterCurve::Triangle,
+        "trapezoid" => ShutterCurve::Trapezoid {
+            open: 0.25,
+            close: 0.25,
+        },
+        _ => match value.strip_prefix("trapezoid:") {
+            Some(fractions) => match numbers(fractions)?.as_slice() {
+                [open, close] => ShutterCurve::Trapezoid {
+                    open: *open,
+                    close: *close,
+                },
+                _ => return Err(invalid("expected the opening and closing fractions")),
+            },
+            None if value.contains(',') => ShutterCurve::Tabulated(numbers(value)?),
+            None => {
+                return Err(invalid(
+                    "expected box, triangle, trapezoid or comma-separated openings",
+                ))
+            }
+        },
+    };
+
+    curve.validate().map_err(invalid)?;
+
+    Ok(curve)
+}
+
+/// Parses a number of blades, optionally followed by their rotation in
+/// degrees.
+fn parse_blades(flag: &str, value: &str) -> Result<(u32, f64), CliError> {
+    let (blades, rotation) = match value.find(',') {
+        Some(index) => (
+            parse_number(flag, &value[..index])?,
+            parse_number(flag, &value[index + 1..])?,
+        ),
+        None => (parse_number(flag, value)?, 0.0),
+    };
+
+    check(flag, blades, blades >= 3, || {
+        "expected at least 3 blades".to_string()
+    })?;
+
+    Ok((blades, rotation))
+}
+
+/// Parses a projection name, followed by its parameters after a colon.
+fn parse_projection(flag: &str, value: &str) -> Result<Projection, CliError> {
+    let invalid = |reason: String| CliError::InvalidValue {
+        flag: flag.to_string(),
+        value: value.to_string(),
+        reason,
+    };
+
+    let (name, parameters) = match value.find(':') {
+        Some(index) => (&value[..index], Some(&value[index + 1..])),
+        None => (value, None),
+    };
+
+    let projection = Projection::from_name(name)
+        .ok_or_else(|| invalid(format!("expected one of {}", Projection::NAMES.join(", "))))?;
+
+    let parameters = match parameters {
+        Some(parameters) => parameters,
+        None => return Ok(projection),
+    };
+
+    match projection {
+        Projection::Orthographic { .. } => {
+            let height = parse_number(flag, parameters)?;
+
+            check(flag, height, height > 0.0, || {
+                "the height must be greater than zero".to_string()
+            })?;
+
+            Ok(Projection::Orthographic { height })
+        }
+        Projection::Fisheye { fov, .. } => {
+            let (mapping, fov) = match parameters.find(',') {
+                Some(index) => (
+                    &parameters[..index],
+                    parse_number(flag, &parameters[index + 1..])?,
+                ),
+                None => (parameters, fov),
+            };
+
+            let mapping = FisheyeMapping::from_name(mapping).ok_or_else(|| {
+                invalid(format!(
+                    "expected a mapping among {}",
+                    FisheyeMapping::NAMES.join(", ")
+                ))
+            })?;
+
+            check(flag, fov, fov > 0.0 && fov <= 360.0, || {
+                "the field of view must be above 0 and up to 360 degrees".to_string()
+            })?;
+
+            Ok(Projection::Fisheye { mapping, fov })
+        }
+        Projection::Cubemap { .. } => {
+            let face = CubeFace::from_name(parameters).ok_or_else(|| {
+                invalid(format!(
+                    "expected a face among {}",
+                    CubeFace::NAMES.join(", ")
+                ))
+            })?;
+
+            Ok(Projection::Cubemap { face: Some(face) })
+        }
+        Projection::Realistic(lens) => {
+            let film_diagonal = parse_number(flag, parameters)?;
+
+            check(flag, film_diagonal, film_diagonal > 0.0, || {
+                "the film diagonal must be greater than zero".to_string()
+            })?;
+
+            Ok(Projection::Realistic(RealisticLens {
+                film_diagonal,
+                ..lens
+            }))
+        }
+        _ => Err(invalid(format!("{} takes no parameters", name))),
+    }
+}
+
+fn parse_shift(flag: &str, value: &str) -> Result<Vec2, CliError> {
+    let components = value
+        .split(',')
+        .map(|component| parse_number::<f64>(flag, component))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match components.as_slice() {
+        [x, y] => Ok(Vec2(*x, *y)),
+        _ => Err(CliError::InvalidValue {
+            flag: flag.to_string(),
+            value: value.to_string(),
+            reason: "expected two comma-separated numbers".to_string(),
+        }),
+    }
+}
+
+fn parse_vector(flag: &str, value: &str) -> Result<Vec3, CliError> {
+    let components = value
+        .split(',')
+        .map(|component| parse_number::<f64>(flag, component))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match components.as_slice() {
+        [x, y, z] => Ok(Vec3(*x, *y, *z)),
+        _ => Err(CliError::InvalidValue {
+            flag: flag.to_string(),
+            value: value.to_string(),
+            reason: "expected three comma-separated numbers".to_string(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_settings(args: &[&str]) -> Result<Settings, CliError> {
+        match parse(args.iter().map(|arg| arg.to_string()))? {
+            Command::Render(settings) => Ok(*settings),
+            Command::Help => panic!("Unexpected help command"),
+        }
+    }
+
+    #[test]
+    fn must_use_defaults_without_arguments() {
+        let settings = parse_settings(&[]).unwrap();
+
+        assert_eq!(
+            ImageSettings::default(),
+            settings.image(ImageSettings::default())
+        );
+        assert!(settings.output.is_none());
+        assert_eq!("random", settings.scene);
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_parse_flags_and_inline_values() {
+        let settings = parse_settings(&[
+            "-w",
+            "400",
+            "--height=300",
+            "--samples",
+            "10",
+            "--output",
+            "image.png",
+            "--look-from=1,2.5,-3",
+            "--seed",
+            "42",
+        ])
+        .unwrap();
+
+        assert_eq!(Some(400), settings.image_width);
+        assert_eq!(Some(300), settings.image_height);
+        assert_eq!(Some(10), settings.samples_per_pixel);
+        assert_eq!(Some(PathBuf::from("image.png")), settings.output);
+        assert_eq!(ImageFormat::Png, settings.format);
+        assert_eq!(Some(Point3(1.0, 2.5, -3.0)), settings.look_from);
+        assert_eq!(Some(42), settings.seed);
+    }
+
+    #[test]
+    fn must_derive_height_from_width() {
+        let settings = parse_settings(&["--width", "400"]).unwrap();
+
+        assert_eq!(225, settings.image(ImageSettings::default()).height);
+
+        let square = ImageSettings {
+            width: 100,
+            height: 100,
+            ..ImageSettings::default()
+        };
+
+        assert_eq!(400, settings.image(square).height);
+    }
+
+    #[test]
+    fn must_reject_two_scene_sources() {
+        assert!(matches!(
+            parse_settings(&["--scene", "random", "--scene-file", "scene.json"]),
+            Err(CliError::Inconsistent(_))
+        ));
+    }
+
+    #[test]
+    fn must_reject_invalid_values() {
+        assert!(matches!(
+            parse_settings(&["--samples", "0"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_settings(&["--width", "abc"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_settings(&["--up", "1,2"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_settings(&["--fov", "180"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn must_report_missing_value() {
+        assert_eq!(
+            Err(CliError::MissingValue("--depth".to_string())),
+            parse_settings(&["--depth"]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn must_reject_unknown_flags_and_scenes() {
+        assert_eq!(
+            Err(CliError::UnknownFlag("--foo".to_string())),
+            parse_settings(&["--foo"]).map(|_| ())
+        );
+        assert_eq!(
+            Err(CliError::UnknownScene("cornell".to_string())),
+            parse_settings(&["--scene", "cornell"]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn must_pick_output_format() {
+        assert_eq!(ImageFormat::PlainPpm, parse_settings(&[]).unwrap().format);
+        assert_eq!(
+            ImageFormat::Ppm,
+            parse_settings(&["-o", "a.ppm"]).unwrap().format
+        );
+        assert_eq!(
+            ImageFormat::PlainPpm,
+            parse_settings(&["-o", "a.png", "-f", "ppm-ascii"])
+                .unwrap()
+                .format
+        );
+        assert!(matches!(
+            parse_settings(&["-o", "a.jpg"]),
+            Err(CliError::Inconsistent(_))
+        ));
+        assert_eq!(
+            Err(CliError::UnknownFormat("gif".to_string())),
+            parse_settings(&["-f", "gif"]).map(|_| ())
+        );
+    }
+
+    #[test]
+    fn must_parse_display_transform() {
+        let settings = parse_settings(&[
+            "--tonemap",
+            "reinhard-extended",
+            "--white=2",
+            "--exposure",
+            "-1.5",
+            "--transfer",
+            "gamma2",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            DisplayTransform {
+                exposure: -1.5,
+                tone_map: ToneMap::ExtendedReinhard { white: 2.0 },
+                transfer: TransferFunction::Gamma(2.0),
+            },
+            settings.display
+        );
+
+        assert!(matches!(
+            parse_settings(&["--tonemap", "filmic"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_settings(&["--transfer", "rec709"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn must_override_scene_view() {
+        let settings = parse_settings(&["--fov", "40", "--look-at", "1,1,1"]).unwrap();
+
+        let view = settings.view(View::default()).unwrap();
+
+        assert_eq!(
+            View {
+                vertical_fov: 40.0,
+                look_at: Point3(1.0, 1.0, 1.0),
+                ..View::default()
+            },
+            view
+        );
+    }
+
+    #[test]
+    fn must_parse_background() {
+        assert_eq!(
+            Some(Background::Solid(Vec3(0.0, 0.0, 0.0))),
+            parse_settings(&["--background", "0,0,0"])
+                .unwrap()
+                .background
+        );
+        assert_eq!(
+            Some(Background::sky()),
+            parse_settings(&["--background=sky"]).unwrap().background
+        );
+    }
+
+    #[test]
+    fn must_collect_meshes() {
+        let settings = parse_settings(&["--mesh", "a.obj", "--mesh=b/c.PLY"]).unwrap();
+
+        assert_eq!(
+            vec![PathBuf::from("a.obj"), PathBuf::from("b/c.PLY")],
+            settings.meshes
+        );
+
+        assert!(matches!(
+            parse_settings(&["--mesh", "model.fbx"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn must_parse_bvh_options() {
+        let settings = parse_settings(&["--bvh", "random", "--leaf-size", "1"]).unwrap();
+
+        assert_eq!(
+            BvhOptions {
+                split: SplitMethod::Random,
+                max_leaf_size: 1,
+            },
+            settings.bvh
+        );
+
+        assert!(matches!(
+            parse_settings(&["--bvh", "octree"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_parse_animation_options() {
+        let settings = parse_settings(&["--frames", "1..48", "--shutter-angle=90"]).unwrap();
+
+        assert_eq!(Some((1, 48)), settings.frames);
+        assert_eq!(ImageFormat::Png, settings.format);
+        assert_eq!(
+            PathBuf::from("frame_0007.png"),
+            settings.image_path(Some(7), None)
+        );
+
+        let animation = settings.animation(None).unwrap().unwrap();
+
+        assert_eq!(24.0, animation.fps);
+        assert_eq!(90.0, animation.shutter_angle);
+
+        let interval = animation.frame_interval(12);
+
+        assert_eq!(0.5, interval.start());
+        assert!((interval.end() - (0.5 + 0.25 / 24.0)).abs() < 1e-12);
+
+        // Options of the command line override the animation of the scene
+        let settings = parse_settings(&["--fps", "30", "-o", "out/turntable.exr"]).unwrap();
+        let scene = Animation {
+            first_frame: 0,
+            last_frame: 9,
+            ..Animation::default()
+        };
+
+        assert_eq!(
+            Some(Animation { fps: 30.0, ..scene }),
+            settings.animation(Some(scene)).unwrap()
+        );
+        assert_eq!(
+            PathBuf::from("out/turntable_0120.exr"),
+            settings.image_path(Some(120), None)
+        );
+        assert!(matches!(
+            settings.animation(None),
+            Err(CliError::Inconsistent(_))
+        ));
+
+        assert_eq!(
+            Some((5, 5)),
+            parse_settings(&["--frames", "5"]).unwrap().frames
+        );
+        assert!(matches!(
+            parse_settings(&["--frames", "9..2"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            parse_settings(&["--shutter-angle", "0"]),
+            Err(CliError::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_parse_shutter() {
+        let settings = parse_settings(&["--shutter", "trapezoid:0.1,0.3"]).unwrap();
+
+        assert_eq!(
+            Some(ShutterCurve::Trapezoid {
+                open: 0.1,
+                close: 0.3
+            }),
+            settings.shutter_curve
+        );
+
+        // The readout of the scene is kept
+        let scene = Shutter::default().with_readout(0.2);
+        let shutter = settings.shutter(scene);
+
+        assert_eq!(0.2, shutter.readout());
+        assert_eq!(settings.shutter_curve.as_ref(), Some(shutter.curve()));
+
+        let settings = parse_settings(&["--shutter", "0,1,0.5", "--rolling-shutter=0.5"]).unwrap();
+        let shutter = settings.shutter(Shutter::default());
+
+        assert_eq!(
+            &ShutterCurve::Tabulated(vec![0.0, 1.0, 0.5]),
+            shutter.curve()
+        );
+        assert_eq!(0.5, shutter.readout());
+
+        for args in &[
+            ["--shutter", "gaussian"],
+            ["--shutter", "trapezoid:0.7,0.7"],
+            ["--shutter", "0,0,0"],
+            ["--rolling-shutter", "1"],
+        ] {
+            assert!(matches!(
+                parse_settings(args),
+                Err(CliError::InvalidValue { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn must_parse_projections() {
+        let projection = |value: &str| {
+            parse_settings(&["--camera", value])
+                .unwrap()
+                .view(View::default())
+                .unwrap()
+                .projection
+        };
+
+        assert_eq!(Projection::Perspective, projection("perspective"));
+        assert_eq!(
+            Projection::Orthographic { height: 4.5 },
+            projection("orthographic:4.5")
+        );
+        assert_eq!(
+            Projection::Fisheye {
+                mapping: FisheyeMapping::Equisolid,
+                fov: 220.0
+            },
+            projection("fisheye:equisolid,220")
+        );
+        assert_eq!(
+            Projection::Cubemap {
+                face: Some(CubeFace::NegativeY)
+            },
+            projection("cubemap:ny")
+        );
+        assert_eq!(
+            Projection::Realistic(RealisticLens {
+                film_diagonal: 28.0,
+                ..RealisticLens::default()
+            }),
+            projection("realistic:28")
+        );
+
+        // Panoramas get their usual aspect ratio unless a height is given
+        let settings = parse_settings(&["--camera", "equirectangular", "-w", "400"]).unwrap();
+        let image = settings.image(ImageSettings::default());
+
+        assert_eq!((400, 200), (image.width, image.height));
+
+        let settings = parse_settings(&["--camera", "cubemap", "--height", "100"]).unwrap();
+        let image = settings.image(ImageSettings::default());
+
+        assert_eq!(100, image.height);
+
+        for value in &[
+            "pinhole",
+            "orthographic:0",
+            "fisheye:stereographic",
+            "fisheye:equidistant,400",
+            "cubemap:top",
+            "equirectangular:2",
+            "realistic:0",
+        ] {
+            assert!(matches!(
+                parse_settings(&["--camera", value]),
+                Err(CliError::InvalidValue { .. })
+            ));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_parse_stereo_options() {
+        assert_eq!(
+            None,
+            parse_settings(&[])
+                .unwrap()
+                .stereo(None, &Projection::Perspective)
+                .unwrap()
+        );
+
+        let settings = parse_settings(&["--stereo", "separate", "--interaxial", "0.1"]).unwrap();
+        let (rig, layout) = settings
+            .stereo(None, &Projection::Equirectangular)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(StereoLayout::Separate, layout);
+        assert_eq!(0.1, rig.interaxial);
+        assert_eq!(Convergence::Omnidirectional, rig.convergence);
+
+        // Separate eyes go to files named after the output
+        assert_eq!(ImageFormat::Png, settings.format);
+        assert_eq!(
+            PathBuf::from("frame_right.png"),
+            settings.image_path(None, Some(Eye::Right))
+        );
+
+        let settings = parse_settings(&[
+            "--convergence",
+            "toed-in",
+            "-o",
+            "out/pair.exr",
+            "--frames",
+            "3",
+        ])
+        .unwrap();
+        let scene = StereoRig::for_projection(&Projection::Perspective);
+
+        assert_eq!(
+            Some((
+                StereoRig {
+                    convergence: Convergence::ToedIn,
+                    ..scene
+                },
+                StereoLayout::SideBySide
+            )),
+            settings
+                .stereo(Some(scene), &Projection::Perspective)
+                .unwrap()
+        );
+        assert_eq!(
+            PathBuf::from("out/pair_left_0003.exr"),
+            settings.image_path(Some(3), Some(Eye::Left))
+        );
+
+        // Omni-directional stereo only works with panoramas
+        let settings = parse_settings(&["--convergence", "ods"]).unwrap();
+
+        assert!(matches!(
+            settings.stereo(None, &Projection::Perspective),
+            Err(CliError::Inconsistent(_))
+        ));
+
+        for args in &[
+            ["--stereo", "anaglyph"],
+            ["--interaxial", "-1"],
+            ["--convergence", "crossed"],
+        ] {
+            assert!(matches!(
+                parse_settings(args),
+                Err(CliError::InvalidValue { .. })
+            ));
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_parse_bokeh_options() {
+        let settings = parse_settings(&["--blades", "6,15", "--cat-eye", "0.3"]).unwrap();
+        let scene = Bokeh::default().with_cat_eye(0.1);
+        let bokeh = settings.bokeh(scene.clone(), None);
+
+        assert_eq!(
+            &ApertureShape::Polygon {
+                blades: 6,
+                rotation: 15.0
+            },
+            bokeh.shape()
+        );
+        assert_eq!(0.3, bokeh.cat_eye());
+
+        // The scene keeps its bokeh without options
+        assert_eq!(
+            scene,
+            parse_settings(&[]).unwrap().bokeh(scene.clone(), None)
+        );
+
+        assert!(matches!(
+            parse_settings(&["--blades", "5", "--aperture-mask", "heart.png"]),
+            Err(CliError::Inconsistent(_))
+        ));
+
+        for args in &[
+            ["--blades", "2"],
+            ["--blades", "6,wide"],
+            ["--aperture-mask", "heart.svg"],
+            ["--cat-eye", "1.5"],
+        ] {
+            assert!(matches!(
+                parse_settings(args),
+                Err(CliError::InvalidValue { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn must_parse_focus_options() {
+        let scene = Some(Focus::Auto);
+
+        let settings = parse_settings(&["--focus-on", "1,2,3"]).unwrap();
+        assert_eq!(
+            Some(Focus::Point(Point3(1.0, 2.0, 3.0))),
+            settings.focus(scene)
+        );
+
+        // A focus distance given by hand turns off the focus of the scene
+        let settings = parse_settings(&["--focus-distance", "4"]).unwrap();
+        assert_eq!(None, settings.focus(scene));
+
+        let settings = parse_settings(&["--focus-on", "auto"]).unwrap();
+        assert_eq!(Some(Focus::Auto), settings.focus(None));
+
+        let settings = parse_settings(&["--tilt", "-12", "--shift", "0,0.2"]).unwrap();
+        let view = settings.view(View::default()).unwrap();
+        assert_eq!((-12.0, Vec2(0.0, 0.2)), (view.tilt, view.shift));
+
+        assert!(matches!(
+            parse_settings(&["--focus-on", "auto", "--focus-distance", "4"]),
+            Err(CliError::Inconsistent(_))
+        ));
+
+        for args in &[
+            ["--focus-on", "center"],
+            ["--tilt", "90"],
+            ["--shift", "0.2"],
+        ] {
+            assert!(matches!(
+                parse_settings(args),
+                Err(CliError::InvalidValue { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn must_reject_degenerate_camera() {
+        let settings = parse_settings(&["--look-from", "0,0,0"]).unwrap();
+
+        assert!(matches!(
+            settings.view(View::default()),
+            Err(CliError::Inconsistent(_))
+        ));
+
+        let settings = parse_settings(&["--look-from", "0,5,0"]).unwrap();
+
+        assert!(matches!(
+            settings.view(View::default()),
+            Err(CliError::Inconsistent(_))
+        ));
+    }
+}
@@ -0,0 +1,267 @@
+use std::ops;
+
+use super::vec3::{Point3, Vec3};
+use super::vector::Vector;
+
+type Scalar = f64;
+
+/// 4x4 matrix acting on homogeneous coordinates, stored by rows.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Matrix4(pub [[Scalar; 4]; 4]);
+
+#[allow(dead_code)]
+impl Matrix4 {
+    pub const fn identity() -> Self {
+        Self([
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub const fn translation(offset: Vec3) -> Self {
+        Self([
+            [1.0, 0.0, 0.0, offset.0],
+            [0.0, 1.0, 0.0, offset.1],
+            [0.0, 0.0, 1.0, offset.2],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub const fn scaling(factors: Vec3) -> Self {
+        Self([
+            [factors.0, 0.0, 0.0, 0.0],
+            [0.0, factors.1, 0.0, 0.0],
+            [0.0, 0.0, factors.2, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Counterclockwise rotation of `degrees` around `axis`, looking from
+    /// the tip of the axis towards the origin.
+    pub fn rotation(axis: Vec3, degrees: Scalar) -> Self {
+        let Vec3(x, y, z) = axis.normalized();
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        let t = 1.0 - cos;
+
+        Self([
+            [
+                t * x * x + cos,
+                t * x * y - sin * z,
+                t * x * z + sin * y,
+                0.0,
+            ],
+            [
+                t * x * y + sin * z,
+                t * y * y + cos,
+                t * y * z - sin * x,
+                0.0,
+            ],
+            [
+                t * x * z - sin * y,
+                t * y * z + sin * x,
+                t * z * z + cos,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Places an object at `from`, facing `to`: its -z axis points towards
+    /// `to` and its y axis is as close to `up` as possible. This is the
+    /// placement of a camera, the inverse of a view matrix.
+    pub fn look_at(from: Point3, to: Point3, up: Vec3) -> Self {
+        let w = (from - to).normalized();
+        let u = up.cross(w).normalized();
+        let v = w.cross(u);
+
+        Self([
+            [u.x(), v.x(), w.x(), from.x()],
+            [u.y(), v.y(), w.y(), from.y()],
+            [u.z(), v.z(), w.z(), from.z()],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut result = [[0.0; 4]; 4];
+
+        for (row, values) in self.0.iter().enumerate() {
+            for (column, value) in values.iter().enumerate() {
+                result[column][row] = *value;
+            }
+        }
+
+        Self(result)
+    }
+
+    /// Inverse by Gauss-Jordan elimination, or `None` for singular matrices.
+    pub fn inverse(&self) -> Option<Self> {
+        let mut m = self.0;
+        let mut inverse = Self::identity().0;
+
+        for column in 0..4 {
+            // Partial pivoting, for stability
+            let pivot = (column..4)
+                .max_by(|&a, &b| m[a][column].abs().total_cmp(&m[b][column].abs()))
+                .unwrap_or(column);
+
+            let pivot_value = m[pivot][column];
+
+            if pivot_value == 0.0 || !pivot_value.is_finite() {
+                return None;
+            }
+
+            m.swap(column, pivot);
+            inverse.swap(column, pivot);
+
+            let scale = 1.0 / m[column][column];
+
+            for k in 0..4 {
+                m[column][k] *= scale;
+                inverse[column][k] *= scale;
+            }
+
+            for row in 0..4 {
+                if row != column {
+                    let factor = m[row][column];
+
+                    for k in 0..4 {
+                        m[row][k] -= factor * m[column][k];
+                        inverse[row][k] -= factor * inverse[column][k];
+                    }
+                }
+            }
+        }
+
+        Some(Self(inverse))
+    }
+
+    /// Transforms a point, including the projective division.
+    pub fn point(&self, point: Point3) -> Point3 {
+        let m = &self.0;
+        let row =
+            |r: usize| m[r][0] * point.x() + m[r][1] * point.y() + m[r][2] * point.z() + m[r][3];
+
+        let w = row(3);
+        let result = Point3(row(0), row(1), row(2));
+
+        if w == 1.0 {
+            result
+        } else {
+            result / w
+        }
+    }
+
+    /// Transforms a direction, which ignores the translation.
+    pub fn vector(&self, vector: Vec3) -> Vec3 {
+        let m = &self.0;
+        let row = |r: usize| m[r][0] * vector.x() + m[r][1] * vector.y() + m[r][2] * vector.z();
+
+        Vec3(row(0), row(1), row(2))
+    }
+}
+
+impl Default for Matrix4 {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl ops::Mul for Matrix4 {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        let mut result = [[0.0; 4]; 4];
+
+        for (row, values) in result.iter_mut().enumerate() {
+            for (column, value) in values.iter_mut().enumerate() {
+                *value = (0..4).map(|k| self.0[row][k] * rhs.0[k][column]).sum();
+            }
+        }
+
+        Self(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::EPSILON;
+
+    fn assert_matrix_close(expected: &Matrix4, actual: &Matrix4) {
+        for row in 0..4 {
+            for column in 0..4 {
+                assert!(
+                    (expected.0[row][column] - actual.0[row][column]).abs() < EPSILON,
+                    "expected {:?}, found {:?}",
+                    expected,
+                    actual
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn must_invert() {
+        let matrix = Matrix4::translation(Vec3(1.0, -2.0, 3.0))
+            * Matrix4::rotation(Vec3(1.0, 2.0, 3.0), 37.0)
+            * Matrix4::scaling(Vec3(2.0, 0.5, -3.0));
+
+        let inverse = matrix.inverse().unwrap();
+
+        assert_matrix_close(&Matrix4::identity(), &(matrix * inverse));
+        assert_matrix_close(&Matrix4::identity(), &(inverse * matrix));
+
+        // A general matrix, needing row swaps
+        let matrix = Matrix4([
+            [0.0, 2.0, 1.0, 4.0],
+            [1.0, 0.0, 3.0, 0.0],
+            [2.0, 1.0, 0.0, 1.0],
+            [0.0, 1.0, 1.0, 0.0],
+        ]);
+
+        assert_matrix_close(&Matrix4::identity(), &(matrix * matrix.inverse().unwrap()));
+    }
+
+    #[test]
+    fn must_reject_singular_matrices() {
+        assert_eq!(None, Matrix4::scaling(Vec3(1.0, 0.0, 1.0)).inverse());
+
+        let mut matrix = Matrix4::identity();
+        matrix.0[2] = matrix.0[1];
+
+        assert_eq!(None, matrix.inverse());
+    }
+
+    #[test]
+    fn must_transpose() {
+        let matrix = Matrix4::translation(Vec3(1.0, 2.0, 3.0));
+        let transposed = matrix.transpose();
+
+        assert_eq!([1.0, 2.0, 3.0, 1.0], transposed.0[3]);
+        assert_eq!(matrix, transposed.transpose());
+    }
+
+    #[test]
+    fn must_look_at_target() {
+        let from = Point3(1.0, 2.0, 3.0);
+        let to = Point3(4.0, 2.0, -1.0);
+        let matrix = Matrix4::look_at(from, to, Vec3(0.0, 1.0, 0.0));
+
+        assert!((matrix.point(Point3(0.0, 0.0, 0.0)) - from).length() < EPSILON);
+
+        // -z points towards the target, which is 5 away
+        assert!((matrix.point(Point3(0.0, 0.0, -5.0)) - to).length() < EPSILON);
+        assert!((matrix.vector(Vec3(0.0, 1.0, 0.0)) - Vec3(0.0, 1.0, 0.0)).length() < EPSILON);
+    }
+
+    #[test]
+    fn must_divide_projective_points() {
+        let mut matrix = Matrix4::identity();
+        matrix.0[3] = [0.0, 0.0, 1.0, 0.0];
+
+        assert_eq!(Point3(1.0, 2.0, 1.0), matrix.point(Point3(2.0, 4.0, 2.0)));
+    }
+}
@@ -1,11 +1,17 @@
+mod matrix4;
+mod quaternion;
 mod ray;
 mod ray3;
+mod transform;
 mod vec2;
 mod vec3;
 mod vector;
 
+pub use matrix4::*;
+pub use quaternion::*;
 pub use ray::*;
 pub use ray3::*;
+pub use transform::*;
 pub use vec2::*;
 pub use vec3::*;
 pub use vector::*;
@@ -0,0 +1,216 @@
+use super::matrix4::Matrix4;
+use super::vec3::Vec3;
+use super::vector::Vector;
+
+type Scalar = f64;
+
+/// Unit quaternion representing a rotation, interpolated with [`slerp`].
+///
+/// [`slerp`]: Quaternion::slerp
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Quaternion {
+    pub w: Scalar,
+    pub x: Scalar,
+    pub y: Scalar,
+    pub z: Scalar,
+}
+
+#[allow(dead_code)]
+impl Quaternion {
+    pub const fn identity() -> Self {
+        Self {
+            w: 1.0,
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        }
+    }
+
+    /// Rotation of `degrees` around `axis`, in the same direction as
+    /// [`Matrix4::rotation`].
+    pub fn from_axis_angle(axis: Vec3, degrees: Scalar) -> Self {
+        let Vec3(x, y, z) = axis.normalized();
+        let (sin, cos) = (degrees.to_radians() / 2.0).sin_cos();
+
+        Self {
+            w: cos,
+            x: x * sin,
+            y: y * sin,
+            z: z * sin,
+        }
+    }
+
+    /// Axis and angle in degrees of the rotation, as given to
+    /// [`Quaternion::from_axis_angle`]. No rotation turns around the x axis.
+    pub fn to_axis_angle(self) -> (Vec3, Scalar) {
+        let Self { w, x, y, z } = self.normalized();
+        let sin = (x * x + y * y + z * z).sqrt();
+
+        if sin == 0.0 {
+            return (Vec3(1.0, 0.0, 0.0), 0.0);
+        }
+
+        (
+            Vec3(x / sin, y / sin, z / sin),
+            2.0 * sin.atan2(w).to_degrees(),
+        )
+    }
+
+    pub fn dot(&self, other: &Self) -> Scalar {
+        self.w * other.w + self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    pub fn normalized(&self) -> Self {
+        self.scaled(1.0 / self.dot(self).sqrt())
+    }
+
+    fn scaled(&self, factor: Scalar) -> Self {
+        Self {
+            w: self.w * factor,
+            x: self.x * factor,
+            y: self.y * factor,
+            z: self.z * factor,
+        }
+    }
+
+    fn plus(&self, other: &Self) -> Self {
+        Self {
+            w: self.w + other.w,
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+
+    /// Spherical interpolation from `self` at `t = 0` to `other` at `t = 1`,
+    /// turning at a constant speed along the shortest path.
+    pub fn slerp(&self, other: &Self, t: Scalar) -> Self {
+        let mut cos = self.dot(other);
+
+        // q and -q are the same rotation: go the short way
+        let other = if cos < 0.0 {
+            cos = -cos;
+            other.scaled(-1.0)
+        } else {
+            *other
+        };
+
+        // Nearly equal rotations would divide by a vanishing sine
+        if cos > 0.9995 {
+            return self.scaled(1.0 - t).plus(&other.scaled(t)).normalized();
+        }
+
+        let angle = cos.acos();
+        let sin = angle.sin();
+
+        self.scaled(((1.0 - t) * angle).sin() / sin)
+            .plus(&other.scaled((t * angle).sin() / sin))
+    }
+
+    /// Angle in degrees between the two rotations.
+    pub fn angle_to(&self, other: &Self) -> Scalar {
+        let cos = self.dot(other).abs().min(1.0);
+
+        2.0 * cos.acos().to_degrees()
+    }
+
+    pub fn to_matrix(self) -> Matrix4 {
+        let Self { w, x, y, z } = self;
+
+        Matrix4([
+            [
+                1.0 - 2.0 * (y * y + z * z),
+                2.0 * (x * y - w * z),
+                2.0 * (x * z + w * y),
+                0.0,
+            ],
+            [
+                2.0 * (x * y + w * z),
+                1.0 - 2.0 * (x * x + z * z),
+                2.0 * (y * z - w * x),
+                0.0,
+            ],
+            [
+                2.0 * (x * z - w * y),
+                2.0 * (y * z + w * x),
+                1.0 - 2.0 * (x * x + y * y),
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{assert_close, EPSILON};
+
+    #[test]
+    fn must_match_rotation_matrices() {
+        let axis = Vec3(1.0, -2.0, 0.5);
+        let quaternion = Quaternion::from_axis_angle(axis, 73.0).to_matrix();
+        let matrix = Matrix4::rotation(axis, 73.0);
+
+        for vector in &[
+            Vec3(1.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+            Vec3(0.3, 2.0, -1.0),
+        ] {
+            assert_close(matrix.vector(*vector), quaternion.vector(*vector));
+        }
+    }
+
+    #[test]
+    fn must_give_back_axis_and_angle() {
+        let axis = Vec3(1.0, -2.0, 0.5);
+        let (back_axis, angle) = Quaternion::from_axis_angle(axis, 73.0).to_axis_angle();
+
+        assert_close(axis.normalized(), back_axis);
+        assert!((73.0 - angle).abs() < EPSILON);
+
+        assert_eq!(
+            (Vec3(1.0, 0.0, 0.0), 0.0),
+            Quaternion::identity().to_axis_angle()
+        );
+    }
+
+    #[test]
+    fn must_slerp_at_constant_speed() {
+        let axis = Vec3(0.0, 0.0, 1.0);
+        let start = Quaternion::from_axis_angle(axis, 0.0);
+        let end = Quaternion::from_axis_angle(axis, 120.0);
+
+        for &t in &[0.0, 0.25, 0.5, 1.0] {
+            let rotation = start.slerp(&end, t).to_matrix();
+            let (sin, cos): (f64, f64) = (120.0 * t).to_radians().sin_cos();
+
+            assert_close(Vec3(cos, sin, 0.0), rotation.vector(Vec3(1.0, 0.0, 0.0)));
+        }
+
+        assert!((start.angle_to(&end) - 120.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn must_slerp_the_short_way() {
+        let axis = Vec3(0.0, 1.0, 0.0);
+        let start = Quaternion::from_axis_angle(axis, 170.0);
+
+        // The same rotation as 190 degrees, 20 degrees further along
+        let end = Quaternion::from_axis_angle(axis, -170.0);
+
+        let middle = start.slerp(&end, 0.5).to_matrix();
+        let expected = Matrix4::rotation(axis, 180.0);
+
+        assert_close(
+            expected.vector(Vec3(1.0, 0.0, 0.0)),
+            middle.vector(Vec3(1.0, 0.0, 0.0)),
+        );
+    }
+}
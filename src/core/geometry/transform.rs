@@ -0,0 +1,203 @@
+use super::matrix4::Matrix4;
+use super::quaternion::Quaternion;
+use super::vec3::{Point3, Vec3};
+
+/// Invertible transform, kept with its inverse to bring rays into the space
+/// of transformed objects.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    matrix: Matrix4,
+    inverse: Matrix4,
+}
+
+#[allow(dead_code)]
+impl Transform {
+    /// Transform of `matrix`, or `None` when it cannot be inverted.
+    pub fn new(matrix: Matrix4) -> Option<Self> {
+        Some(Self {
+            matrix,
+            inverse: matrix.inverse()?,
+        })
+    }
+
+    pub const fn identity() -> Self {
+        Self {
+            matrix: Matrix4::identity(),
+            inverse: Matrix4::identity(),
+        }
+    }
+
+    pub const fn translation(offset: Vec3) -> Self {
+        Self {
+            matrix: Matrix4::translation(offset),
+            inverse: Matrix4::translation(Vec3(-offset.0, -offset.1, -offset.2)),
+        }
+    }
+
+    /// Scales each axis; the factors must not be zero.
+    pub fn scaling(factors: Vec3) -> Self {
+        Self {
+            matrix: Matrix4::scaling(factors),
+            inverse: Matrix4::scaling(Vec3(1.0 / factors.0, 1.0 / factors.1, 1.0 / factors.2)),
+        }
+    }
+
+    /// See [`Matrix4::rotation`].
+    pub fn rotation(axis: Vec3, degrees: f64) -> Self {
+        let matrix = Matrix4::rotation(axis, degrees);
+
+        Self {
+            matrix,
+            // Rotations are orthogonal
+            inverse: matrix.transpose(),
+        }
+    }
+
+    pub fn from_quaternion(rotation: Quaternion) -> Self {
+        let matrix = rotation.to_matrix();
+
+        Self {
+            matrix,
+            inverse: matrix.transpose(),
+        }
+    }
+
+    /// See [`Matrix4::look_at`]; `from` and `to` must differ and `up` must
+    /// not be parallel to the direction between them.
+    pub fn look_at(from: Point3, to: Point3, up: Vec3) -> Self {
+        let matrix = Matrix4::look_at(from, to, up);
+
+        // A rotation followed by a translation
+        let mut rotation = matrix;
+        rotation.0[0][3] = 0.0;
+        rotation.0[1][3] = 0.0;
+        rotation.0[2][3] = 0.0;
+
+        Self {
+            matrix,
+            inverse: rotation.transpose() * Matrix4::translation(-(from - Point3(0.0, 0.0, 0.0))),
+        }
+    }
+
+    pub const fn matrix(&self) -> &Matrix4 {
+        &self.matrix
+    }
+
+    pub const fn inverse_matrix(&self) -> &Matrix4 {
+        &self.inverse
+    }
+
+    /// Transform applying `self`, then `next`.
+    pub fn then(&self, next: &Self) -> Self {
+        Self {
+            matrix: next.matrix * self.matrix,
+            inverse: self.inverse * next.inverse,
+        }
+    }
+
+    pub const fn inverse(&self) -> Self {
+        Self {
+            matrix: self.inverse,
+            inverse: self.matrix,
+        }
+    }
+
+    pub fn point(&self, point: Point3) -> Point3 {
+        self.matrix.point(point)
+    }
+
+    pub fn vector(&self, vector: Vec3) -> Vec3 {
+        self.matrix.vector(vector)
+    }
+
+    /// Transforms a normal with the inverse transpose, so that it stays
+    /// perpendicular to the transformed surface. The result is not
+    /// normalized.
+    pub fn normal(&self, normal: Vec3) -> Vec3 {
+        let m = &self.inverse.0;
+
+        // Upper 3x3 block of the transposed inverse
+        Vec3(
+            m[0][0] * normal.0 + m[1][0] * normal.1 + m[2][0] * normal.2,
+            m[0][1] * normal.0 + m[1][1] * normal.1 + m[2][1] * normal.2,
+            m[0][2] * normal.0 + m[1][2] * normal.1 + m[2][2] * normal.2,
+        )
+    }
+
+    /// Brings a point back through the transform.
+    pub fn inverse_point(&self, point: Point3) -> Point3 {
+        self.inverse.point(point)
+    }
+
+    pub fn inverse_vector(&self, vector: Vec3) -> Vec3 {
+        self.inverse.vector(vector)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::Vector;
+    use crate::testing::{assert_close, EPSILON};
+
+    #[test]
+    fn must_rotate_counterclockwise() {
+        let rotation = Transform::rotation(Vec3(0.0, 0.0, 2.0), 90.0);
+
+        assert_close(Vec3(0.0, 1.0, 0.0), rotation.vector(Vec3(1.0, 0.0, 0.0)));
+        assert_close(Vec3(-1.0, 0.0, 0.0), rotation.vector(Vec3(0.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn must_compose_in_order() {
+        let transform = Transform::scaling(Vec3(2.0, 2.0, 2.0))
+            .then(&Transform::rotation(Vec3(0.0, 1.0, 0.0), 90.0))
+            .then(&Transform::translation(Vec3(0.0, 5.0, 0.0)));
+
+        let point = Point3(1.0, 0.0, 0.0);
+        let moved = transform.point(point);
+
+        assert_close(Point3(0.0, 5.0, -2.0), moved);
+        assert_close(point, transform.inverse_point(moved));
+        assert_close(point, transform.inverse().point(moved));
+    }
+
+    #[test]
+    fn must_keep_normals_perpendicular() {
+        let transform = Transform::scaling(Vec3(1.0, 4.0, 0.5))
+            .then(&Transform::rotation(Vec3(1.0, 1.0, 0.0), 30.0));
+
+        // Plane spanned by u and v, with normal n
+        let u = Vec3(1.0, -1.0, 0.0);
+        let v = Vec3(0.0, 0.0, 1.0);
+        let n = u.cross(v);
+
+        let normal = transform.normal(n);
+
+        assert!(normal.dot(transform.vector(u)).abs() < EPSILON);
+        assert!(normal.dot(transform.vector(v)).abs() < EPSILON);
+    }
+
+    #[test]
+    fn must_invert_look_at() {
+        let transform = Transform::look_at(
+            Point3(1.0, 2.0, 3.0),
+            Point3(-2.0, 0.0, 1.0),
+            Vec3(0.0, 1.0, 0.0),
+        );
+
+        let point = Point3(0.3, -4.0, 2.5);
+
+        assert_close(point, transform.inverse_point(transform.point(point)));
+        assert_eq!(
+            None,
+            Transform::new(*Transform::scaling(Vec3(0.0, 1.0, 1.0)).matrix())
+        );
+    }
+}
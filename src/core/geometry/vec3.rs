@@ -7,7 +7,7 @@ use crate::core::optic::{Reflect, Refract};
 
 type Scalar = f64;
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub struct Vec3(pub Scalar, pub Scalar, pub Scalar);
 
 impl Vec3 {
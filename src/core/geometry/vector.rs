@@ -7,6 +7,7 @@ pub trait Vector: Copy {
 
     fn sq_length(&self) -> Self::Scalar;
 
+    #[allow(dead_code)]
     fn normalize(&mut self) -> &mut Self;
 
     fn normalized(&self) -> Self;
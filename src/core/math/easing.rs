@@ -0,0 +1,84 @@
+/// Curve reshaping the progress between two keyframes.
+///
+/// Every curve maps 0 to 0 and 1 to 1 and never decreases, so values
+/// interpolated with it stay between the values of the keyframes.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub enum Easing {
+    #[default]
+    Linear,
+    /// Starts slowly, then accelerates
+    EaseIn,
+    /// Starts fast, then decelerates
+    EaseOut,
+    /// Accelerates, then decelerates
+    EaseInOut,
+    /// Keeps the first value until the next keyframe
+    Hold,
+}
+
+impl Easing {
+    /// Names accepted by [`Easing::from_name`].
+    pub const NAMES: [&'static str; 5] = ["linear", "ease_in", "ease_out", "ease_in_out", "hold"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "linear" => Some(Self::Linear),
+            "ease_in" => Some(Self::EaseIn),
+            "ease_out" => Some(Self::EaseOut),
+            "ease_in_out" => Some(Self::EaseInOut),
+            "hold" => Some(Self::Hold),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::EaseIn => "ease_in",
+            Self::EaseOut => "ease_out",
+            Self::EaseInOut => "ease_in_out",
+            Self::Hold => "hold",
+        }
+    }
+
+    /// Eased progress for a progress `t` between 0 and 1.
+    pub fn apply(&self, t: f64) -> f64 {
+        match self {
+            Self::Linear => t,
+            Self::EaseIn => t * t,
+            Self::EaseOut => t * (2.0 - t),
+            Self::EaseInOut => t * t * (3.0 - 2.0 * t),
+            Self::Hold => {
+                if t < 1.0 {
+                    0.0
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_keep_ends_and_order() {
+        for name in &Easing::NAMES {
+            let easing = Easing::from_name(name).unwrap();
+
+            assert_eq!(*name, easing.name());
+            assert_eq!(0.0, easing.apply(0.0));
+            assert_eq!(1.0, easing.apply(1.0));
+
+            let values: Vec<f64> = (0..=20).map(|i| easing.apply(i as f64 / 20.0)).collect();
+
+            assert!(values.windows(2).all(|pair| pair[0] <= pair[1]), "{}", name);
+        }
+
+        assert_eq!(0.25, Easing::EaseIn.apply(0.5));
+        assert_eq!(0.5, Easing::EaseInOut.apply(0.5));
+    }
+}
@@ -1,4 +1,5 @@
 pub mod constants;
+pub mod easing;
 pub mod numeric;
 pub mod optic;
 pub mod rand;
@@ -3,23 +3,31 @@ use rand::prelude::*;
 use super::constants::PI;
 use crate::core::geometry::{Vec3, Vector};
 use rand::distributions::uniform::SampleUniform;
+use rand::rngs::StdRng;
+use std::cell::RefCell;
 
-pub fn rand() -> f64 {
-    let mut rng = rand::thread_rng();
+thread_local! {
+    static GENERATOR: RefCell<StdRng> = RefCell::new(StdRng::from_entropy());
+}
 
-    rng.gen_range(0.0, 1.0)
+/// Reseeds the generator of the current thread, making the following
+/// sequence of random numbers reproducible.
+pub fn seed(value: u64) {
+    GENERATOR.with(|generator| *generator.borrow_mut() = StdRng::seed_from_u64(value));
 }
 
-pub fn rand_between<T: SampleUniform>(min: T, max: T) -> T {
-    let mut rng = rand::thread_rng();
+pub fn rand() -> f64 {
+    GENERATOR.with(|generator| generator.borrow_mut().gen_range(0.0, 1.0))
+}
 
-    rng.gen_range(min, max)
+pub fn rand_between<T: SampleUniform>(min: T, max: T) -> T {
+    GENERATOR.with(|generator| generator.borrow_mut().gen_range(min, max))
 }
 
 pub fn rand_unit_vector() -> Vec3 {
     let azimuth = rand_between(0.0, 2.0 * PI);
     let z = rand_between(-1.0, 1.0);
-    let radius = ((1.0 as f64) - z * z).sqrt();
+    let radius = (1.0_f64 - z * z).sqrt();
 
     Vec3(radius * azimuth.cos(), radius * azimuth.sin(), z)
 }
@@ -37,6 +45,7 @@ pub fn rand_in_hemisphere(normal: Vec3) -> Vec3 {
     }
 }
 
+#[allow(dead_code)]
 pub fn rand_in_unit_disk() -> Vec3 {
     loop {
         let p = Vec3(rand_between(-1.0, 1.0), rand_between(-1.0, 1.0), 0.0);
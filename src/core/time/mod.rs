@@ -1,7 +1,9 @@
 mod interval;
 mod timeray3;
 mod timestamp;
+mod track;
 
 pub use interval::*;
 pub use timeray3::*;
 pub use timestamp::*;
+pub use track::*;
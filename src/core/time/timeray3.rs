@@ -1,4 +1,4 @@
-use crate::core::geometry::{Point3, Ray, Ray3, Vec3};
+use crate::core::geometry::{Point3, Ray, Ray3, Transform, Vec3};
 
 #[derive(Copy, Clone)]
 pub struct TimeRay3 {
@@ -36,6 +36,26 @@ impl TimeRay3 {
     pub const fn time(self) -> f64 {
         self.time
     }
+
+    /// Ray moved by `transform`. The direction is not normalized, so that
+    /// distances along the ray are the same in both spaces.
+    pub fn transformed(self, transform: &Transform) -> Self {
+        Self::new(
+            transform.point(self.origin),
+            transform.vector(self.direction),
+            self.time,
+        )
+    }
+
+    /// Ray brought back through `transform`, e.g. into the space of a
+    /// transformed object.
+    pub fn inverse_transformed(self, transform: &Transform) -> Self {
+        Self::new(
+            transform.inverse_point(self.origin),
+            transform.inverse_vector(self.direction),
+            self.time,
+        )
+    }
 }
 
 impl Ray for TimeRay3 {
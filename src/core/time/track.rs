@@ -0,0 +1,226 @@
+use super::{Interval, Timestamp};
+use crate::core::geometry::{Quaternion, Transform, Vec3, Vector};
+use crate::core::math::easing::Easing;
+
+/// Placement of an object: scaled, then rotated, then translated.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pose {
+    pub translation: Vec3,
+    pub rotation: Quaternion,
+    pub scale: Vec3,
+}
+
+impl Pose {
+    pub fn transform(&self) -> Transform {
+        Transform::scaling(self.scale)
+            .then(&Transform::from_quaternion(self.rotation))
+            .then(&Transform::translation(self.translation))
+    }
+
+    /// Interpolation from `self` at `t = 0` to `other` at `t = 1`.
+    pub fn interpolated(&self, other: &Self, t: f64) -> Self {
+        Self {
+            translation: self.translation + t * (other.translation - self.translation),
+            rotation: self.rotation.slerp(&other.rotation, t),
+            scale: self.scale + t * (other.scale - self.scale),
+        }
+    }
+}
+
+impl Default for Pose {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::zero(),
+            rotation: Quaternion::identity(),
+            scale: Vec3(1.0, 1.0, 1.0),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Keyframe {
+    pub time: Timestamp,
+    pub pose: Pose,
+    /// Easing of the motion towards the next keyframe
+    pub easing: Easing,
+}
+
+#[allow(dead_code)]
+impl Keyframe {
+    pub fn new(time: Timestamp, pose: Pose) -> Self {
+        Self {
+            time,
+            pose,
+            easing: Easing::Linear,
+        }
+    }
+
+    pub fn with_easing(self, easing: Easing) -> Self {
+        Self { easing, ..self }
+    }
+}
+
+/// Part of the motion of a track between two times, with no keyframe in
+/// between.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MotionPiece {
+    pub start: Pose,
+    pub end: Pose,
+    /// Whether the rotation changes, which makes the path of a point curved
+    pub rotates: bool,
+}
+
+/// Poses of an object over time, interpolated between keyframes. The pose
+/// is held before the first keyframe and after the last one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransformTrack {
+    keyframes: Vec<Keyframe>,
+}
+
+#[allow(dead_code)]
+impl TransformTrack {
+    /// Track through `keyframes`, or `None` if there are none or their times
+    /// are not finite and increasing.
+    pub fn new(keyframes: Vec<Keyframe>) -> Option<Self> {
+        let increasing = keyframes.windows(2).all(|pair| pair[0].time < pair[1].time);
+
+        if keyframes.is_empty() || !increasing || keyframes.iter().any(|k| !k.time.is_finite()) {
+            None
+        } else {
+            Some(Self { keyframes })
+        }
+    }
+
+    pub fn keyframes(&self) -> &[Keyframe] {
+        &self.keyframes
+    }
+
+    pub fn pose(&self, time: Timestamp) -> Pose {
+        // Index of the first keyframe after `time`
+        let next = self
+            .keyframes
+            .partition_point(|keyframe| keyframe.time <= time);
+
+        if next == 0 {
+            return self.keyframes[0].pose;
+        }
+
+        let previous = &self.keyframes[next - 1];
+
+        match self.keyframes.get(next) {
+            Some(next) => {
+                let progress = (time - previous.time) / (next.time - previous.time);
+
+                previous
+                    .pose
+                    .interpolated(&next.pose, previous.easing.apply(progress))
+            }
+            None => previous.pose,
+        }
+    }
+
+    pub fn at(&self, time: Timestamp) -> Transform {
+        self.pose(time).transform()
+    }
+
+    /// Splits the motion during `interval` at the keyframes.
+    ///
+    /// Easing curves never decrease, so within a piece the translation and
+    /// the scale stay between their values at both ends.
+    pub fn pieces(&self, interval: Interval) -> Vec<MotionPiece> {
+        let mut times = vec![interval.start()];
+
+        times.extend(
+            self.keyframes
+                .iter()
+                .map(|keyframe| keyframe.time)
+                .filter(|&time| interval.start() < time && time < interval.end()),
+        );
+
+        times.push(interval.end().max(interval.start()));
+
+        times
+            .windows(2)
+            .map(|pair| {
+                let start = self.pose(pair[0]);
+                let end = self.pose(pair[1]);
+
+                // Rotations are interpolated along a single arc within a
+                // piece, so comparing its ends tells whether it turns
+                MotionPiece {
+                    start,
+                    end,
+                    rotates: start.rotation.angle_to(&end.rotation) > 1e-9,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::Point3;
+    use crate::testing::EPSILON;
+
+    fn keyframe(time: Timestamp, x: f64, degrees: f64) -> Keyframe {
+        Keyframe::new(
+            time,
+            Pose {
+                translation: Vec3(x, 0.0, 0.0),
+                rotation: Quaternion::from_axis_angle(Vec3(0.0, 0.0, 1.0), degrees),
+                ..Pose::default()
+            },
+        )
+    }
+
+    #[test]
+    fn must_interpolate_between_keyframes() {
+        let track = TransformTrack::new(vec![
+            keyframe(0.0, 0.0, 0.0),
+            keyframe(1.0, 4.0, 90.0).with_easing(Easing::EaseIn),
+            keyframe(2.0, 8.0, 90.0),
+        ])
+        .unwrap();
+
+        let point = |time: f64| track.at(time).point(Point3(1.0, 0.0, 0.0));
+
+        assert!((point(-1.0) - Point3(1.0, 0.0, 0.0)).length() < EPSILON);
+        assert!((point(1.0) - Point3(4.0, 1.0, 0.0)).length() < EPSILON);
+        assert!((point(3.0) - Point3(8.0, 1.0, 0.0)).length() < EPSILON);
+
+        let half = 0.5_f64.sqrt();
+        assert!((point(0.5) - Point3(2.0 + half, half, 0.0)).length() < EPSILON);
+
+        // Eased in: a quarter of the way at half time
+        assert!((point(1.5) - Point3(5.0, 1.0, 0.0)).length() < EPSILON);
+    }
+
+    #[test]
+    fn must_split_motion_at_keyframes() {
+        let track = TransformTrack::new(vec![
+            keyframe(0.0, 0.0, 0.0),
+            keyframe(1.0, 4.0, 90.0),
+            keyframe(2.0, 8.0, 90.0),
+        ])
+        .unwrap();
+
+        let pieces = track.pieces(Interval::new(0.5, 1.5));
+
+        assert_eq!(2, pieces.len());
+        assert!(pieces[0].rotates);
+        assert!(!pieces[1].rotates);
+        assert!((pieces[1].end.translation - Vec3(6.0, 0.0, 0.0)).length() < EPSILON);
+
+        assert_eq!(1, track.pieces(Interval::new(3.0, 4.0)).len());
+    }
+
+    #[test]
+    fn must_reject_unordered_keyframes() {
+        assert_eq!(None, TransformTrack::new(vec![]));
+        assert_eq!(
+            None,
+            TransformTrack::new(vec![keyframe(1.0, 0.0, 0.0), keyframe(1.0, 1.0, 0.0)])
+        );
+    }
+}
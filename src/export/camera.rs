@@ -0,0 +1,198 @@
+use super::{array, number, object, string, vector, with_member};
+use crate::core::geometry::Vec2;
+use crate::import::JsonValue;
+use crate::scene::camera::{
+    ApertureShape, Bokeh, Camera, CameraKind, Focus, Placement, RealisticLens, StereoRig,
+};
+use crate::scene::{Shutter, ShutterCurve};
+
+/// Description of a camera in a scene file, with its stereo rig and what it
+/// focuses on. The aspect ratio belongs to the image size there.
+pub fn export_camera(
+    camera: &dyn Camera,
+    stereo: Option<&StereoRig>,
+    focus: Option<&Focus>,
+) -> JsonValue {
+    let placement = camera.placement();
+
+    let mut camera = match camera.kind() {
+        CameraKind::Perspective(camera) => {
+            let options = camera.options();
+
+            let mut members = vec![
+                ("vertical_fov", number(options.vertical_fov)),
+                ("aperture", number(options.aperture)),
+                ("focus_distance", number(options.focus_distance)),
+            ];
+
+            if options.tilt != 0.0 {
+                members.push(("tilt", number(options.tilt)));
+            }
+
+            if options.shift != Vec2(0.0, 0.0) {
+                let shift = options.shift;
+
+                members.push(("shift", array(vec![number(shift.x()), number(shift.y())])));
+            }
+
+            if !camera.bokeh().is_default() {
+                members.extend(export_bokeh(camera.bokeh()).map(|bokeh| ("bokeh", bokeh)));
+            }
+
+            export_placement(placement, None, members)
+        }
+        CameraKind::Orthographic(camera) => export_placement(
+            placement,
+            Some("orthographic"),
+            vec![("height", number(camera.height()))],
+        ),
+        CameraKind::Fisheye(camera) => export_placement(
+            placement,
+            Some("fisheye"),
+            vec![
+                ("mapping", string(camera.mapping().name())),
+                ("fov", number(camera.fov())),
+            ],
+        ),
+        CameraKind::Equirectangular(_) => {
+            export_placement(placement, Some("equirectangular"), Vec::new())
+        }
+        CameraKind::Cubemap(camera) => {
+            let members = match camera.face() {
+                Some(face) => vec![("face", string(face.name()))],
+                None => Vec::new(),
+            };
+
+            export_placement(placement, Some("cubemap"), members)
+        }
+        CameraKind::Realistic(camera) => {
+            let mut members = export_lens(camera.lens());
+            members.push(("focus_distance", number(camera.focus_distance())));
+
+            export_placement(placement, Some("realistic"), members)
+        }
+    };
+
+    if let Some(stereo) = stereo {
+        let rig = object(vec![
+            ("interaxial", number(stereo.interaxial)),
+            ("convergence", string(stereo.convergence.name())),
+        ]);
+
+        camera = with_member(camera, "stereo", rig);
+    }
+
+    match focus {
+        Some(Focus::Point(point)) => with_member(camera, "focus_on", vector(*point)),
+        Some(Focus::Auto) => with_member(camera, "autofocus", JsonValue::Bool(true)),
+        None => camera,
+    }
+}
+
+/// Description of a camera of type `kind`, with its own `members`, in a
+/// scene file. Perspective cameras are the default and have no type.
+fn export_placement(
+    placement: &Placement,
+    kind: Option<&str>,
+    members: Vec<(&str, JsonValue)>,
+) -> JsonValue {
+    let mut all = Vec::with_capacity(members.len() + 5);
+
+    if let Some(kind) = kind {
+        all.push(("type", string(kind)));
+    }
+
+    all.push(("look_from", vector(placement.position())));
+    all.push(("look_at", vector(placement.look_at())));
+    all.push(("up", vector(placement.up())));
+    all.extend(members);
+
+    if !placement.shutter().is_default() {
+        all.push(("shutter", export_shutter(placement.shutter())));
+    }
+
+    object(all)
+}
+
+/// Description of the shutter in a scene file.
+fn export_shutter(shutter: &Shutter) -> JsonValue {
+    let mut members = vec![("curve", string(shutter.curve().name()))];
+
+    match shutter.curve() {
+        ShutterCurve::Trapezoid { open, close } => {
+            members.push(("open", number(*open)));
+            members.push(("close", number(*close)));
+        }
+        ShutterCurve::Tabulated(values) => {
+            members.push((
+                "values",
+                array(values.iter().map(|value| number(*value)).collect()),
+            ));
+        }
+        _ => (),
+    }
+
+    if shutter.readout() > 0.0 {
+        members.push(("readout", number(shutter.readout())));
+    }
+
+    object(members)
+}
+
+/// Description of the bokeh in a scene file, or `None` for masks not read
+/// from a file.
+fn export_bokeh(bokeh: &Bokeh) -> Option<JsonValue> {
+    let mut members = vec![("shape", string(bokeh.shape().name()))];
+
+    match bokeh.shape() {
+        ApertureShape::Circle => (),
+        ApertureShape::Polygon { blades, rotation } => {
+            members.push(("blades", number(*blades as f64)));
+            members.push(("rotation", number(*rotation)));
+        }
+        ApertureShape::Mask(mask) => {
+            members.push(("file", string(mask.source()?.to_string_lossy())));
+        }
+    }
+
+    if bokeh.cat_eye() > 0.0 {
+        members.push(("cat_eye", number(bokeh.cat_eye())));
+    }
+
+    Some(object(members))
+}
+
+/// Description of the lens in a scene file.
+fn export_lens(lens: &RealisticLens) -> Vec<(&'static str, JsonValue)> {
+    let mut members = Vec::new();
+
+    match lens.prescription.source() {
+        Some(source) => members.push(("lens_file", string(source.to_string_lossy()))),
+        None => members.push((
+            "elements",
+            array(
+                lens.prescription
+                    .elements()
+                    .iter()
+                    .map(|element| {
+                        array(vec![
+                            number(element.radius),
+                            number(element.thickness),
+                            number(element.ior),
+                            number(element.aperture),
+                        ])
+                    })
+                    .collect(),
+            ),
+        )),
+    }
+
+    members.push(("film_diagonal", number(lens.film_diagonal)));
+
+    if let Some(stop) = lens.aperture_stop {
+        members.push(("aperture_stop", number(stop)));
+    }
+
+    members.push(("millimeters_per_unit", number(lens.millimeters_per_unit)));
+    members
+}
@@ -0,0 +1,59 @@
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum ExportError {
+    /// Object, material or texture without an equivalent in scene files
+    Unsupported(&'static str),
+    /// Scene files only hold finite numbers
+    NotFinite(f64),
+    /// Scene files apply animations after transforms, never inside them
+    NestedAnimation,
+}
+
+impl ExportError {
+    /// Error for the type of the given `type_name`, named without its path
+    /// nor its generic arguments.
+    pub fn unsupported(type_name: &'static str) -> Self {
+        let name = type_name.split('<').next().unwrap_or(type_name);
+
+        Self::Unsupported(name.rsplit("::").next().unwrap_or(name))
+    }
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unsupported(name) => write!(f, "cannot describe a {} in a scene file", name),
+            Self::NotFinite(number) => write!(f, "cannot write the number {}", number),
+            Self::NestedAnimation => write!(
+                f,
+                "cannot describe an animation inside a transform or another animation"
+            ),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+#[cfg(test)]
+mod tests {
+    use std::any::type_name;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::scene::object::Transformed;
+    use crate::scene::Hit;
+
+    #[test]
+    fn must_name_types_without_their_path() {
+        assert_eq!(
+            ExportError::Unsupported("ExportError"),
+            ExportError::unsupported(type_name::<ExportError>())
+        );
+        assert_eq!(
+            ExportError::Unsupported("Transformed"),
+            ExportError::unsupported(type_name::<Transformed<Arc<dyn Hit>>>())
+        );
+    }
+}
@@ -0,0 +1,727 @@
+use std::any::type_name;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::{
+    array, export_camera, number, object, string, vector, with_member, write_json, ExportError,
+};
+use crate::core::geometry::Transform;
+use crate::core::math::easing::Easing;
+use crate::core::time::{Interval, TransformTrack};
+use crate::import::JsonValue;
+use crate::materials::{Material, MaterialKind};
+use crate::scene::camera::{Camera, Focus, StereoRig};
+use crate::scene::object::{MeshTriangle, Plane};
+use crate::scene::{Background, Hit, HitKind};
+use crate::scenes::ImageSettings;
+use crate::textures::{ImageTexture, Texture, TextureKind};
+
+/// Collects the objects, materials and textures of a scene into the scene
+/// file format read by [`parse_scene`].
+///
+/// Materials and textures shared by several objects are written once and
+/// referenced by id. Objects inside transforms, animations and instances
+/// are written with the `transform`, `animation` and `material` members
+/// that place them.
+///
+/// [`parse_scene`]: crate::import::parse_scene
+#[derive(Default)]
+pub struct SceneExporter {
+    textures: Vec<(String, JsonValue)>,
+    materials: Vec<(String, JsonValue)>,
+    objects: Vec<JsonValue>,
+    texture_ids: HashMap<usize, String>,
+    material_ids: HashMap<usize, String>,
+    /// Shared objects already written, by address and placement
+    shared_objects: HashSet<(usize, usize)>,
+    /// Files already referenced, by placement
+    files: HashSet<(PathBuf, usize)>,
+    /// Placements around the object being exported, innermost last
+    placements: Vec<Placement>,
+    placement_count: usize,
+    uses_bvh: bool,
+}
+
+/// Placement of the objects being exported, gathered from the transforms,
+/// animations and instances around them.
+#[derive(Clone, Default)]
+struct Placement {
+    /// Tells apart the instances of a shared object
+    id: usize,
+    /// Transform applied before the animation
+    transform: Option<Transform>,
+    animation: Option<TransformTrack>,
+    /// Id of the material replacing those of the objects
+    material: Option<JsonValue>,
+}
+
+// Address of the value behind an `Arc`, which identifies shared values
+fn address<T: ?Sized>(value: &Arc<T>) -> usize {
+    Arc::as_ptr(value) as *const () as usize
+}
+
+impl SceneExporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an object, and the materials it uses.
+    pub fn add(&mut self, hit: &dyn Hit) -> Result<(), ExportError> {
+        match hit.kind() {
+            HitKind::Sphere(sphere) => {
+                let description = object(vec![
+                    ("type", string("sphere")),
+                    ("center", vector(sphere.center())),
+                    ("radius", number(sphere.radius())),
+                    ("material", self.material(&sphere.material())?),
+                ]);
+
+                self.add_object(description);
+            }
+            HitKind::MovingSphere(sphere) => {
+                let description = object(vec![
+                    ("type", string("moving_sphere")),
+                    ("start_center", vector(sphere.start_center())),
+                    ("end_center", vector(sphere.end_center())),
+                    ("time", export_interval(sphere.time_interval())),
+                    ("radius", number(sphere.radius())),
+                    ("material", self.material(&sphere.material())?),
+                ]);
+
+                self.add_object(description);
+            }
+            HitKind::Triangle(triangle) => {
+                let description = object(vec![
+                    ("type", string("triangle")),
+                    (
+                        "vertices",
+                        array(triangle.vertices().iter().copied().map(vector).collect()),
+                    ),
+                    ("material", self.material(&triangle.material())?),
+                ]);
+
+                self.add_object(description);
+            }
+            // The file of the mesh, once for all its triangles
+            HitKind::MeshTriangle(triangle) => {
+                let source = triangle
+                    .mesh()
+                    .source()
+                    .ok_or_else(|| ExportError::unsupported(type_name::<MeshTriangle>()))?;
+
+                let description = object(vec![
+                    ("type", string("mesh")),
+                    ("file", string(source.to_string_lossy())),
+                ]);
+
+                self.add_file(source, description);
+            }
+            HitKind::Cuboid(cuboid) => {
+                let description = object(vec![
+                    ("type", string("box")),
+                    ("min", vector(cuboid.min())),
+                    ("max", vector(cuboid.max())),
+                    ("material", self.material(&cuboid.material())?),
+                ]);
+
+                self.add_object(description);
+            }
+            HitKind::AxisRect(rect) => {
+                let plane = match rect.plane() {
+                    Plane::XY => "xy",
+                    Plane::XZ => "xz",
+                    Plane::YZ => "yz",
+                };
+                let ((a0, a1), (b0, b1)) = (rect.a(), rect.b());
+
+                let description = object(vec![
+                    ("type", string("rect")),
+                    ("plane", string(plane)),
+                    ("a", array(vec![number(a0), number(a1)])),
+                    ("b", array(vec![number(b0), number(b1)])),
+                    ("k", number(rect.k())),
+                    ("flipped", JsonValue::Bool(rect.is_flipped())),
+                    ("material", self.material(&rect.material())?),
+                ]);
+
+                self.add_object(description);
+            }
+            HitKind::Quad(quad) => {
+                let description = object(vec![
+                    ("type", string("quad")),
+                    ("origin", vector(quad.origin())),
+                    ("u", vector(quad.u())),
+                    ("v", vector(quad.v())),
+                    ("material", self.material(&quad.material())?),
+                ]);
+
+                self.add_object(description);
+            }
+            HitKind::Transformed(transform, object) => {
+                self.transformed(transform, None, |exporter| exporter.add(object))?
+            }
+            HitKind::Animated(track, object) => {
+                self.animated(track, |exporter| exporter.add(object))?
+            }
+            HitKind::Instance(instance) => {
+                self.transformed(instance.transform(), instance.material(), |exporter| {
+                    exporter.add(instance.object().as_ref())
+                })?
+            }
+            HitKind::Bvh(bvh) => {
+                self.use_bvh();
+
+                for object in bvh.objects() {
+                    self.add_shared(object)?;
+                }
+            }
+            HitKind::List(list) => {
+                for object in list.objects() {
+                    self.add(object.as_ref())?;
+                }
+            }
+            HitKind::Other(name) => return Err(ExportError::unsupported(name)),
+        }
+
+        Ok(())
+    }
+
+    /// Reference to a texture: its id or, for plain colors, the color itself.
+    fn texture(&mut self, texture: &Arc<dyn Texture>) -> Result<JsonValue, ExportError> {
+        if let Some(id) = self.texture_ids.get(&address(texture)) {
+            return Ok(JsonValue::String(id.clone()));
+        }
+
+        let description = match texture.kind() {
+            TextureKind::Solid(solid) => return Ok(vector(solid.color())),
+            TextureKind::Checker(checker) => object(vec![
+                ("type", string("checker")),
+                ("odd", self.texture(checker.odd())?),
+                ("even", self.texture(checker.even())?),
+            ]),
+            TextureKind::Image(image) => {
+                let source = image
+                    .source()
+                    .ok_or_else(|| ExportError::unsupported(type_name::<ImageTexture>()))?;
+
+                object(vec![
+                    ("type", string("image")),
+                    ("file", string(source.to_string_lossy())),
+                ])
+            }
+            TextureKind::VertexColor(colors) => object(vec![
+                ("type", string("vertex_color")),
+                ("fallback", vector(colors.fallback())),
+            ]),
+            TextureKind::Other(name) => return Err(ExportError::unsupported(name)),
+        };
+
+        let id = format!("texture{}", self.textures.len());
+
+        self.textures.push((id.clone(), description));
+        self.texture_ids.insert(address(texture), id.clone());
+
+        Ok(JsonValue::String(id))
+    }
+
+    /// Id of a material.
+    fn material(&mut self, material: &Arc<dyn Material>) -> Result<JsonValue, ExportError> {
+        if let Some(id) = self.material_ids.get(&address(material)) {
+            return Ok(JsonValue::String(id.clone()));
+        }
+
+        let description = match material.kind() {
+            MaterialKind::Lambertian(lambertian) => object(vec![
+                ("type", string("lambertian")),
+                ("albedo", self.texture(&lambertian.albedo())?),
+            ]),
+            MaterialKind::Metal(metal) => object(vec![
+                ("type", string("metal")),
+                ("albedo", vector(metal.albedo())),
+                ("fuzz", number(metal.fuzz())),
+            ]),
+            MaterialKind::Dielectric(dielectric) => object(vec![
+                ("type", string("dielectric")),
+                ("refractive_index", number(dielectric.refractive_index())),
+            ]),
+            MaterialKind::DiffuseLight(light) => object(vec![
+                ("type", string("diffuse_light")),
+                ("emit", self.texture(&light.emit())?),
+            ]),
+            MaterialKind::Other(name) => return Err(ExportError::unsupported(name)),
+        };
+        let id = format!("material{}", self.materials.len());
+
+        self.materials.push((id.clone(), description));
+        self.material_ids.insert(address(material), id.clone());
+
+        Ok(JsonValue::String(id))
+    }
+
+    /// Adds the description of an object, with the members of its placement.
+    fn add_object(&mut self, object: JsonValue) {
+        let mut object = object;
+
+        if let Some(placement) = self.placements.last() {
+            if let Some(transform) = placement
+                .transform
+                .filter(|transform| *transform != Transform::identity())
+            {
+                object = with_member(object, "transform", export_transform(&transform));
+            }
+
+            if let Some(track) = &placement.animation {
+                object = with_member(object, "animation", export_track(track));
+            }
+
+            if let Some(material) = &placement.material {
+                object = with_member(object, "material", material.clone());
+            }
+        }
+
+        self.objects.push(object);
+    }
+
+    /// Exports an object that may be referenced more than once, as the
+    /// children of a [`BVH`] leaf, only the first time in each placement.
+    ///
+    /// [`BVH`]: crate::scene::object::BVH
+    fn add_shared(&mut self, object: &Arc<dyn Hit>) -> Result<(), ExportError> {
+        if self
+            .shared_objects
+            .insert((address(object), self.placement_id()))
+        {
+            self.add(object.as_ref())?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds an object read from a `file` as a whole, such as a mesh, only
+    /// the first time in each placement.
+    fn add_file(&mut self, file: &Path, object: JsonValue) {
+        if self.files.insert((file.to_path_buf(), self.placement_id())) {
+            self.add_object(object);
+        }
+    }
+
+    /// Records that the scene had a [`BVH`], so that the file builds one too.
+    /// Hierarchies inside placed objects, such as those of meshes, are built
+    /// by the loader anyway.
+    ///
+    /// [`BVH`]: crate::scene::object::BVH
+    fn use_bvh(&mut self) {
+        if self.placements.is_empty() {
+            self.uses_bvh = true;
+        }
+    }
+
+    /// Exports the objects of `export` moved by `transform`, with their
+    /// materials replaced by `material` when given.
+    fn transformed<F>(
+        &mut self,
+        transform: &Transform,
+        material: Option<&Arc<dyn Material>>,
+        export: F,
+    ) -> Result<(), ExportError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), ExportError>,
+    {
+        let mut placement = self.placements.last().cloned().unwrap_or_default();
+
+        // Inner transforms apply first
+        placement.transform = Some(match &placement.transform {
+            Some(outer) => transform.then(outer),
+            None => *transform,
+        });
+
+        // Outer materials replace inner ones
+        if let (None, Some(material)) = (&placement.material, material) {
+            placement.material = Some(self.material(material)?);
+        }
+
+        self.placed(placement, export)
+    }
+
+    /// Exports the objects of `export` moved by `track`. Scene files apply
+    /// animations last, so they cannot be inside transforms or other
+    /// animations.
+    fn animated<F>(&mut self, track: &TransformTrack, export: F) -> Result<(), ExportError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), ExportError>,
+    {
+        let mut placement = self.placements.last().cloned().unwrap_or_default();
+
+        if placement.transform.is_some() || placement.animation.is_some() {
+            return Err(ExportError::NestedAnimation);
+        }
+
+        placement.animation = Some(track.clone());
+
+        self.placed(placement, export)
+    }
+
+    fn placed<F>(&mut self, placement: Placement, export: F) -> Result<(), ExportError>
+    where
+        F: FnOnce(&mut Self) -> Result<(), ExportError>,
+    {
+        self.placement_count += 1;
+        self.placements.push(Placement {
+            id: self.placement_count,
+            ..placement
+        });
+
+        let result = export(self);
+
+        self.placements.pop();
+        result
+    }
+
+    fn placement_id(&self) -> usize {
+        self.placements.last().map_or(0, |placement| placement.id)
+    }
+}
+
+/// Description of a time interval.
+fn export_interval(interval: Interval) -> JsonValue {
+    object(vec![
+        ("start", number(interval.start())),
+        ("end", number(interval.end())),
+    ])
+}
+
+/// Description of a transform as its matrix.
+fn export_transform(transform: &Transform) -> JsonValue {
+    let rows = transform
+        .matrix()
+        .0
+        .iter()
+        .map(|row| array(row.iter().map(|value| number(*value)).collect()))
+        .collect();
+
+    object(vec![("matrix", array(rows))])
+}
+
+/// Description of the keyframes of a track.
+fn export_track(track: &TransformTrack) -> JsonValue {
+    let keyframes = track
+        .keyframes()
+        .iter()
+        .map(|keyframe| {
+            let (axis, angle) = keyframe.pose.rotation.to_axis_angle();
+
+            let mut members = vec![
+                ("time", number(keyframe.time)),
+                ("scale", vector(keyframe.pose.scale)),
+                (
+                    "rotate",
+                    object(vec![("axis", vector(axis)), ("angle", number(angle))]),
+                ),
+                ("translate", vector(keyframe.pose.translation)),
+            ];
+
+            if keyframe.easing != Easing::Linear {
+                members.push(("easing", string(keyframe.easing.name())));
+            }
+
+            object(members)
+        })
+        .collect();
+
+    array(keyframes)
+}
+
+/// Description of the background in a scene file.
+fn export_background(background: &Background) -> JsonValue {
+    match *background {
+        background if background == Background::sky() => string("sky"),
+        Background::Solid(color) => vector(color),
+        Background::Gradient { bottom, top } => {
+            object(vec![("bottom", vector(bottom)), ("top", vector(top))])
+        }
+    }
+}
+
+/// Describes a scene in the scene file format.
+pub fn export_scene(
+    world: &dyn Hit,
+    camera: &dyn Camera,
+    stereo: Option<&StereoRig>,
+    focus: Option<&Focus>,
+    background: &Background,
+    image: &ImageSettings,
+) -> Result<String, ExportError> {
+    let mut exporter = SceneExporter::new();
+
+    exporter.add(world)?;
+
+    let time = camera.placement().time_interval();
+
+    let named = |members: Vec<(String, JsonValue)>| {
+        object(
+            members
+                .iter()
+                .map(|(name, value)| (name.as_str(), value.clone()))
+                .collect(),
+        )
+    };
+
+    let scene = object(vec![
+        ("camera", export_camera(camera, stereo, focus)),
+        ("time", export_interval(time)),
+        (
+            "image",
+            object(vec![
+                ("width", number(image.width as f64)),
+                ("height", number(image.height as f64)),
+                ("samples", number(image.samples_per_pixel as f64)),
+                ("depth", number(image.max_depth as f64)),
+            ]),
+        ),
+        ("background", export_background(background)),
+        ("textures", named(exporter.textures)),
+        ("materials", named(exporter.materials)),
+        ("objects", array(exporter.objects)),
+        ("bvh", JsonValue::Bool(exporter.uses_bvh)),
+    ]);
+
+    write_json(&scene)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use super::*;
+    use crate::core::geometry::{Point3, Vec2, Vec3, Vector};
+    use crate::core::math::rand;
+    use crate::core::time::TimeRay3;
+    use crate::import::parse_scene;
+    use crate::scene::camera::{
+        ApertureShape, Bokeh, Convergence, CubeFace, FisheyeMapping, Projection, RealisticLens,
+    };
+    use crate::scene::object::{Transformed, AABB};
+    use crate::scene::{HitList, MaterialHitRecord, Shutter, ShutterCurve};
+    use crate::scenes::{generate_scene, AnimationOptions, Scene};
+
+    fn export(scene: &Scene) -> String {
+        let camera = scene.view.camera(
+            scene.image.aspect_ratio(),
+            scene.time,
+            scene.shutter.clone(),
+            &scene.bokeh,
+        );
+
+        export_scene(
+            &scene.world,
+            camera.as_ref(),
+            scene.stereo.as_ref(),
+            scene.focus.as_ref(),
+            &scene.background,
+            &scene.image,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn must_round_trip_generated_scenes() {
+        rand::seed(7);
+
+        for name in ["random", "two-spheres", "simple-light", "cornell-box"] {
+            let exported = export(&generate_scene(name, &AnimationOptions::default()).unwrap());
+            let loaded = parse_scene(&exported, Path::new(".")).unwrap();
+
+            assert_eq!(exported, export(&loaded), "scene {}", name);
+        }
+    }
+
+    #[test]
+    fn must_round_trip_placed_meshes() {
+        let directory = std::env::temp_dir().join("raytracing-rs-export");
+        fs::create_dir_all(&directory).unwrap();
+
+        fs::write(
+            directory.join("square.obj"),
+            "v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf 1 2 3 4\n",
+        )
+        .unwrap();
+
+        let source = r#"{
+  "materials": { "red": { "type": "lambertian", "albedo": [0.8, 0.1, 0.1] } },
+  "objects": [
+    { "type": "mesh", "file": "square.obj" },
+    {
+      "type": "mesh",
+      "file": "square.obj",
+      "material": "red",
+      "transform": { "scale": 2, "rotate": { "axis": [0, 1, 0], "angle": 90 }, "translate": [5, 0, 0] },
+      "animation": [
+        { "time": 0 },
+        { "time": 1, "rotate": { "axis": [0, 0, 1], "angle": 30 }, "translate": [0, 1, 0], "easing": "ease_in" }
+      ]
+    },
+    { "type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "red", "transform": { "translate": [0, 0, -5] } }
+  ]
+}"#;
+
+        let scene = parse_scene(source, &directory).unwrap();
+        let exported = export(&scene);
+
+        // The file once per instance, rather than its triangles
+        assert_eq!(2, exported.matches("square.obj").count());
+
+        // Mesh files are found from the directory of any scene file
+        let loaded = parse_scene(&exported, Path::new(".")).unwrap();
+
+        assert_eq!(exported, export(&loaded));
+
+        for &(origin, direction, time) in &[
+            (Point3(0.5, 0.5, 5.0), Vec3(0.0, 0.0, -1.0), 0.0),
+            (Point3(10.0, 1.0, -1.0), Vec3(-1.0, 0.0, 0.0), 0.0),
+            (Point3(10.0, 2.5, -1.0), Vec3(-1.0, 0.0, 0.0), 0.7),
+            (Point3(0.2, 0.2, -2.0), Vec3(0.0, 0.0, -1.0), 0.3),
+        ] {
+            let ray = TimeRay3::new(origin, direction, time);
+
+            let expected = scene.world.hit(ray, 0.001, f64::INFINITY).unwrap();
+            let actual = loaded.world.hit(ray, 0.001, f64::INFINITY).unwrap();
+
+            assert!((expected.point() - actual.point()).length() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn must_export_shutters() {
+        let mut scene = generate_scene("two-spheres", &AnimationOptions::default()).unwrap();
+
+        assert!(!export(&scene).contains("shutter"));
+
+        scene.shutter = Shutter::new(ShutterCurve::Trapezoid {
+            open: 0.1,
+            close: 0.4,
+        })
+        .unwrap()
+        .with_readout(0.25);
+
+        let loaded = parse_scene(&export(&scene), Path::new(".")).unwrap();
+
+        assert_eq!(scene.shutter, loaded.shutter);
+    }
+
+    #[test]
+    fn must_export_projections() {
+        let mut scene = generate_scene("two-spheres", &AnimationOptions::default()).unwrap();
+
+        for projection in &[
+            Projection::Orthographic { height: 3.0 },
+            Projection::Fisheye {
+                mapping: FisheyeMapping::Equisolid,
+                fov: 200.0,
+            },
+            Projection::Equirectangular,
+            Projection::Cubemap {
+                face: Some(CubeFace::NegativeZ),
+            },
+            Projection::Realistic(RealisticLens {
+                film_diagonal: 30.0,
+                aperture_stop: Some(10.0),
+                ..RealisticLens::default()
+            }),
+        ] {
+            scene.view.projection = projection.clone();
+
+            let loaded = parse_scene(&export(&scene), Path::new(".")).unwrap();
+
+            assert_eq!(scene.view.projection, loaded.view.projection);
+        }
+    }
+
+    #[test]
+    fn must_export_stereo_rigs() {
+        let mut scene = generate_scene("two-spheres", &AnimationOptions::default()).unwrap();
+
+        scene.view.projection = Projection::Equirectangular;
+        scene.stereo = Some(StereoRig {
+            interaxial: 0.3,
+            convergence: Convergence::Omnidirectional,
+        });
+
+        let loaded = parse_scene(&export(&scene), Path::new(".")).unwrap();
+
+        assert_eq!(scene.stereo, loaded.stereo);
+    }
+
+    #[test]
+    fn must_export_bokeh() {
+        let mut scene = generate_scene("two-spheres", &AnimationOptions::default()).unwrap();
+
+        assert!(!export(&scene).contains("bokeh"));
+
+        scene.bokeh = Bokeh::new(ApertureShape::Polygon {
+            blades: 7,
+            rotation: 10.0,
+        })
+        .unwrap()
+        .with_cat_eye(0.4);
+
+        let loaded = parse_scene(&export(&scene), Path::new(".")).unwrap();
+
+        assert_eq!(scene.bokeh, loaded.bokeh);
+    }
+
+    #[test]
+    fn must_export_focus() {
+        let mut scene = generate_scene("two-spheres", &AnimationOptions::default()).unwrap();
+
+        scene.view.tilt = -15.0;
+        scene.view.shift = Vec2(0.1, 0.2);
+        scene.focus = Some(Focus::Point(Point3(0.0, 1.0, 0.0)));
+
+        let loaded = parse_scene(&export(&scene), Path::new(".")).unwrap();
+
+        assert_eq!(scene.view, loaded.view);
+        assert_eq!(scene.focus, loaded.focus);
+
+        scene.focus = Some(Focus::Auto);
+
+        let loaded = parse_scene(&export(&scene), Path::new(".")).unwrap();
+
+        assert_eq!(scene.focus, loaded.focus);
+    }
+
+    #[test]
+    fn must_share_materials() {
+        let scene = generate_scene("cornell-box", &AnimationOptions::default()).unwrap();
+        let exported = export(&scene);
+
+        // Red, green, white and the light
+        assert!(exported.contains("\"material3\""));
+        assert!(!exported.contains("\"material4\""));
+    }
+
+    /// Object without an equivalent in scene files.
+    struct Probe;
+
+    impl Hit for Probe {
+        fn hit(&self, _ray: TimeRay3, _t_min: f64, _t_max: f64) -> Option<MaterialHitRecord> {
+            None
+        }
+
+        fn bounding_box(&self, _interval: Interval) -> Option<AABB> {
+            None
+        }
+    }
+
+    #[test]
+    fn must_reject_unknown_objects() {
+        let mut world = HitList::new();
+        world.add(Box::new(Transformed::new(Probe, Transform::identity())));
+
+        assert_eq!(
+            Err(ExportError::Unsupported("Probe")),
+            SceneExporter::new().add(&world)
+        );
+    }
+}
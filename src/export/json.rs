@@ -0,0 +1,236 @@
+use super::ExportError;
+use crate::core::geometry::Vec3;
+use crate::import::{Json, JsonValue};
+
+/// Values fitting in this many columns are written on a single line.
+const LINE_WIDTH: usize = 100;
+
+pub fn number(value: f64) -> JsonValue {
+    JsonValue::Number(value)
+}
+
+pub fn string<S: Into<String>>(value: S) -> JsonValue {
+    JsonValue::String(value.into())
+}
+
+pub fn vector(value: Vec3) -> JsonValue {
+    array(vec![
+        number(value.x()),
+        number(value.y()),
+        number(value.z()),
+    ])
+}
+
+pub fn array(items: Vec<JsonValue>) -> JsonValue {
+    JsonValue::Array(items.into_iter().map(without_position).collect())
+}
+
+pub fn object(members: Vec<(&str, JsonValue)>) -> JsonValue {
+    JsonValue::Object(
+        members
+            .into_iter()
+            .map(|(name, value)| (name.to_string(), without_position(value)))
+            .collect(),
+    )
+}
+
+/// Adds a member at the end of an `object`, replacing any member of the
+/// same name; other values are unchanged.
+pub fn with_member(object: JsonValue, name: &str, value: JsonValue) -> JsonValue {
+    match object {
+        JsonValue::Object(mut members) => {
+            members.retain(|(member, _)| member != name);
+            members.push((name.to_string(), without_position(value)));
+
+            JsonValue::Object(members)
+        }
+        other => other,
+    }
+}
+
+fn without_position(value: JsonValue) -> Json {
+    Json {
+        value,
+        line: 0,
+        column: 0,
+    }
+}
+
+/// Formats a value with two-space indentation, keeping short arrays and
+/// objects on a single line.
+///
+/// Numbers are written with the shortest representation that reads back to
+/// the same value.
+pub fn write_json(value: &JsonValue) -> Result<String, ExportError> {
+    let mut out = String::new();
+
+    write_value(value, 0, &mut out)?;
+    out.push('\n');
+
+    Ok(out)
+}
+
+fn write_value(value: &JsonValue, indent: usize, out: &mut String) -> Result<(), ExportError> {
+    let inline = write_inline(value)?;
+
+    if indent + inline.len() <= LINE_WIDTH {
+        out.push_str(&inline);
+        return Ok(());
+    }
+
+    let padding = " ".repeat(indent + 2);
+
+    match value {
+        JsonValue::Array(items) => {
+            out.push_str("[\n");
+
+            for (index, item) in items.iter().enumerate() {
+                out.push_str(&padding);
+                write_value(&item.value, indent + 2, out)?;
+                out.push_str(if index + 1 < items.len() { ",\n" } else { "\n" });
+            }
+
+            out.push_str(&" ".repeat(indent));
+            out.push(']');
+        }
+        JsonValue::Object(members) => {
+            out.push_str("{\n");
+
+            for (index, (name, member)) in members.iter().enumerate() {
+                out.push_str(&padding);
+                out.push_str(&quote(name));
+                out.push_str(": ");
+                write_value(&member.value, indent + 2, out)?;
+                out.push_str(if index + 1 < members.len() {
+                    ",\n"
+                } else {
+                    "\n"
+                });
+            }
+
+            out.push_str(&" ".repeat(indent));
+            out.push('}');
+        }
+        _ => out.push_str(&inline),
+    }
+
+    Ok(())
+}
+
+fn write_inline(value: &JsonValue) -> Result<String, ExportError> {
+    let text = match value {
+        JsonValue::Null => "null".to_string(),
+        JsonValue::Bool(value) => value.to_string(),
+        JsonValue::Number(number) if number.is_finite() => number.to_string(),
+        JsonValue::Number(number) => return Err(ExportError::NotFinite(*number)),
+        JsonValue::String(string) => quote(string),
+        JsonValue::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| write_inline(&item.value))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            format!("[{}]", items.join(", "))
+        }
+        JsonValue::Object(members) if members.is_empty() => "{}".to_string(),
+        JsonValue::Object(members) => {
+            let members = members
+                .iter()
+                .map(|(name, member)| {
+                    Ok(format!("{}: {}", quote(name), write_inline(&member.value)?))
+                })
+                .collect::<Result<Vec<_>, ExportError>>()?;
+
+            format!("{{ {} }}", members.join(", "))
+        }
+    };
+
+    Ok(text)
+}
+
+fn quote(string: &str) -> String {
+    let mut quoted = String::with_capacity(string.len() + 2);
+
+    quoted.push('"');
+
+    for c in string.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::parse_json;
+
+    fn strip_positions(value: &JsonValue) -> JsonValue {
+        match value {
+            JsonValue::Array(items) => array(
+                items
+                    .iter()
+                    .map(|item| strip_positions(&item.value))
+                    .collect(),
+            ),
+            JsonValue::Object(members) => JsonValue::Object(
+                members
+                    .iter()
+                    .map(|(name, member)| {
+                        (
+                            name.clone(),
+                            without_position(strip_positions(&member.value)),
+                        )
+                    })
+                    .collect(),
+            ),
+            value => value.clone(),
+        }
+    }
+
+    #[test]
+    fn must_read_back_written_values() {
+        let value = object(vec![
+            ("name", string("quote \" and \\ and \u{1}")),
+            (
+                "numbers",
+                array(vec![
+                    number(0.1),
+                    number(-0.0),
+                    number(1e-300),
+                    number(1e300),
+                ]),
+            ),
+            (
+                "list",
+                array(
+                    (0..20)
+                        .map(|index| object(vec![("index", number(index as f64))]))
+                        .collect(),
+                ),
+            ),
+        ]);
+
+        let text = write_json(&value).unwrap();
+
+        assert_eq!(value, strip_positions(&parse_json(&text).unwrap().value));
+        assert!(text.contains("\n    { \"index\": 0 },\n"));
+    }
+
+    #[test]
+    fn must_reject_non_finite_numbers() {
+        assert_eq!(
+            Err(ExportError::NotFinite(f64::INFINITY)),
+            write_json(&array(vec![number(f64::INFINITY)]))
+        );
+    }
+}
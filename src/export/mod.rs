@@ -0,0 +1,9 @@
+mod camera;
+mod error;
+mod exporter;
+mod json;
+
+pub use camera::*;
+pub use error::*;
+pub use exporter::*;
+pub use json::*;
@@ -0,0 +1,238 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::Path;
+
+use super::{Framebuffer, ImageFormat, TransferFunction};
+use crate::core::color::Color;
+
+/// Reads an 8-bit PNG or PPM image, converting its sRGB values to linear
+/// colors.
+pub fn read_image<P: AsRef<Path>>(path: P) -> io::Result<Framebuffer> {
+    let path = path.as_ref();
+    let reader = BufReader::new(File::open(path)?);
+
+    match ImageFormat::from_path(path) {
+        Some(ImageFormat::Png) => decode_png(reader),
+        Some(ImageFormat::Ppm) | Some(ImageFormat::PlainPpm) => decode_ppm(reader),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("unsupported image format: {}", path.display()),
+        )),
+    }
+}
+
+pub fn decode_png<R: Read>(reader: R) -> io::Result<Framebuffer> {
+    let mut decoder = png::Decoder::new(reader);
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+
+    let mut reader = decoder.read_info()?;
+    let mut buffer = vec![0; reader.output_buffer_size()];
+
+    let info = reader.next_frame(&mut buffer)?;
+    let bytes = &buffer[..info.buffer_size()];
+
+    let channels = info.color_type.samples();
+
+    let samples = bytes.chunks(channels).map(|pixel| match pixel.len() {
+        // Grayscale, with or without alpha
+        1 | 2 => [pixel[0], pixel[0], pixel[0]],
+        _ => [pixel[0], pixel[1], pixel[2]],
+    });
+
+    Ok(from_srgb8(info.width, info.height, samples))
+}
+
+/// Decodes binary (P6) and ASCII (P3) PPM images.
+pub fn decode_ppm<R: BufRead>(mut reader: R) -> io::Result<Framebuffer> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+
+    let invalid = |message: &str| io::Error::new(io::ErrorKind::InvalidData, message.to_string());
+
+    let mut position = 0;
+
+    let next_token = |position: &mut usize| -> Option<String> {
+        loop {
+            while *position < data.len() && data[*position].is_ascii_whitespace() {
+                *position += 1;
+            }
+
+            if *position < data.len() && data[*position] == b'#' {
+                while *position < data.len() && data[*position] != b'\n' {
+                    *position += 1;
+                }
+            } else {
+                break;
+            }
+        }
+
+        let start = *position;
+
+        while *position < data.len() && !data[*position].is_ascii_whitespace() {
+            *position += 1;
+        }
+
+        if start == *position {
+            None
+        } else {
+            Some(String::from_utf8_lossy(&data[start..*position]).into_owned())
+        }
+    };
+
+    let magic = next_token(&mut position).ok_or_else(|| invalid("missing PPM header"))?;
+
+    let mut header = [0_u32; 3];
+
+    for value in header.iter_mut() {
+        *value = next_token(&mut position)
+            .and_then(|token| token.parse().ok())
+            .ok_or_else(|| invalid("invalid PPM header"))?;
+    }
+
+    let [width, height, max_value] = header;
+
+    if max_value == 0 || max_value > 65535 {
+        return Err(invalid("invalid PPM maximum value"));
+    }
+
+    let count = (width as usize)
+        .checked_mul(height as usize)
+        .and_then(|pixels| pixels.checked_mul(3))
+        .ok_or_else(|| invalid("invalid PPM header"))?;
+
+    let values: Vec<u32> = match magic.as_str() {
+        "P3" => (0..count)
+            .map(|_| next_token(&mut position).and_then(|token| token.parse().ok()))
+            .collect::<Option<_>>()
+            .ok_or_else(|| invalid("truncated PPM data"))?,
+        "P6" => {
+            // Single whitespace byte between the header and the data
+            let start = position + 1;
+            let size = if max_value < 256 { 1 } else { 2 };
+
+            let bytes = count
+                .checked_mul(size)
+                .and_then(|length| data.get(start..start.checked_add(length)?))
+                .ok_or_else(|| invalid("truncated PPM data"))?;
+
+            bytes
+                .chunks(size)
+                .map(|value| {
+                    value
+                        .iter()
+                        .fold(0, |acc, &byte| (acc << 8) | u32::from(byte))
+                })
+                .collect()
+        }
+        _ => return Err(invalid("unsupported PPM variant")),
+    };
+
+    let mut image = Framebuffer::new(width, height);
+    let decode =
+        |value: u32| TransferFunction::Srgb.decode(f64::from(value) / f64::from(max_value));
+
+    for (index, pixel) in values.chunks(3).enumerate() {
+        let x = index as u32 % width;
+        let y = index as u32 / width;
+
+        image.set_pixel(
+            x,
+            y,
+            Color(decode(pixel[0]), decode(pixel[1]), decode(pixel[2])),
+        );
+    }
+
+    Ok(image)
+}
+
+fn from_srgb8<I: Iterator<Item = [u8; 3]>>(width: u32, height: u32, samples: I) -> Framebuffer {
+    let decode = |value: u8| TransferFunction::Srgb.decode(f64::from(value) / 255.0);
+
+    let mut image = Framebuffer::new(width, height);
+
+    for (index, [r, g, b]) in samples.enumerate() {
+        let x = index as u32 % width;
+        let y = index as u32 / width;
+
+        image.set_pixel(x, y, Color(decode(r), decode(g), decode(b)));
+    }
+
+    image
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::image::{DisplayTransform, Encoder, PlainPpmEncoder, PngEncoder, PpmEncoder};
+
+    fn make_image() -> Framebuffer {
+        let mut image = Framebuffer::new(3, 2);
+
+        for y in 0..2 {
+            for x in 0..3 {
+                image.set_pixel(x, y, Color(x as f64 / 2.0, y as f64, 0.25));
+            }
+        }
+
+        image
+    }
+
+    fn assert_round_trip(encoder: &dyn Encoder, decode: fn(&[u8]) -> io::Result<Framebuffer>) {
+        let image = make_image();
+
+        let mut out = Vec::new();
+        encoder.encode(&image, &mut out).unwrap();
+
+        let decoded = decode(&out).unwrap();
+
+        assert_eq!(image.width(), decoded.width());
+        assert_eq!(image.height(), decoded.height());
+
+        for (expected, actual) in image.pixels().iter().zip(decoded.pixels()) {
+            assert!((*expected - *actual).x().abs() < 0.01);
+            assert!((*expected - *actual).y().abs() < 0.01);
+            assert!((*expected - *actual).z().abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn must_decode_encoded_png() {
+        assert_round_trip(&PngEncoder::new(DisplayTransform::default()), |data| {
+            decode_png(data)
+        });
+    }
+
+    #[test]
+    fn must_decode_encoded_ppm() {
+        assert_round_trip(&PpmEncoder::new(DisplayTransform::default()), |data| {
+            decode_ppm(data)
+        });
+        assert_round_trip(&PlainPpmEncoder::new(DisplayTransform::default()), |data| {
+            decode_ppm(data)
+        });
+    }
+
+    #[test]
+    fn must_skip_ppm_comments() {
+        let data = b"P3\n# comment\n1 1\n# another\n255\n255 0 0\n";
+
+        let image = decode_ppm(&data[..]).unwrap();
+
+        assert_eq!(Color(1.0, 0.0, 0.0), image.pixel(0, 0));
+    }
+
+    #[test]
+    fn must_reject_truncated_ppm() {
+        assert!(decode_ppm(&b"P6\n2 2\n255\n\x00\x01"[..]).is_err());
+        assert!(decode_ppm(&b"P3\n2 2\n255\n1 2 3"[..]).is_err());
+    }
+
+    #[test]
+    fn must_reject_oversized_ppm() {
+        let error = decode_ppm(&b"P6\n4294967295 4294967295\n255\n\x00"[..])
+            .err()
+            .unwrap();
+
+        assert_eq!("invalid PPM header", error.to_string());
+    }
+}
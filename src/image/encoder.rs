@@ -0,0 +1,7 @@
+use std::io::{self, Write};
+
+use super::Framebuffer;
+
+pub trait Encoder {
+    fn encode(&self, image: &Framebuffer, out: &mut dyn Write) -> io::Result<()>;
+}
@@ -0,0 +1,152 @@
+use std::io::{self, Write};
+
+use super::{Encoder, Framebuffer};
+
+/// Scanline OpenEXR encoder, with uncompressed 32-bit float RGB channels.
+pub struct ExrEncoder;
+
+const MAGIC: [u8; 4] = [0x76, 0x2f, 0x31, 0x01];
+const VERSION: [u8; 4] = [2, 0, 0, 0];
+
+const PIXEL_TYPE_FLOAT: i32 = 2;
+const NO_COMPRESSION: u8 = 0;
+const INCREASING_Y: u8 = 0;
+
+// Channels must be sorted by name
+const CHANNELS: [(&str, usize); 3] = [("B", 2), ("G", 1), ("R", 0)];
+
+impl Encoder for ExrEncoder {
+    fn encode(&self, image: &Framebuffer, out: &mut dyn Write) -> io::Result<()> {
+        let mut file = Vec::new();
+
+        file.extend_from_slice(&MAGIC);
+        file.extend_from_slice(&VERSION);
+
+        write_header(&mut file, image.width(), image.height());
+
+        let width = image.width() as usize;
+        let block_size = width * CHANNELS.len() * 4;
+
+        // One block per scanline, each one with its line number and size
+        let table_size = image.height() as usize * 8;
+        let first_block = (file.len() + table_size) as u64;
+
+        for line in 0..image.height() as u64 {
+            let offset = first_block + line * (8 + block_size as u64);
+
+            file.extend_from_slice(&offset.to_le_bytes());
+        }
+
+        for (line, row) in image.pixels().chunks(width).enumerate() {
+            file.extend_from_slice(&(line as i32).to_le_bytes());
+            file.extend_from_slice(&(block_size as i32).to_le_bytes());
+
+            for &(_, index) in &CHANNELS {
+                for pixel in row {
+                    file.extend_from_slice(&(pixel[index] as f32).to_le_bytes());
+                }
+            }
+        }
+
+        out.write_all(&file)
+    }
+}
+
+fn write_header(file: &mut Vec<u8>, width: u32, height: u32) {
+    let mut channels = Vec::new();
+
+    for &(name, _) in &CHANNELS {
+        channels.extend_from_slice(name.as_bytes());
+        channels.push(0);
+        channels.extend_from_slice(&PIXEL_TYPE_FLOAT.to_le_bytes());
+        // Linear flag and reserved bytes
+        channels.extend_from_slice(&[0, 0, 0, 0]);
+        // Horizontal and vertical sampling
+        channels.extend_from_slice(&1_i32.to_le_bytes());
+        channels.extend_from_slice(&1_i32.to_le_bytes());
+    }
+
+    channels.push(0);
+
+    let mut window = Vec::new();
+
+    for &value in &[0, 0, width as i32 - 1, height as i32 - 1] {
+        window.extend_from_slice(&value.to_le_bytes());
+    }
+
+    let mut center = Vec::new();
+    center.extend_from_slice(&0.0_f32.to_le_bytes());
+    center.extend_from_slice(&0.0_f32.to_le_bytes());
+
+    write_attribute(file, "channels", "chlist", &channels);
+    write_attribute(file, "compression", "compression", &[NO_COMPRESSION]);
+    write_attribute(file, "dataWindow", "box2i", &window);
+    write_attribute(file, "displayWindow", "box2i", &window);
+    write_attribute(file, "lineOrder", "lineOrder", &[INCREASING_Y]);
+    write_attribute(file, "pixelAspectRatio", "float", &1.0_f32.to_le_bytes());
+    write_attribute(file, "screenWindowCenter", "v2f", &center);
+    write_attribute(file, "screenWindowWidth", "float", &1.0_f32.to_le_bytes());
+
+    file.push(0);
+}
+
+fn write_attribute(file: &mut Vec<u8>, name: &str, kind: &str, value: &[u8]) {
+    file.extend_from_slice(name.as_bytes());
+    file.push(0);
+    file.extend_from_slice(kind.as_bytes());
+    file.push(0);
+    file.extend_from_slice(&(value.len() as i32).to_le_bytes());
+    file.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::color::Color;
+
+    fn read_u64(bytes: &[u8]) -> u64 {
+        let mut buffer = [0; 8];
+        buffer.copy_from_slice(&bytes[..8]);
+
+        u64::from_le_bytes(buffer)
+    }
+
+    #[test]
+    fn must_point_offsets_to_scanlines() {
+        let mut image = Framebuffer::new(2, 3);
+
+        image.set_pixel(1, 2, Color(0.5, 2.0, 8.0));
+
+        let mut out = Vec::new();
+        ExrEncoder.encode(&image, &mut out).unwrap();
+
+        assert_eq!(&MAGIC, &out[..4]);
+
+        let block_size = 8 + 2 * 3 * 4;
+        let table_start = out.len() - 3 * block_size - 3 * 8;
+
+        // Header must end right before the offset table
+        assert_eq!(0, out[table_start - 1]);
+
+        let last = read_u64(&out[table_start + 16..]) as usize;
+        assert_eq!(out.len() - block_size, last);
+
+        let block = &out[last..];
+        assert_eq!(&2_i32.to_le_bytes(), &block[..4]);
+
+        let value = |index: usize| {
+            let start = 8 + index * 4;
+            f32::from_le_bytes([
+                block[start],
+                block[start + 1],
+                block[start + 2],
+                block[start + 3],
+            ])
+        };
+
+        // B, G and R of the second pixel
+        assert!((value(1) - 8.0).abs() < f32::EPSILON);
+        assert!((value(3) - 2.0).abs() < f32::EPSILON);
+        assert!((value(5) - 0.5).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,93 @@
+use std::path::Path;
+
+use super::{
+    DisplayTransform, Encoder, ExrEncoder, HdrEncoder, PfmEncoder, PlainPpmEncoder, PngEncoder,
+    PpmEncoder,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ImageFormat {
+    Png,
+    /// Binary (P6) PPM
+    Ppm,
+    /// ASCII (P3) PPM
+    PlainPpm,
+    /// Radiance RGBE
+    Hdr,
+    /// Portable float map
+    Pfm,
+    /// OpenEXR
+    Exr,
+}
+
+impl ImageFormat {
+    /// Names accepted by [`ImageFormat::from_name`].
+    pub const NAMES: [&'static str; 6] = ["png", "ppm", "ppm-ascii", "hdr", "pfm", "exr"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "ppm" => Some(Self::Ppm),
+            "ppm-ascii" => Some(Self::PlainPpm),
+            "hdr" => Some(Self::Hdr),
+            "pfm" => Some(Self::Pfm),
+            "exr" => Some(Self::Exr),
+            _ => None,
+        }
+    }
+
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        path.as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| match extension.to_ascii_lowercase().as_str() {
+                "png" => Some(Self::Png),
+                "ppm" => Some(Self::Ppm),
+                "hdr" => Some(Self::Hdr),
+                "pfm" => Some(Self::Pfm),
+                "exr" => Some(Self::Exr),
+                _ => None,
+            })
+    }
+
+    /// Usual file extension of the format.
+    pub const fn extension(self) -> &'static str {
+        match self {
+            Self::Png => "png",
+            Self::Ppm | Self::PlainPpm => "ppm",
+            Self::Hdr => "hdr",
+            Self::Pfm => "pfm",
+            Self::Exr => "exr",
+        }
+    }
+
+    /// Builds the encoder of the format. High-dynamic-range formats store the
+    /// linear values and ignore `display`.
+    pub fn encoder(self, display: DisplayTransform) -> Box<dyn Encoder> {
+        match self {
+            Self::Png => Box::new(PngEncoder::new(display)),
+            Self::Ppm => Box::new(PpmEncoder::new(display)),
+            Self::PlainPpm => Box::new(PlainPpmEncoder::new(display)),
+            Self::Hdr => Box::new(HdrEncoder),
+            Self::Pfm => Box::new(PfmEncoder),
+            Self::Exr => Box::new(ExrEncoder),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_pick_format_from_extension() {
+        assert_eq!(
+            Some(ImageFormat::Png),
+            ImageFormat::from_path("out/image.PNG")
+        );
+        assert_eq!(Some(ImageFormat::Ppm), ImageFormat::from_path("image.ppm"));
+        assert_eq!(Some(ImageFormat::Exr), ImageFormat::from_path("image.exr"));
+        assert_eq!(None, ImageFormat::from_path("image.jpg"));
+        assert_eq!(None, ImageFormat::from_path("image"));
+    }
+}
@@ -0,0 +1,92 @@
+use super::DisplayTransform;
+use crate::core::color::Color;
+use crate::core::geometry::Vector;
+
+/// Linear colors of a rendered image, row by row from the top-left corner.
+pub struct Framebuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<Color>,
+}
+
+#[allow(dead_code)]
+impl Framebuffer {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![Color::zero(); (width * height) as usize],
+        }
+    }
+
+    /// Builds the image from the sum of `samples_per_pixel` samples of each
+    /// pixel.
+    pub fn from_samples(width: u32, height: u32, sums: Vec<Color>, samples_per_pixel: u32) -> Self {
+        assert_eq!((width * height) as usize, sums.len());
+
+        let scale = 1.0 / samples_per_pixel as f64;
+
+        Self {
+            width,
+            height,
+            pixels: sums.into_iter().map(|sum| sum * scale).collect(),
+        }
+    }
+
+    pub const fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub const fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixel(&self, x: u32, y: u32) -> Color {
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    pub fn set_pixel(&mut self, x: u32, y: u32, color: Color) {
+        self.pixels[(y * self.width + x) as usize] = color;
+    }
+
+    pub fn pixels(&self) -> &[Color] {
+        &self.pixels
+    }
+
+    /// Image with `other`, of the same height, on the right of this one.
+    pub fn beside(&self, other: &Self) -> Self {
+        assert_eq!(self.height, other.height);
+
+        let pixels = self
+            .pixels
+            .chunks(self.width as usize)
+            .zip(other.pixels.chunks(other.width as usize))
+            .flat_map(|(left, right)| left.iter().chain(right).copied())
+            .collect();
+
+        Self {
+            width: self.width + other.width,
+            height: self.height,
+            pixels,
+        }
+    }
+
+    /// Image with `other`, of the same width, below this one.
+    pub fn above(&self, other: &Self) -> Self {
+        assert_eq!(self.width, other.width);
+
+        Self {
+            width: self.width,
+            height: self.height + other.height,
+            pixels: [self.pixels(), other.pixels()].concat(),
+        }
+    }
+
+    /// Converts the image to 8 bits per channel, in RGB order.
+    pub fn to_rgb8(&self, display: &DisplayTransform) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&pixel| display.quantize(pixel).to_vec())
+            .collect()
+    }
+}
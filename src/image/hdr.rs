@@ -0,0 +1,173 @@
+use std::io::{self, Write};
+
+use super::{Encoder, Framebuffer};
+use crate::core::color::Color;
+
+/// Radiance RGBE (`.hdr`) encoder, with run-length encoded scanlines.
+pub struct HdrEncoder;
+
+// Scanlines outside of these bounds cannot be run-length encoded
+const MIN_RLE_WIDTH: u32 = 8;
+const MAX_RLE_WIDTH: u32 = 0x7fff;
+
+const MIN_RUN: usize = 4;
+const MAX_RUN: usize = 127;
+
+impl Encoder for HdrEncoder {
+    fn encode(&self, image: &Framebuffer, out: &mut dyn Write) -> io::Result<()> {
+        write!(
+            out,
+            "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+            image.height(),
+            image.width()
+        )?;
+
+        let width = image.width() as usize;
+
+        for row in image.pixels().chunks(width) {
+            let scanline: Vec<[u8; 4]> = row.iter().map(|&pixel| to_rgbe(pixel)).collect();
+
+            if image.width() < MIN_RLE_WIDTH || image.width() > MAX_RLE_WIDTH {
+                for pixel in scanline {
+                    out.write_all(&pixel)?;
+                }
+            } else {
+                out.write_all(&[2, 2, (width >> 8) as u8, (width & 0xff) as u8])?;
+
+                for channel in 0..4 {
+                    let values: Vec<u8> = scanline.iter().map(|pixel| pixel[channel]).collect();
+
+                    write_rle(&values, out)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Converts a color to a shared-exponent RGBE pixel.
+pub fn to_rgbe(color: Color) -> [u8; 4] {
+    let Color(r, g, b) = color;
+    let (r, g, b) = (r.max(0.0), g.max(0.0), b.max(0.0));
+
+    let max = r.max(g).max(b);
+
+    if max < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+
+    // max = mantissa * 2^exponent, with mantissa in [0.5, 1)
+    let exponent = max.log2().floor() as i32 + 1;
+    let mantissa = max / 2.0_f64.powi(exponent);
+
+    let scale = mantissa * 256.0 / max;
+
+    [
+        (r * scale) as u8,
+        (g * scale) as u8,
+        (b * scale) as u8,
+        (exponent + 128) as u8,
+    ]
+}
+
+fn write_rle(values: &[u8], out: &mut dyn Write) -> io::Result<()> {
+    let mut current = 0;
+
+    while current < values.len() {
+        // Look for the next run long enough to be worth encoding
+        let mut run_start = current;
+        let mut run_length = 0;
+
+        while run_start < values.len() {
+            run_length = values[run_start..]
+                .iter()
+                .take(MAX_RUN)
+                .take_while(|&&value| value == values[run_start])
+                .count();
+
+            if run_length >= MIN_RUN {
+                break;
+            }
+
+            run_start += run_length;
+        }
+
+        // Literal values before the run
+        for chunk in values[current..run_start].chunks(MAX_RUN) {
+            out.write_all(&[chunk.len() as u8])?;
+            out.write_all(chunk)?;
+        }
+
+        if run_length >= MIN_RUN {
+            out.write_all(&[128 + run_length as u8, values[run_start]])?;
+
+            current = run_start + run_length;
+        } else {
+            current = run_start;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_rle(mut data: &[u8], count: usize) -> (Vec<u8>, &[u8]) {
+        let mut values = Vec::new();
+
+        while values.len() < count {
+            let code = data[0] as usize;
+
+            if code > 128 {
+                values.resize(values.len() + code - 128, data[1]);
+                data = &data[2..];
+            } else {
+                values.extend_from_slice(&data[1..=code]);
+                data = &data[code + 1..];
+            }
+        }
+
+        (values, data)
+    }
+
+    #[test]
+    fn must_convert_to_rgbe() {
+        assert_eq!([128, 128, 128, 129], to_rgbe(Color(1.0, 1.0, 1.0)));
+        assert_eq!([128, 64, 0, 131], to_rgbe(Color(4.0, 2.0, 0.0)));
+        assert_eq!([0, 0, 0, 0], to_rgbe(Color(0.0, -1.0, 0.0)));
+    }
+
+    #[test]
+    fn must_encode_run_length_scanlines() {
+        let mut image = Framebuffer::new(10, 1);
+
+        image.set_pixel(3, 0, Color(4.0, 2.0, 0.0));
+
+        let mut out = Vec::new();
+        HdrEncoder.encode(&image, &mut out).unwrap();
+
+        let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 10\n";
+
+        assert_eq!(&header[..], &out[..header.len()]);
+
+        let mut data = &out[header.len()..];
+        assert_eq!(&[2, 2, 0, 10], &data[..4]);
+        data = &data[4..];
+
+        for expected in &[128, 64, 0, 131] {
+            let (channel, rest) = decode_rle(data, 10);
+
+            let mut expected_channel = vec![0; 10];
+            expected_channel[3] = *expected;
+
+            assert_eq!(expected_channel, channel);
+
+            data = rest;
+        }
+
+        assert!(data.is_empty());
+    }
+}
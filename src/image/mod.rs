@@ -0,0 +1,21 @@
+mod decoder;
+mod encoder;
+mod exr;
+mod format;
+mod framebuffer;
+mod hdr;
+mod pfm;
+mod png;
+mod ppm;
+mod tonemap;
+
+pub use decoder::*;
+pub use encoder::*;
+pub use exr::*;
+pub use format::*;
+pub use framebuffer::*;
+pub use hdr::*;
+pub use pfm::*;
+pub use png::*;
+pub use ppm::*;
+pub use tonemap::*;
@@ -0,0 +1,51 @@
+use std::io::{self, Write};
+
+use super::{Encoder, Framebuffer};
+
+/// Portable float map (`.pfm`) encoder, with little-endian RGB values.
+pub struct PfmEncoder;
+
+impl Encoder for PfmEncoder {
+    fn encode(&self, image: &Framebuffer, out: &mut dyn Write) -> io::Result<()> {
+        // A negative scale means little-endian data
+        write!(out, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+
+        // Rows are stored from the bottom to the top of the image
+        for row in image.pixels().chunks(image.width() as usize).rev() {
+            for pixel in row {
+                for &value in &[pixel.x(), pixel.y(), pixel.z()] {
+                    out.write_all(&(value as f32).to_le_bytes())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::color::Color;
+
+    #[test]
+    fn must_write_rows_bottom_up() {
+        let mut image = Framebuffer::new(1, 2);
+
+        image.set_pixel(0, 0, Color(1.0, 2.0, 3.0));
+        image.set_pixel(0, 1, Color(4.0, 5.0, 6.0));
+
+        let mut out = Vec::new();
+        PfmEncoder.encode(&image, &mut out).unwrap();
+
+        let header = b"PF\n1 2\n-1.0\n";
+        assert_eq!(&header[..], &out[..header.len()]);
+
+        let values: Vec<f32> = out[header.len()..]
+            .chunks(4)
+            .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .collect();
+
+        assert_eq!(vec![4.0, 5.0, 6.0, 1.0, 2.0, 3.0], values);
+    }
+}
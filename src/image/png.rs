@@ -0,0 +1,29 @@
+use std::io::{self, Write};
+
+use super::{DisplayTransform, Encoder, Framebuffer};
+
+pub struct PngEncoder {
+    display: DisplayTransform,
+}
+
+impl PngEncoder {
+    pub const fn new(display: DisplayTransform) -> Self {
+        Self { display }
+    }
+}
+
+impl Encoder for PngEncoder {
+    fn encode(&self, image: &Framebuffer, out: &mut dyn Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(out, image.width(), image.height());
+
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+
+        writer.write_image_data(&image.to_rgb8(&self.display))?;
+        writer.finish()?;
+
+        Ok(())
+    }
+}
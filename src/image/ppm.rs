@@ -0,0 +1,93 @@
+use std::io::{self, Write};
+
+use super::{DisplayTransform, Encoder, Framebuffer};
+
+/// Binary (P6) PPM encoder.
+pub struct PpmEncoder {
+    display: DisplayTransform,
+}
+
+/// ASCII (P3) PPM encoder, with one pixel per line.
+pub struct PlainPpmEncoder {
+    display: DisplayTransform,
+}
+
+impl PpmEncoder {
+    pub const fn new(display: DisplayTransform) -> Self {
+        Self { display }
+    }
+}
+
+impl PlainPpmEncoder {
+    pub const fn new(display: DisplayTransform) -> Self {
+        Self { display }
+    }
+}
+
+impl Encoder for PpmEncoder {
+    fn encode(&self, image: &Framebuffer, out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "P6\n{} {}\n255\n", image.width(), image.height())?;
+
+        out.write_all(&image.to_rgb8(&self.display))
+    }
+}
+
+impl Encoder for PlainPpmEncoder {
+    fn encode(&self, image: &Framebuffer, out: &mut dyn Write) -> io::Result<()> {
+        write!(out, "P3\n{} {}\n255\n", image.width(), image.height())?;
+
+        for pixel in image.to_rgb8(&self.display).chunks(3) {
+            writeln!(out, "{} {} {}", pixel[0], pixel[1], pixel[2])?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::color::Color;
+    use crate::image::{ToneMap, TransferFunction};
+
+    const GAMMA_2: DisplayTransform = DisplayTransform {
+        exposure: 0.0,
+        tone_map: ToneMap::Clamp,
+        transfer: TransferFunction::Gamma(2.0),
+    };
+
+    fn make_image() -> Framebuffer {
+        let mut image = Framebuffer::new(2, 1);
+
+        image.set_pixel(0, 0, Color(1.0, 0.25, 0.0));
+        image.set_pixel(1, 0, Color(4.0, -1.0, 0.0625));
+
+        image
+    }
+
+    #[test]
+    fn must_encode_plain_ppm() {
+        let mut out = Vec::new();
+
+        PlainPpmEncoder::new(GAMMA_2)
+            .encode(&make_image(), &mut out)
+            .unwrap();
+
+        assert_eq!(
+            "P3\n2 1\n255\n255 127 0\n255 0 63\n",
+            String::from_utf8(out).unwrap()
+        );
+    }
+
+    #[test]
+    fn must_encode_binary_ppm() {
+        let mut out = Vec::new();
+
+        PpmEncoder::new(GAMMA_2)
+            .encode(&make_image(), &mut out)
+            .unwrap();
+
+        assert_eq!(b"P6\n2 1\n255\n", &out[..11]);
+        assert_eq!(&[255, 127, 0, 255, 0, 63], &out[11..]);
+    }
+}
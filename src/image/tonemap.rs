@@ -0,0 +1,279 @@
+use crate::core::color::Color;
+use crate::core::math::numeric::clamp;
+
+/// Operator compressing scene-referred linear radiance into [0, 1].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ToneMap {
+    /// Clips every value above 1.0
+    Clamp,
+    /// Reinhard operator over the luminance
+    Reinhard,
+    /// Reinhard operator mapping `white` (and above) to 1.0
+    ExtendedReinhard { white: f64 },
+    /// Narkowicz's fit of the ACES filmic curve
+    Aces,
+    /// Hable's filmic curve from Uncharted 2
+    Hable,
+}
+
+/// Encoding from linear values to the display signal.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TransferFunction {
+    Linear,
+    Gamma(f64),
+    Srgb,
+}
+
+/// Conversion from the linear framebuffer to display values, applied in
+/// order: exposure (in stops), tone mapping and transfer function.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DisplayTransform {
+    pub exposure: f64,
+    pub tone_map: ToneMap,
+    pub transfer: TransferFunction,
+}
+
+impl Default for DisplayTransform {
+    fn default() -> Self {
+        Self {
+            exposure: 0.0,
+            tone_map: ToneMap::Clamp,
+            transfer: TransferFunction::Srgb,
+        }
+    }
+}
+
+impl DisplayTransform {
+    pub fn apply(&self, color: Color) -> Color {
+        let exposed = color * 2.0_f64.powf(self.exposure);
+        let Color(r, g, b) = self.tone_map.apply(exposed);
+
+        let encode = |value: f64| self.transfer.encode(clamp(value, 0.0, 1.0));
+
+        Color(encode(r), encode(g), encode(b))
+    }
+
+    pub fn quantize(&self, color: Color) -> [u8; 3] {
+        let quantize = |value: f64| (255.999 * clamp(value, 0.0, 0.999)) as u8;
+        let Color(r, g, b) = self.apply(color);
+
+        [quantize(r), quantize(g), quantize(b)]
+    }
+}
+
+impl ToneMap {
+    /// Names accepted by [`ToneMap::from_name`].
+    pub const NAMES: [&'static str; 5] =
+        ["clamp", "reinhard", "reinhard-extended", "aces", "hable"];
+
+    /// Parses an operator name; `white` is only used by the extended Reinhard
+    /// operator.
+    pub fn from_name(name: &str, white: f64) -> Option<Self> {
+        match name {
+            "clamp" => Some(Self::Clamp),
+            "reinhard" => Some(Self::Reinhard),
+            "reinhard-extended" => Some(Self::ExtendedReinhard { white }),
+            "aces" => Some(Self::Aces),
+            "hable" => Some(Self::Hable),
+            _ => None,
+        }
+    }
+
+    pub fn apply(self, color: Color) -> Color {
+        let Color(r, g, b) = color;
+
+        match self {
+            Self::Clamp => color,
+            Self::Reinhard => scale_luminance(color, |l| l / (1.0 + l)),
+            Self::ExtendedReinhard { white } => {
+                scale_luminance(color, |l| l * (1.0 + l / (white * white)) / (1.0 + l))
+            }
+            Self::Aces => Color(aces(r), aces(g), aces(b)),
+            Self::Hable => {
+                const EXPOSURE_BIAS: f64 = 2.0;
+                const WHITE: f64 = 11.2;
+
+                let white_scale = 1.0 / hable(WHITE);
+                let map = |value: f64| hable(EXPOSURE_BIAS * value) * white_scale;
+
+                Color(map(r), map(g), map(b))
+            }
+        }
+    }
+}
+
+impl TransferFunction {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "linear" => Some(Self::Linear),
+            "srgb" => Some(Self::Srgb),
+            _ => name
+                .strip_prefix("gamma")
+                .and_then(|gamma| gamma.parse().ok())
+                .filter(|&gamma: &f64| gamma > 0.0)
+                .map(Self::Gamma),
+        }
+    }
+
+    pub fn encode(self, value: f64) -> f64 {
+        match self {
+            Self::Linear => value,
+            Self::Gamma(gamma) => value.powf(1.0 / gamma),
+            Self::Srgb => {
+                if value <= 0.003_130_8 {
+                    12.92 * value
+                } else {
+                    1.055_f64.mul_add(value.powf(1.0 / 2.4), -0.055)
+                }
+            }
+        }
+    }
+
+    /// Inverse of [`TransferFunction::encode`], from display to linear values.
+    pub fn decode(self, value: f64) -> f64 {
+        match self {
+            Self::Linear => value,
+            Self::Gamma(gamma) => value.powf(gamma),
+            Self::Srgb => {
+                if value <= 0.040_45 {
+                    value / 12.92
+                } else {
+                    ((value + 0.055) / 1.055).powf(2.4)
+                }
+            }
+        }
+    }
+}
+
+fn luminance(color: Color) -> f64 {
+    0.2126_f64.mul_add(color.x(), 0.7152_f64.mul_add(color.y(), 0.0722 * color.z()))
+}
+
+fn scale_luminance<F: Fn(f64) -> f64>(color: Color, map: F) -> Color {
+    let l = luminance(color);
+
+    if l <= 0.0 {
+        color
+    } else {
+        color * (map(l) / l)
+    }
+}
+
+fn aces(x: f64) -> f64 {
+    (x * 2.51_f64.mul_add(x, 0.03)) / x.mul_add(2.43_f64.mul_add(x, 0.59), 0.14)
+}
+
+fn hable(x: f64) -> f64 {
+    const A: f64 = 0.15;
+    const B: f64 = 0.50;
+    const C: f64 = 0.10;
+    const D: f64 = 0.20;
+    const E: f64 = 0.02;
+    const F: f64 = 0.30;
+
+    ((x * A.mul_add(x, C * B) + D * E) / (x * A.mul_add(x, B) + D * F)) - E / F
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-6;
+
+    fn almost_equal(v1: f64, v2: f64) -> bool {
+        (v1 - v2).abs() < EPSILON
+    }
+
+    #[test]
+    fn must_apply_exposure_in_stops() {
+        let transform = DisplayTransform {
+            exposure: 1.0,
+            tone_map: ToneMap::Clamp,
+            transfer: TransferFunction::Linear,
+        };
+
+        assert!(almost_equal(
+            0.5,
+            transform.apply(Color(0.25, 0.0, 0.0)).x()
+        ));
+        assert!(almost_equal(1.0, transform.apply(Color(4.0, 0.0, 0.0)).x()));
+    }
+
+    #[test]
+    fn must_encode_srgb() {
+        let srgb = TransferFunction::Srgb;
+
+        assert!(almost_equal(0.0, srgb.encode(0.0)));
+        assert!(almost_equal(12.92 * 0.002, srgb.encode(0.002)));
+        assert!(almost_equal(0.735_356_983, srgb.encode(0.5)));
+        assert!(almost_equal(1.0, srgb.encode(1.0)));
+    }
+
+    #[test]
+    fn must_decode_what_was_encoded() {
+        for &function in &[
+            TransferFunction::Srgb,
+            TransferFunction::Gamma(2.2),
+            TransferFunction::Linear,
+        ] {
+            for step in 0..=10 {
+                let value = step as f64 / 10.0;
+
+                assert!(almost_equal(value, function.decode(function.encode(value))));
+            }
+        }
+    }
+
+    #[test]
+    fn must_map_extended_reinhard_white_to_one() {
+        let white = 4.0;
+        let mapped = ToneMap::ExtendedReinhard { white }.apply(Color(white, white, white));
+
+        assert!(almost_equal(1.0, mapped.x()));
+        assert!(almost_equal(1.0, mapped.z()));
+    }
+
+    #[test]
+    fn must_keep_operators_in_display_range() {
+        let operators = [
+            ToneMap::Reinhard,
+            ToneMap::ExtendedReinhard { white: 2.0 },
+            ToneMap::Aces,
+            ToneMap::Hable,
+        ];
+
+        for &tone_map in &operators {
+            let transform = DisplayTransform {
+                exposure: 0.0,
+                tone_map,
+                transfer: TransferFunction::Linear,
+            };
+
+            let dark = transform.apply(Color(0.0, 0.0, 0.0));
+            let middle = transform.apply(Color(0.18, 0.18, 0.18));
+            let bright = transform.apply(Color(100.0, 100.0, 100.0));
+
+            assert!(dark.x().abs() < 1e-3, "{:?}", tone_map);
+            assert!(
+                middle.x() > dark.x() && middle.x() < bright.x(),
+                "{:?}",
+                tone_map
+            );
+            assert!(bright.x() > 0.9, "{:?}", tone_map);
+        }
+    }
+
+    #[test]
+    fn must_parse_transfer_names() {
+        assert_eq!(
+            Some(TransferFunction::Srgb),
+            TransferFunction::from_name("srgb")
+        );
+        assert_eq!(
+            Some(TransferFunction::Gamma(2.2)),
+            TransferFunction::from_name("gamma2.2")
+        );
+        assert_eq!(None, TransferFunction::from_name("gamma-1"));
+        assert_eq!(None, TransferFunction::from_name("log"));
+    }
+}
@@ -0,0 +1,106 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+/// Place of an error: a (1-based) line, or line and column, of a text file,
+/// or the offset of binary data.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Position {
+    Line(usize),
+    LineColumn(usize, usize),
+    Byte(u64),
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub position: Position,
+    pub message: String,
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Io {
+        path: PathBuf,
+        error: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        error: ParseError,
+    },
+    /// Mesh file without any triangle
+    Empty {
+        path: PathBuf,
+    },
+}
+
+impl ParseError {
+    pub fn new<S: Into<String>>(line: usize, message: S) -> Self {
+        Self {
+            position: Position::Line(line),
+            message: message.into(),
+        }
+    }
+
+    pub fn at<S: Into<String>>(position: Position, message: S) -> Self {
+        Self {
+            position,
+            message: message.into(),
+        }
+    }
+}
+
+impl ImportError {
+    pub fn io<P: Into<PathBuf>>(path: P, error: io::Error) -> Self {
+        Self::Io {
+            path: path.into(),
+            error,
+        }
+    }
+
+    pub fn parse<P: Into<PathBuf>>(path: P, error: ParseError) -> Self {
+        Self::Parse {
+            path: path.into(),
+            error,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.position {
+            Position::Line(line) => write!(f, "line {}: {}", line, self.message),
+            Position::LineColumn(line, column) => {
+                write!(f, "line {}, column {}: {}", line, column, self.message)
+            }
+            Position::Byte(offset) => write!(f, "byte {}: {}", offset, self.message),
+        }
+    }
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io { path, error } => write!(f, "{}: {}", path.display(), error),
+            Self::Parse { path, error } => match error.position {
+                Position::Line(line) => {
+                    write!(f, "{}:{}: {}", path.display(), line, error.message)
+                }
+                Position::LineColumn(line, column) => write!(
+                    f,
+                    "{}:{}:{}: {}",
+                    path.display(),
+                    line,
+                    column,
+                    error.message
+                ),
+                Position::Byte(_) => write!(f, "{}: {}", path.display(), error),
+            },
+            Self::Empty { path } => write!(f, "{}: the mesh has no triangles", path.display()),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+impl Error for ImportError {}
@@ -0,0 +1,174 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{load_obj, parse_ply, parse_stl, ImportError, ImportedMesh, MeshData, ParseError};
+use crate::core::color::Color;
+use crate::materials::{Lambertian, Material};
+use crate::textures::VertexColor;
+
+/// Albedo of the grey material of meshes without a material of their own.
+pub const DEFAULT_MESH_ALBEDO: Color = Color(0.73, 0.73, 0.73);
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum MeshFormat {
+    /// Wavefront OBJ, with MTL materials
+    Obj,
+    /// Stanford polygon file
+    Ply,
+    /// Stereolithography
+    Stl,
+}
+
+impl MeshFormat {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        path.as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| match extension.to_ascii_lowercase().as_str() {
+                "obj" => Some(Self::Obj),
+                "ply" => Some(Self::Ply),
+                "stl" => Some(Self::Stl),
+                _ => None,
+            })
+    }
+
+    /// Reads the meshes of a file, leaving out empty ones. Every mesh uses
+    /// `material` when given. Otherwise OBJ meshes use their MTL materials
+    /// and PLY meshes with vertex colors a [`VertexColor`] texture, and any
+    /// other mesh a grey of [`DEFAULT_MESH_ALBEDO`]. Files without any
+    /// triangle are an error.
+    pub fn load<P: AsRef<Path>>(
+        self,
+        path: P,
+        material: Option<Arc<dyn Material>>,
+    ) -> Result<Vec<ImportedMesh>, ImportError> {
+        let path = path.as_ref();
+
+        let parse = |parse: fn(BufReader<File>) -> Result<MeshData, ParseError>| {
+            let file = File::open(path).map_err(|error| ImportError::io(path, error))?;
+
+            parse(BufReader::new(file)).map_err(|error| ImportError::parse(path, error))
+        };
+
+        let default_material =
+            || -> Arc<dyn Material> { Arc::new(Lambertian::from_color(DEFAULT_MESH_ALBEDO)) };
+
+        let meshes = match (self, material) {
+            (Self::Obj, None) => load_obj(path, default_material())?,
+            (Self::Obj, Some(material)) => load_obj(path, material.clone())?
+                .into_iter()
+                .map(|imported| ImportedMesh {
+                    mesh: imported.mesh.with_material(material.clone()),
+                    ..imported
+                })
+                .collect(),
+            (_, material) => {
+                let data = match self {
+                    Self::Ply => parse(parse_ply)?,
+                    _ => parse(parse_stl)?,
+                };
+
+                let material = material.unwrap_or_else(|| {
+                    if data.colors.is_some() {
+                        let texture = Arc::new(VertexColor::new(DEFAULT_MESH_ALBEDO));
+
+                        Arc::new(Lambertian::new(texture))
+                    } else {
+                        default_material()
+                    }
+                });
+
+                vec![ImportedMesh {
+                    name: file_name(path),
+                    mesh: data.into_mesh(material),
+                }]
+            }
+        };
+
+        let meshes: Vec<_> = meshes
+            .into_iter()
+            .filter(|imported| !imported.mesh.is_empty())
+            .collect();
+
+        if meshes.is_empty() {
+            return Err(ImportError::Empty {
+                path: path.to_path_buf(),
+            });
+        }
+
+        Ok(meshes)
+    }
+}
+
+/// Name of a file without its directory and extension.
+pub fn file_name(path: &Path) -> String {
+    path.file_stem()
+        .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::testing::grey_material;
+
+    const COLORED_PLY: &[u8] = b"ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\nproperty float y\nproperty float z\nproperty uchar red\nproperty uchar green\nproperty uchar blue\nelement face 1\nproperty list uchar int vertex_indices\nend_header\n0 0 0 255 0 0\n1 0 0 0 255 0\n0 1 0 0 0 255\n3 0 1 2\n";
+
+    fn write(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let directory = std::env::temp_dir().join("raytracing-rs-formats");
+        fs::create_dir_all(&directory).unwrap();
+
+        let path = directory.join(name);
+        fs::write(&path, contents).unwrap();
+
+        path
+    }
+
+    fn load(name: &str, contents: &[u8]) -> Result<Vec<ImportedMesh>, ImportError> {
+        let path = write(name, contents);
+
+        MeshFormat::from_path(&path).unwrap().load(&path, None)
+    }
+
+    #[test]
+    fn must_reject_empty_meshes() {
+        let mut binary_stl = vec![0; 80];
+        binary_stl.extend_from_slice(&0_u32.to_le_bytes());
+
+        for (name, contents) in [
+            ("empty.obj", b"v 0 0 0\nv 1 0 0\nv 0 1 0\n".to_vec()),
+            ("empty-ascii.stl", b"solid x\nendsolid x\n".to_vec()),
+            ("empty-binary.stl", binary_stl),
+            (
+                "empty.ply",
+                b"ply\nformat ascii 1.0\nelement vertex 1\nproperty float x\nproperty float y\nproperty float z\nelement face 0\nproperty list uchar int vertex_indices\nend_header\n0 0 0\n".to_vec(),
+            ),
+        ] {
+            assert!(
+                matches!(load(name, &contents), Err(ImportError::Empty { .. })),
+                "{}",
+                name
+            );
+        }
+
+        let triangle = load("triangle.obj", b"v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1 2 3\n").unwrap();
+
+        assert_eq!(1, triangle.len());
+    }
+
+    #[test]
+    fn must_prefer_an_explicit_material_to_vertex_colors() {
+        let path = write("colored.ply", COLORED_PLY);
+        let material = grey_material();
+
+        let explicit = MeshFormat::Ply.load(&path, Some(material.clone())).unwrap();
+        assert!(Arc::ptr_eq(&material, &explicit[0].mesh.material()));
+
+        let colored = MeshFormat::Ply.load(&path, None).unwrap();
+        assert!(!Arc::ptr_eq(&material, &colored[0].mesh.material()));
+        assert!(colored[0].mesh.colors().is_some());
+    }
+}
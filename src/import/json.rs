@@ -0,0 +1,401 @@
+use super::{ParseError, Position};
+
+/// JSON value with the position where it starts, so that errors found after
+/// parsing can still point to the source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Json {
+    pub value: JsonValue,
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    /// Members in the order of the source
+    Object(Vec<(String, Json)>),
+}
+
+#[allow(dead_code)]
+impl Json {
+    pub const fn position(&self) -> Position {
+        Position::LineColumn(self.line, self.column)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match &self.value {
+            JsonValue::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Name of the type of the value, for error messages.
+    pub const fn kind(&self) -> &'static str {
+        match self.value {
+            JsonValue::Null => "null",
+            JsonValue::Bool(_) => "a boolean",
+            JsonValue::Number(_) => "a number",
+            JsonValue::String(_) => "a string",
+            JsonValue::Array(_) => "an array",
+            JsonValue::Object(_) => "an object",
+        }
+    }
+}
+
+pub fn parse_json(source: &str) -> Result<Json, ParseError> {
+    let mut parser = Parser {
+        chars: source.chars().collect(),
+        index: 0,
+        line: 1,
+        column: 1,
+    };
+
+    let value = parser.value()?;
+
+    parser.skip_whitespace();
+
+    if parser.peek().is_some() {
+        return Err(parser.error("unexpected content after the value"));
+    }
+
+    Ok(value)
+}
+
+struct Parser {
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+    column: usize,
+}
+
+impl Parser {
+    fn error<S: Into<String>>(&self, message: S) -> ParseError {
+        ParseError::at(Position::LineColumn(self.line, self.column), message)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+
+        self.index += 1;
+
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+
+        Some(c)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ParseError> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.next();
+                Ok(())
+            }
+            Some(c) => Err(self.error(format!("expected '{}', found '{}'", expected, c))),
+            None => Err(self.error(format!(
+                "expected '{}', found the end of the file",
+                expected
+            ))),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(' ' | '\t' | '\n' | '\r') = self.peek() {
+            self.next();
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, ParseError> {
+        self.skip_whitespace();
+
+        let (line, column) = (self.line, self.column);
+
+        let value = match self.peek() {
+            Some('{') => self.object()?,
+            Some('[') => self.array()?,
+            Some('"') => JsonValue::String(self.string()?),
+            Some('-' | '0'..='9') => JsonValue::Number(self.number()?),
+            Some('a'..='z') => self.literal()?,
+            Some(c) => return Err(self.error(format!("unexpected '{}'", c))),
+            None => return Err(self.error("expected a value, found the end of the file")),
+        };
+
+        Ok(Json {
+            value,
+            line,
+            column,
+        })
+    }
+
+    fn object(&mut self) -> Result<JsonValue, ParseError> {
+        self.expect('{')?;
+
+        let mut members: Vec<(String, Json)> = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.peek() == Some('}') {
+            self.next();
+            return Ok(JsonValue::Object(members));
+        }
+
+        loop {
+            self.skip_whitespace();
+
+            if self.peek() != Some('"') {
+                return Err(self.error("expected a member name"));
+            }
+
+            let (line, column) = (self.line, self.column);
+            let name = self.string()?;
+
+            if members.iter().any(|(other, _)| *other == name) {
+                return Err(ParseError::at(
+                    Position::LineColumn(line, column),
+                    format!("duplicate member '{}'", name),
+                ));
+            }
+
+            self.skip_whitespace();
+            self.expect(':')?;
+
+            let value = self.value()?;
+            members.push((name, value));
+
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => self.next(),
+                Some('}') => {
+                    self.next();
+                    return Ok(JsonValue::Object(members));
+                }
+                _ => return Err(self.error("expected ',' or '}' after a member")),
+            };
+        }
+    }
+
+    fn array(&mut self) -> Result<JsonValue, ParseError> {
+        self.expect('[')?;
+
+        let mut items = Vec::new();
+
+        self.skip_whitespace();
+
+        if self.peek() == Some(']') {
+            self.next();
+            return Ok(JsonValue::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(',') => self.next(),
+                Some(']') => {
+                    self.next();
+                    return Ok(JsonValue::Array(items));
+                }
+                _ => return Err(self.error("expected ',' or ']' after an item")),
+            };
+        }
+    }
+
+    fn string(&mut self) -> Result<String, ParseError> {
+        self.expect('"')?;
+
+        let mut string = String::new();
+
+        loop {
+            match self.next() {
+                Some('"') => return Ok(string),
+                Some('\\') => {
+                    let c = match self.next() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('/') => '/',
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => self.unicode_escape()?,
+                        _ => return Err(self.error("invalid escape sequence")),
+                    };
+
+                    string.push(c);
+                }
+                Some(c) if (c as u32) < 0x20 => {
+                    return Err(self.error("control character in string"));
+                }
+                Some(c) => string.push(c),
+                None => return Err(self.error("unterminated string")),
+            }
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32, ParseError> {
+        let mut value = 0;
+
+        for _ in 0..4 {
+            let digit = self
+                .next()
+                .and_then(|c| c.to_digit(16))
+                .ok_or_else(|| self.error("invalid unicode escape"))?;
+
+            value = value * 16 + digit;
+        }
+
+        Ok(value)
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, ParseError> {
+        let high = self.hex4()?;
+
+        let code = if (0xD800..0xDC00).contains(&high) {
+            // Surrogate pair
+            if self.next() != Some('\\') || self.next() != Some('u') {
+                return Err(self.error("missing low surrogate"));
+            }
+
+            let low = self.hex4()?;
+
+            if !(0xDC00..0xE000).contains(&low) {
+                return Err(self.error("invalid low surrogate"));
+            }
+
+            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+        } else {
+            high
+        };
+
+        char::from_u32(code).ok_or_else(|| self.error("invalid unicode escape"))
+    }
+
+    fn number(&mut self) -> Result<f64, ParseError> {
+        let start = self.index;
+        let (line, column) = (self.line, self.column);
+
+        while let Some('-' | '+' | '.' | 'e' | 'E' | '0'..='9') = self.peek() {
+            self.next();
+        }
+
+        let text: String = self.chars[start..self.index].iter().collect();
+
+        // Rust accepts a superset of the JSON grammar, e.g. "1." or "01"
+        let digits = text.strip_prefix('-').unwrap_or(&text);
+        let integer = digits.split(['.', 'e', 'E']).next().unwrap_or("");
+        let valid = !integer.is_empty()
+            && (integer == "0" || !integer.starts_with('0'))
+            && !digits.contains(".e")
+            && !digits.contains(".E")
+            && !digits.ends_with('.');
+
+        match text.parse::<f64>() {
+            Ok(number) if valid && number.is_finite() => Ok(number),
+            _ => Err(ParseError::at(
+                Position::LineColumn(line, column),
+                format!("invalid number '{}'", text),
+            )),
+        }
+    }
+
+    fn literal(&mut self) -> Result<JsonValue, ParseError> {
+        let (line, column) = (self.line, self.column);
+        let mut word = String::new();
+
+        while let Some(c @ 'a'..='z') = self.peek() {
+            word.push(c);
+            self.next();
+        }
+
+        match word.as_str() {
+            "null" => Ok(JsonValue::Null),
+            "true" => Ok(JsonValue::Bool(true)),
+            "false" => Ok(JsonValue::Bool(false)),
+            _ => Err(ParseError::at(
+                Position::LineColumn(line, column),
+                format!("unexpected '{}'", word),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(json: &Json) -> &JsonValue {
+        &json.value
+    }
+
+    #[test]
+    fn must_parse_values_with_positions() {
+        let json = parse_json("{\n  \"a\": [1, -2.5e1, true, null],\n  \"b\": \"x\\u00e9\\n\"\n}")
+            .unwrap();
+
+        let a = json.get("a").unwrap();
+
+        assert_eq!((2, 8), (a.line, a.column));
+
+        match value(a) {
+            JsonValue::Array(items) => {
+                assert_eq!(JsonValue::Number(1.0), items[0].value);
+                assert_eq!(JsonValue::Number(-25.0), items[1].value);
+                assert_eq!(JsonValue::Bool(true), items[2].value);
+                assert_eq!(JsonValue::Null, items[3].value);
+                assert_eq!((2, 12), (items[1].line, items[1].column));
+            }
+            _ => panic!("Expected an array"),
+        }
+
+        assert_eq!(
+            &JsonValue::String("x\u{e9}\n".to_string()),
+            value(json.get("b").unwrap())
+        );
+    }
+
+    #[test]
+    fn must_report_syntax_errors() {
+        let error = |source: &str| parse_json(source).unwrap_err();
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(3, 3),
+                "expected ',' or '}' after a member"
+            ),
+            error("{\n  \"a\": 1\n  \"b\": 2}")
+        );
+        assert_eq!(
+            ParseError::at(Position::LineColumn(1, 2), "invalid number '01'"),
+            error("[01]")
+        );
+        assert_eq!(
+            ParseError::at(Position::LineColumn(1, 10), "duplicate member 'a'"),
+            error("{\"a\": 1, \"a\": 2}")
+        );
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 4),
+                "unexpected content after the value"
+            ),
+            error("[] []")
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+
+use crate::core::color::Color;
+use crate::core::geometry::{Point3, Vec2, Vec3};
+use crate::materials::Material;
+use crate::scene::object::TriangleMesh;
+
+/// Mesh read from a file, with the name of the object it describes.
+pub struct ImportedMesh {
+    pub name: String,
+    pub mesh: TriangleMesh,
+}
+
+/// Indexed triangles and their optional vertex attributes, as read from a
+/// file.
+#[derive(Debug, Default, PartialEq)]
+pub struct MeshData {
+    pub positions: Vec<Point3>,
+    pub indices: Vec<[usize; 3]>,
+    pub normals: Option<Vec<Vec3>>,
+    pub texture_coords: Option<Vec<Vec2>>,
+    pub colors: Option<Vec<Color>>,
+}
+
+impl MeshData {
+    pub fn into_mesh(self, material: Arc<dyn Material>) -> TriangleMesh {
+        let mesh = TriangleMesh::new(
+            self.positions,
+            self.indices,
+            self.normals,
+            self.texture_coords,
+            material,
+        );
+
+        match self.colors {
+            Some(colors) => mesh.with_colors(colors),
+            None => mesh,
+        }
+    }
+}
@@ -0,0 +1,19 @@
+mod error;
+mod format;
+mod json;
+mod mesh;
+mod mtl;
+mod obj;
+mod ply;
+mod scene;
+mod stl;
+
+pub use error::*;
+pub use format::*;
+pub use json::*;
+pub use mesh::*;
+pub use mtl::*;
+pub use obj::*;
+pub use ply::*;
+pub use scene::*;
+pub use stl::*;
@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{ImportError, ParseError};
+use crate::core::color::Color;
+use crate::image::read_image;
+use crate::materials::{Dielectric, DiffuseLight, Lambertian, Material, Metal};
+use crate::textures::{ImageTexture, Texture};
+
+/// Materials of a library, by name.
+pub type MaterialLibrary = HashMap<String, Arc<dyn Material>>;
+
+/// Material statement of an MTL file, before it is mapped to one of the
+/// renderer materials.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MaterialDescription {
+    pub name: String,
+    /// `Kd`
+    pub diffuse: Color,
+    /// `Ks`
+    pub specular: Color,
+    /// `Ke`
+    pub emission: Color,
+    /// `Ns`, from 0 to 1000
+    pub shininess: f64,
+    /// `Ni`
+    pub refractive_index: f64,
+    /// `d`, or `1 - Tr`
+    pub dissolve: f64,
+    /// `illum`
+    pub illumination: u32,
+    /// `map_Kd`, with the line where it was given
+    pub diffuse_map: Option<(String, usize)>,
+}
+
+impl MaterialDescription {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            diffuse: Color(0.8, 0.8, 0.8),
+            specular: Color(0.0, 0.0, 0.0),
+            emission: Color(0.0, 0.0, 0.0),
+            shininess: 0.0,
+            refractive_index: 1.0,
+            dissolve: 1.0,
+            illumination: 2,
+            diffuse_map: None,
+        }
+    }
+
+    /// Maps the description to the closest material: emissive surfaces become
+    /// [`DiffuseLight`], transparent ones [`Dielectric`], mirror-like ones
+    /// [`Metal`] (rougher as `Ns` decreases) and anything else
+    /// [`Lambertian`].
+    pub fn build(&self, diffuse_map: Option<Arc<dyn Texture>>) -> Arc<dyn Material> {
+        let max = |color: Color| color.x().max(color.y()).max(color.z());
+
+        if max(self.emission) > 0.0 {
+            Arc::new(DiffuseLight::from_color(self.emission))
+        } else if self.dissolve < 1.0 || matches!(self.illumination, 4 | 6 | 7 | 9) {
+            let refractive_index = if self.refractive_index > 1.0 {
+                self.refractive_index
+            } else {
+                1.5
+            };
+
+            Arc::new(Dielectric::new(refractive_index))
+        } else if self.illumination == 3 || max(self.specular) > max(self.diffuse) {
+            // Phong exponent to roughness
+            let fuzz = (2.0 / (self.shininess + 2.0)).sqrt();
+
+            Arc::new(Metal::new(self.specular, fuzz))
+        } else {
+            match diffuse_map {
+                Some(texture) => Arc::new(Lambertian::new(texture)),
+                None => Arc::new(Lambertian::from_color(self.diffuse)),
+            }
+        }
+    }
+}
+
+pub fn parse_mtl<R: BufRead>(reader: R) -> Result<Vec<MaterialDescription>, ParseError> {
+    let mut materials: Vec<MaterialDescription> = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let number = index + 1;
+        let line = line.map_err(|error| ParseError::new(number, error.to_string()))?;
+
+        let mut tokens = statement_tokens(&line);
+
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        let arguments: Vec<&str> = tokens.collect();
+
+        if keyword == "newmtl" {
+            if arguments.is_empty() {
+                return Err(ParseError::new(number, "missing material name"));
+            }
+
+            materials.push(MaterialDescription::new(arguments.join(" ")));
+            continue;
+        }
+
+        let material = match materials.last_mut() {
+            Some(material) => material,
+            None if is_known(keyword) => {
+                return Err(ParseError::new(
+                    number,
+                    format!("'{}' before any 'newmtl'", keyword),
+                ))
+            }
+            None => continue,
+        };
+
+        match keyword {
+            "Kd" => material.diffuse = parse_color(number, keyword, &arguments)?,
+            "Ks" => material.specular = parse_color(number, keyword, &arguments)?,
+            "Ke" => material.emission = parse_color(number, keyword, &arguments)?,
+            "Ns" => material.shininess = parse_scalar(number, keyword, &arguments)?,
+            "Ni" => material.refractive_index = parse_scalar(number, keyword, &arguments)?,
+            "d" => material.dissolve = parse_scalar(number, keyword, &arguments)?,
+            "Tr" => material.dissolve = 1.0 - parse_scalar::<f64>(number, keyword, &arguments)?,
+            "illum" => {
+                material.illumination = parse_scalar::<u32>(number, keyword, &arguments)?;
+            }
+            "map_Kd" => {
+                // Options such as "-s 1 1 1" come before the file name
+                let file = arguments
+                    .last()
+                    .ok_or_else(|| ParseError::new(number, "missing texture file"))?;
+
+                material.diffuse_map = Some((file.to_string(), number));
+            }
+            // Other statements (Ka, map_Bump, ...) have no equivalent
+            _ => {}
+        }
+    }
+
+    Ok(materials)
+}
+
+/// Reads an MTL file, loading its textures relative to the file directory.
+pub fn load_mtl<P: AsRef<Path>>(path: P) -> Result<MaterialLibrary, ImportError> {
+    let path = path.as_ref();
+
+    let file = File::open(path).map_err(|error| ImportError::io(path, error))?;
+    let descriptions =
+        parse_mtl(BufReader::new(file)).map_err(|error| ImportError::parse(path, error))?;
+
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut textures: HashMap<String, Arc<dyn Texture>> = HashMap::new();
+    let mut library = MaterialLibrary::new();
+
+    for description in descriptions {
+        let texture = match &description.diffuse_map {
+            Some((file, line)) => match textures.get(file) {
+                Some(texture) => Some(texture.clone()),
+                None => {
+                    let file_path = directory.join(file);
+                    let image = read_image(&file_path).map_err(|error| {
+                        ImportError::parse(
+                            path,
+                            ParseError::new(*line, format!("cannot read '{}': {}", file, error)),
+                        )
+                    })?;
+
+                    let texture: Arc<dyn Texture> =
+                        Arc::new(ImageTexture::new(image).with_source(file_path));
+                    textures.insert(file.clone(), texture.clone());
+
+                    Some(texture)
+                }
+            },
+            None => None,
+        };
+
+        library.insert(description.name.clone(), description.build(texture));
+    }
+
+    Ok(library)
+}
+
+/// Tokens of an MTL or OBJ statement, up to the token starting a comment, so
+/// that names and paths may contain `#`.
+pub(super) fn statement_tokens(line: &str) -> impl Iterator<Item = &str> {
+    line.split_whitespace()
+        .take_while(|token| !token.starts_with('#'))
+}
+
+fn is_known(keyword: &str) -> bool {
+    matches!(
+        keyword,
+        "Kd" | "Ks" | "Ke" | "Ns" | "Ni" | "d" | "Tr" | "illum" | "map_Kd"
+    )
+}
+
+fn parse_scalar<T: std::str::FromStr>(
+    line: usize,
+    keyword: &str,
+    arguments: &[&str],
+) -> Result<T, ParseError> {
+    match arguments {
+        [value] => value.parse().map_err(|_| {
+            ParseError::new(line, format!("invalid number '{}' in '{}'", value, keyword))
+        }),
+        _ => Err(ParseError::new(
+            line,
+            format!("'{}' expects a single value", keyword),
+        )),
+    }
+}
+
+fn parse_color(line: usize, keyword: &str, arguments: &[&str]) -> Result<Color, ParseError> {
+    let values = arguments
+        .iter()
+        .map(|value| {
+            value.parse::<f64>().map_err(|_| {
+                ParseError::new(line, format!("invalid number '{}' in '{}'", value, keyword))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    match values.as_slice() {
+        [gray] => Ok(Color(*gray, *gray, *gray)),
+        [r, g, b] => Ok(Color(*r, *g, *b)),
+        _ => Err(ParseError::new(
+            line,
+            format!("'{}' expects one or three values", keyword),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::{Point3, Ray, Ray3, Vec2, Vec3};
+    use crate::core::time::TimeRay3;
+    use crate::scene::BasicHitRecord;
+    use crate::textures::SolidColor;
+
+    /// Emission of the material built from `source`, and the attenuation and
+    /// direction of a ray hitting it head-on once scattered.
+    fn build(
+        source: &str,
+        diffuse_map: Option<Arc<dyn Texture>>,
+    ) -> (Color, Option<(Color, Vec3)>) {
+        let materials = parse_mtl(source.as_bytes()).unwrap();
+        let material = materials[0].build(diffuse_map);
+
+        let ray = Ray3::new(Point3(0.0, 0.0, 1.0), Vec3(0.0, 0.0, -1.0));
+        let hit = BasicHitRecord::new(
+            Point3(0.0, 0.0, 0.0),
+            1.0,
+            Vec2(0.0, 0.0),
+            ray,
+            Vec3(0.0, 0.0, 1.0),
+        );
+
+        let emitted = material.emitted(&hit);
+        let scattered = material
+            .scatter(TimeRay3::new(ray.origin(), ray.direction(), 0.0), hit)
+            .map(|record| (record.attenuation, record.ray.direction()));
+
+        (emitted, scattered)
+    }
+
+    #[test]
+    fn must_parse_materials() {
+        let source = "\
+# Two materials
+newmtl red
+Kd 0.8 0.1 0.1
+Ns 10
+
+newmtl glass#2
+d 0.2 # mostly transparent
+Ni 1.45
+map_Kd -s 1 1 1 textures/glass#2.png
+";
+
+        let materials = parse_mtl(source.as_bytes()).unwrap();
+
+        assert_eq!(2, materials.len());
+        assert_eq!("red", materials[0].name);
+        assert_eq!("glass#2", materials[1].name);
+        assert_eq!(Color(0.8, 0.1, 0.1), materials[0].diffuse);
+        assert!((materials[0].shininess - 10.0).abs() < 1e-9);
+        assert!((materials[1].dissolve - 0.2).abs() < 1e-9);
+        assert_eq!(
+            Some(("textures/glass#2.png".to_string(), 9)),
+            materials[1].diffuse_map
+        );
+    }
+
+    #[test]
+    fn must_report_line_of_errors() {
+        let source = "newmtl a\nKd 1 2\n";
+
+        assert_eq!(
+            Err(ParseError::new(2, "'Kd' expects one or three values")),
+            parse_mtl(source.as_bytes())
+        );
+
+        let source = "Kd 1 1 1\n";
+
+        assert_eq!(
+            Err(ParseError::new(1, "'Kd' before any 'newmtl'")),
+            parse_mtl(source.as_bytes())
+        );
+
+        let source = "newmtl a\n\nNs shiny # 10\n";
+
+        assert_eq!(
+            Err(ParseError::new(3, "invalid number 'shiny' in 'Ns'")),
+            parse_mtl(source.as_bytes())
+        );
+    }
+
+    #[test]
+    fn must_build_lambertian_materials() {
+        let (emitted, scattered) = build("newmtl a\nKd 0.8 0.1 0.1\nKs 0.2 0.2 0.2\n", None);
+        let (attenuation, direction) = scattered.unwrap();
+
+        assert_eq!(Color(0.0, 0.0, 0.0), emitted);
+        assert_eq!(Color(0.8, 0.1, 0.1), attenuation);
+        assert!(direction.z() >= 0.0);
+
+        let texture: Arc<dyn Texture> = Arc::new(SolidColor::new(Color(0.1, 0.2, 0.3)));
+        let (_, scattered) = build("newmtl a\nKd 0.8 0.1 0.1\n", Some(texture));
+
+        assert_eq!(Color(0.1, 0.2, 0.3), scattered.unwrap().0);
+    }
+
+    #[test]
+    fn must_build_metal_materials() {
+        // Shiny surfaces reflect almost like mirrors
+        for source in &[
+            "newmtl a\nKd 0.1 0.1 0.1\nKs 0.9 0.8 0.7\nNs 1000\n",
+            "newmtl a\nKd 0.9 0.9 0.9\nKs 0.9 0.8 0.7\nNs 1000\nillum 3\n",
+        ] {
+            let (_, scattered) = build(source, None);
+            let (attenuation, direction) = scattered.unwrap();
+
+            assert_eq!(Color(0.9, 0.8, 0.7), attenuation);
+            assert!(direction.z() > 0.0);
+            assert!(direction.x().abs() < 0.05 && direction.y().abs() < 0.05);
+        }
+    }
+
+    #[test]
+    fn must_build_dielectric_materials() {
+        for source in &[
+            "newmtl a\nKd 0.8 0.1 0.1\nd 0.2\nNi 1.45\n",
+            "newmtl a\nKd 0.8 0.1 0.1\nTr 0.5\n",
+            "newmtl a\nKd 0.8 0.1 0.1\nKs 0.9 0.9 0.9\nillum 7\n",
+        ] {
+            let (emitted, scattered) = build(source, None);
+
+            assert_eq!(Color(0.0, 0.0, 0.0), emitted);
+            assert_eq!(Color(1.0, 1.0, 1.0), scattered.unwrap().0);
+        }
+    }
+
+    #[test]
+    fn must_build_emissive_materials() {
+        let (emitted, scattered) = build("newmtl a\nKe 4 4 4\nd 0.5\nillum 3\n", None);
+
+        assert_eq!(Color(4.0, 4.0, 4.0), emitted);
+        assert!(scattered.is_none());
+    }
+}
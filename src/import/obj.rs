@@ -0,0 +1,514 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::Arc;
+
+use super::mtl::statement_tokens;
+use super::{file_name, load_mtl, ImportError, ImportedMesh, MaterialLibrary, ParseError};
+use crate::core::geometry::{Point3, Vec2, Vec3};
+use crate::materials::Material;
+use crate::scene::object::TriangleMesh;
+
+/// Corner of a face, with 0-based indices into the vertex attributes.
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ObjCorner {
+    pub position: usize,
+    pub texture_coord: Option<usize>,
+    pub normal: Option<usize>,
+}
+
+/// Faces sharing the same object, group and material.
+#[derive(Debug, PartialEq)]
+pub struct ObjPart {
+    pub object: String,
+    pub group: String,
+    pub material: Option<String>,
+    pub triangles: Vec<[ObjCorner; 3]>,
+}
+
+/// Contents of an OBJ file, with polygons already triangulated.
+#[derive(Debug, Default, PartialEq)]
+pub struct ObjData {
+    pub positions: Vec<Point3>,
+    pub texture_coords: Vec<Vec2>,
+    pub normals: Vec<Vec3>,
+    pub parts: Vec<ObjPart>,
+    pub material_libraries: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl ObjData {
+    pub fn triangle_count(&self) -> usize {
+        self.parts.iter().map(|part| part.triangles.len()).sum()
+    }
+
+    /// Builds one mesh per part, named after its object and group. Parts
+    /// whose material is not in `materials` use `default_material`.
+    ///
+    /// Corners sharing the same attributes are merged into a single vertex.
+    /// Normals and texture coordinates are only kept when every corner of
+    /// the part has them.
+    pub fn into_meshes(
+        self,
+        materials: &MaterialLibrary,
+        default_material: Arc<dyn Material>,
+    ) -> Vec<ImportedMesh> {
+        let mut meshes = Vec::with_capacity(self.parts.len());
+
+        for part in &self.parts {
+            let corners = part.triangles.iter().flatten();
+
+            let has_normals = corners.clone().all(|corner| corner.normal.is_some());
+            let has_texture_coords = corners.clone().all(|corner| corner.texture_coord.is_some());
+
+            let mut vertices: HashMap<ObjCorner, usize> = HashMap::new();
+
+            let mut positions = Vec::new();
+            let mut normals = Vec::new();
+            let mut texture_coords = Vec::new();
+
+            let indices = part
+                .triangles
+                .iter()
+                .map(|triangle| {
+                    let mut indices = [0; 3];
+
+                    for (index, corner) in indices.iter_mut().zip(triangle) {
+                        *index = *vertices.entry(*corner).or_insert_with(|| {
+                            positions.push(self.positions[corner.position]);
+
+                            if let (true, Some(normal)) = (has_normals, corner.normal) {
+                                normals.push(self.normals[normal]);
+                            }
+
+                            if let (true, Some(coord)) = (has_texture_coords, corner.texture_coord)
+                            {
+                                texture_coords.push(self.texture_coords[coord]);
+                            }
+
+                            positions.len() - 1
+                        });
+                    }
+
+                    indices
+                })
+                .collect();
+
+            let material = part
+                .material
+                .as_ref()
+                .and_then(|name| materials.get(name))
+                .cloned()
+                .unwrap_or_else(|| default_material.clone());
+
+            let name = [part.object.as_str(), part.group.as_str()]
+                .iter()
+                .filter(|name| !name.is_empty())
+                .copied()
+                .collect::<Vec<_>>()
+                .join("/");
+
+            meshes.push(ImportedMesh {
+                name,
+                mesh: TriangleMesh::new(
+                    positions,
+                    indices,
+                    if has_normals { Some(normals) } else { None },
+                    if has_texture_coords {
+                        Some(texture_coords)
+                    } else {
+                        None
+                    },
+                    material,
+                ),
+            });
+        }
+
+        meshes
+    }
+
+    fn add_triangle(&mut self, key: (&str, &str, Option<&str>), triangle: [ObjCorner; 3]) {
+        let (object, group, material) = key;
+
+        let is_current = self.parts.last().map_or(false, |part| {
+            part.object == object && part.group == group && part.material.as_deref() == material
+        });
+
+        if !is_current {
+            self.parts.push(ObjPart {
+                object: object.to_string(),
+                group: group.to_string(),
+                material: material.map(str::to_string),
+                triangles: Vec::new(),
+            });
+        }
+
+        self.parts.last_mut().unwrap().triangles.push(triangle);
+    }
+}
+
+/// Parses the geometry of an OBJ file. Polygons are triangulated as fans
+/// around their first corner, so they are expected to be convex.
+///
+/// Statements other than `v`, `vt`, `vn`, `f`, `o`, `g`, `usemtl` and
+/// `mtllib` are ignored.
+pub fn parse_obj<R: BufRead>(reader: R) -> Result<ObjData, ParseError> {
+    let mut data = ObjData::default();
+
+    let mut object = String::new();
+    let mut group = String::new();
+    let mut material: Option<String> = None;
+
+    let mut lines = reader.lines().enumerate();
+
+    while let Some((index, line)) = lines.next() {
+        let number = index + 1;
+        let mut line = line.map_err(|error| ParseError::new(number, error.to_string()))?;
+
+        // A trailing backslash continues the statement on the next line
+        while line.ends_with('\\') {
+            line.pop();
+
+            match lines.next() {
+                Some((_, next)) => {
+                    line.push(' ');
+                    line += &next.map_err(|error| ParseError::new(number, error.to_string()))?;
+                }
+                None => break,
+            }
+        }
+
+        let mut tokens = statement_tokens(&line);
+
+        let keyword = match tokens.next() {
+            Some(keyword) => keyword,
+            None => continue,
+        };
+
+        let arguments: Vec<&str> = tokens.collect();
+
+        match keyword {
+            "v" => {
+                // Extra values (w or vertex colors) are ignored
+                let values = parse_numbers(number, keyword, &arguments, 3, 7)?;
+
+                data.positions.push(Point3(values[0], values[1], values[2]));
+            }
+            "vt" => {
+                let values = parse_numbers(number, keyword, &arguments, 1, 3)?;
+
+                data.texture_coords
+                    .push(Vec2(values[0], values.get(1).copied().unwrap_or(0.0)));
+            }
+            "vn" => {
+                let values = parse_numbers(number, keyword, &arguments, 3, 3)?;
+
+                data.normals.push(Vec3(values[0], values[1], values[2]));
+            }
+            "f" => {
+                if arguments.len() < 3 {
+                    return Err(ParseError::new(
+                        number,
+                        "a face needs at least three vertices",
+                    ));
+                }
+
+                let corners = arguments
+                    .iter()
+                    .map(|argument| parse_corner(number, argument, &data))
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                for pair in corners[1..].windows(2) {
+                    data.add_triangle(
+                        (&object, &group, material.as_deref()),
+                        [corners[0], pair[0], pair[1]],
+                    );
+                }
+            }
+            "o" => object = arguments.join(" "),
+            "g" => group = arguments.join(" "),
+            "usemtl" => {
+                if arguments.is_empty() {
+                    return Err(ParseError::new(number, "missing material name"));
+                }
+
+                material = Some(arguments.join(" "));
+            }
+            "mtllib" => {
+                if arguments.is_empty() {
+                    return Err(ParseError::new(number, "missing material library"));
+                }
+
+                data.material_libraries
+                    .extend(arguments.iter().map(|library| library.to_string()));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(data)
+}
+
+/// Reads an OBJ file and the material libraries it references, which are
+/// looked up relative to the file directory.
+pub fn load_obj<P: AsRef<Path>>(
+    path: P,
+    default_material: Arc<dyn Material>,
+) -> Result<Vec<ImportedMesh>, ImportError> {
+    let path = path.as_ref();
+
+    let file = File::open(path).map_err(|error| ImportError::io(path, error))?;
+    let data = parse_obj(BufReader::new(file)).map_err(|error| ImportError::parse(path, error))?;
+
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut materials = MaterialLibrary::new();
+
+    for library in &data.material_libraries {
+        materials.extend(load_mtl(directory.join(library))?);
+    }
+
+    let mut meshes = data.into_meshes(&materials, default_material);
+
+    for mesh in meshes.iter_mut().filter(|mesh| mesh.name.is_empty()) {
+        mesh.name = file_name(path);
+    }
+
+    Ok(meshes)
+}
+
+fn parse_numbers(
+    line: usize,
+    keyword: &str,
+    arguments: &[&str],
+    min: usize,
+    max: usize,
+) -> Result<Vec<f64>, ParseError> {
+    if arguments.len() < min || arguments.len() > max {
+        return Err(ParseError::new(
+            line,
+            format!(
+                "'{}' expects {} to {} values, found {}",
+                keyword,
+                min,
+                max,
+                arguments.len()
+            ),
+        ));
+    }
+
+    arguments
+        .iter()
+        .map(|value| {
+            value
+                .parse()
+                .ok()
+                .filter(|number: &f64| number.is_finite())
+                .ok_or_else(|| {
+                    ParseError::new(line, format!("invalid number '{}' in '{}'", value, keyword))
+                })
+        })
+        .collect()
+}
+
+/// Parses `p`, `p/t`, `p//n` or `p/t/n`.
+fn parse_corner(line: usize, argument: &str, data: &ObjData) -> Result<ObjCorner, ParseError> {
+    let mut indices = argument.split('/');
+
+    let position = indices.next().unwrap_or("");
+    let texture_coord = indices.next().filter(|index| !index.is_empty());
+    let normal = indices.next().filter(|index| !index.is_empty());
+
+    if indices.next().is_some() {
+        return Err(ParseError::new(
+            line,
+            format!("invalid face vertex '{}'", argument),
+        ));
+    }
+
+    let resolve = |index: &str, count: usize, kind: &str| -> Result<usize, ParseError> {
+        let value: i64 = index
+            .parse()
+            .map_err(|_| ParseError::new(line, format!("invalid {} index '{}'", kind, index)))?;
+
+        // Indices start at 1, negative ones are relative to the end
+        let resolved = match value {
+            value if value > 0 => value - 1,
+            value if value < 0 => count as i64 + value,
+            _ => -1,
+        };
+
+        if resolved < 0 || resolved >= count as i64 {
+            return Err(ParseError::new(
+                line,
+                format!(
+                    "{} index {} out of range (only {} defined)",
+                    kind, value, count
+                ),
+            ));
+        }
+
+        Ok(resolved as usize)
+    };
+
+    Ok(ObjCorner {
+        position: resolve(position, data.positions.len(), "vertex")?,
+        texture_coord: texture_coord
+            .map(|index| resolve(index, data.texture_coords.len(), "texture coordinate"))
+            .transpose()?,
+        normal: normal
+            .map(|index| resolve(index, data.normals.len(), "normal"))
+            .transpose()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::grey_material;
+
+    const QUAD: &str = "\
+mtllib quad.mtl
+o quad
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+g front
+usemtl red
+f 1/1/1 2/2/1 3/3/1 4/4/1
+g back
+f -1 -2 -3
+";
+
+    fn corner(position: usize, texture_coord: Option<usize>, normal: Option<usize>) -> ObjCorner {
+        ObjCorner {
+            position,
+            texture_coord,
+            normal,
+        }
+    }
+
+    #[test]
+    fn must_parse_and_triangulate_faces() {
+        let data = parse_obj(QUAD.as_bytes()).unwrap();
+
+        assert_eq!(4, data.positions.len());
+        assert_eq!(4, data.texture_coords.len());
+        assert_eq!(1, data.normals.len());
+        assert_eq!(vec!["quad.mtl".to_string()], data.material_libraries);
+
+        assert_eq!(2, data.parts.len());
+        assert_eq!(3, data.triangle_count());
+
+        let front = &data.parts[0];
+
+        assert_eq!(("quad", "front"), (&*front.object, &*front.group));
+        assert_eq!(Some("red"), front.material.as_deref());
+        assert_eq!(
+            [
+                corner(0, Some(0), Some(0)),
+                corner(2, Some(2), Some(0)),
+                corner(3, Some(3), Some(0))
+            ],
+            front.triangles[1]
+        );
+
+        let back = &data.parts[1];
+
+        assert_eq!("back", back.group);
+        assert_eq!(
+            [
+                corner(3, None, None),
+                corner(2, None, None),
+                corner(1, None, None)
+            ],
+            back.triangles[0]
+        );
+    }
+
+    #[test]
+    fn must_merge_shared_vertices() {
+        let data = parse_obj(QUAD.as_bytes()).unwrap();
+        let meshes = data.into_meshes(&MaterialLibrary::new(), grey_material());
+
+        assert_eq!(2, meshes.len());
+        assert_eq!("quad/front", meshes[0].name);
+
+        let front = &meshes[0].mesh;
+
+        assert_eq!(4, front.positions().len());
+        assert_eq!(&[[0, 1, 2], [0, 2, 3]], front.indices());
+        assert!(front.normals().is_some());
+        assert!(front.texture_coords().is_some());
+
+        let back = &meshes[1].mesh;
+
+        assert_eq!(3, back.positions().len());
+        assert!(back.normals().is_none());
+    }
+
+    #[test]
+    fn must_report_line_of_errors() {
+        let error = |source: &str| parse_obj(source.as_bytes()).unwrap_err();
+
+        assert_eq!(
+            ParseError::new(2, "invalid number 'x' in 'v'"),
+            error("v 0 0 0\nv 1 x 0\n")
+        );
+        assert_eq!(
+            ParseError::new(1, "invalid number 'nan' in 'v'"),
+            error("v nan 0 0\n")
+        );
+        assert_eq!(
+            ParseError::new(2, "invalid number '-inf' in 'vn'"),
+            error("v 0 0 0\nvn 0 -inf 0\n")
+        );
+        assert_eq!(
+            ParseError::new(2, "vertex index 2 out of range (only 1 defined)"),
+            error("v 0 0 0\nf 1 2 1\n")
+        );
+        assert_eq!(
+            ParseError::new(1, "vertex index 0 out of range (only 0 defined)"),
+            error("f 0 0 0\n")
+        );
+        assert_eq!(
+            ParseError::new(3, "a face needs at least three vertices"),
+            error("v 0 0 0\n\nf 1 1\n")
+        );
+        assert_eq!(
+            ParseError::new(1, "'vn' expects 3 to 3 values, found 2"),
+            error("vn 0 1\n")
+        );
+    }
+
+    #[test]
+    fn must_keep_hashes_inside_names() {
+        let source = "# comment\nmtllib parts#1.mtl\no part#1 # first part\ng side #2\nusemtl red#1\nv 0 0 0 # origin\nv 1 0 0\nv 0 1 0\nf 1 2 3\n";
+        let data = parse_obj(source.as_bytes()).unwrap();
+
+        assert_eq!(vec!["parts#1.mtl".to_string()], data.material_libraries);
+        assert_eq!(3, data.positions.len());
+
+        let part = &data.parts[0];
+
+        assert_eq!(("part#1", "side"), (&*part.object, &*part.group));
+        assert_eq!(Some("red#1"), part.material.as_deref());
+    }
+
+    #[test]
+    fn must_join_continued_lines() {
+        let data = parse_obj("v 1 \\\n 2 3\nv 0 0 0\n".as_bytes()).unwrap();
+
+        assert_eq!(
+            vec![Point3(1.0, 2.0, 3.0), Point3(0.0, 0.0, 0.0)],
+            data.positions
+        );
+    }
+}
@@ -0,0 +1,682 @@
+use std::io::{self, BufRead};
+
+use super::{MeshData, ParseError, Position};
+use crate::core::color::Color;
+use crate::core::geometry::{Point3, Vec2, Vec3};
+use crate::image::TransferFunction;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Encoding {
+    Ascii,
+    LittleEndian,
+    BigEndian,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum PropertyType {
+    Scalar(ScalarType),
+    List { count: ScalarType, item: ScalarType },
+}
+
+struct Property {
+    name: String,
+    kind: PropertyType,
+}
+
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+struct Header {
+    encoding: Encoding,
+    elements: Vec<Element>,
+    /// Number of lines, including `end_header`
+    lines: usize,
+}
+
+impl ScalarType {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "char" | "int8" => Some(Self::I8),
+            "uchar" | "uint8" => Some(Self::U8),
+            "short" | "int16" => Some(Self::I16),
+            "ushort" | "uint16" => Some(Self::U16),
+            "int" | "int32" => Some(Self::I32),
+            "uint" | "uint32" => Some(Self::U32),
+            "float" | "float32" => Some(Self::F32),
+            "double" | "float64" => Some(Self::F64),
+            _ => None,
+        }
+    }
+
+    const fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    /// Largest value of integer types, which maps colors to `[0, 1]`.
+    const fn color_scale(self) -> f64 {
+        match self {
+            Self::U8 => 255.0,
+            Self::U16 => 65535.0,
+            _ => 1.0,
+        }
+    }
+}
+
+/// Source of the property values, whatever the encoding of the file.
+enum Values<R> {
+    Ascii {
+        reader: R,
+        tokens: Vec<String>,
+        next: usize,
+        line: usize,
+    },
+    Binary {
+        reader: R,
+        big_endian: bool,
+        offset: u64,
+    },
+}
+
+impl<R: BufRead> Values<R> {
+    fn position(&self) -> Position {
+        match self {
+            Self::Ascii { line, .. } => Position::Line(*line),
+            Self::Binary { offset, .. } => Position::Byte(*offset),
+        }
+    }
+
+    fn read(&mut self, kind: ScalarType) -> Result<f64, ParseError> {
+        let position = self.position();
+
+        match self {
+            Self::Ascii {
+                reader,
+                tokens,
+                next,
+                line,
+            } => {
+                while *next == tokens.len() {
+                    let mut text = String::new();
+
+                    let read = reader
+                        .read_line(&mut text)
+                        .map_err(|error| ParseError::at(position, error.to_string()))?;
+
+                    if read == 0 {
+                        return Err(ParseError::at(position, "unexpected end of file"));
+                    }
+
+                    *line += 1;
+                    *tokens = text.split_whitespace().map(str::to_string).collect();
+                    *next = 0;
+                }
+
+                let token = &tokens[*next];
+                *next += 1;
+
+                token
+                    .parse()
+                    .map_err(|_| ParseError::new(*line, format!("invalid number '{}'", token)))
+            }
+            Self::Binary {
+                reader,
+                big_endian,
+                offset,
+            } => {
+                let mut bytes = [0; 8];
+                let size = kind.size();
+
+                reader
+                    .read_exact(&mut bytes[..size])
+                    .map_err(|error| match error.kind() {
+                        io::ErrorKind::UnexpectedEof => {
+                            ParseError::at(position, "unexpected end of file")
+                        }
+                        _ => ParseError::at(position, error.to_string()),
+                    })?;
+
+                *offset += size as u64;
+
+                if *big_endian {
+                    bytes[..size].reverse();
+                }
+
+                let value = match kind {
+                    ScalarType::I8 => f64::from(bytes[0] as i8),
+                    ScalarType::U8 => f64::from(bytes[0]),
+                    ScalarType::I16 => f64::from(i16::from_le_bytes([bytes[0], bytes[1]])),
+                    ScalarType::U16 => f64::from(u16::from_le_bytes([bytes[0], bytes[1]])),
+                    ScalarType::I32 => {
+                        f64::from(i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    }
+                    ScalarType::U32 => {
+                        f64::from(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    }
+                    ScalarType::F32 => {
+                        f64::from(f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    }
+                    ScalarType::F64 => f64::from_le_bytes(bytes),
+                };
+
+                Ok(value)
+            }
+        }
+    }
+
+    /// Reads a value that must be a finite number, such as a coordinate.
+    fn read_finite(&mut self, kind: ScalarType) -> Result<f64, ParseError> {
+        let value = self.read(kind)?;
+
+        if value.is_finite() {
+            return Ok(value);
+        }
+
+        let position = match self {
+            Self::Ascii { line, .. } => Position::Line(*line),
+            Self::Binary { offset, .. } => Position::Byte(*offset - kind.size() as u64),
+        };
+
+        Err(ParseError::at(
+            position,
+            format!("invalid number {}", value),
+        ))
+    }
+}
+
+/// Reads a PLY mesh, in ASCII or binary encoding.
+///
+/// Vertices must have `x`, `y` and `z` properties; `nx`/`ny`/`nz`, `u`/`v`
+/// (or `s`/`t`) and `red`/`green`/`blue` are read when present. Colors are
+/// taken as sRGB and converted to linear values. Faces are read from the
+/// `vertex_indices` list and triangulated as fans. Other elements and
+/// properties are skipped.
+pub fn parse_ply<R: BufRead>(mut reader: R) -> Result<MeshData, ParseError> {
+    let header = parse_header(&mut reader)?;
+
+    let mut values = match header.encoding {
+        Encoding::Ascii => Values::Ascii {
+            reader,
+            tokens: Vec::new(),
+            next: 0,
+            line: header.lines,
+        },
+        Encoding::LittleEndian | Encoding::BigEndian => Values::Binary {
+            reader,
+            big_endian: header.encoding == Encoding::BigEndian,
+            offset: 0,
+        },
+    };
+
+    let vertex_count = header
+        .elements
+        .iter()
+        .find(|element| element.name == "vertex")
+        .map_or(0, |element| element.count);
+
+    let mut data = MeshData::default();
+
+    for element in &header.elements {
+        match element.name.as_str() {
+            "vertex" => read_vertices(element, &mut values, &mut data)?,
+            "face" => read_faces(element, &mut values, vertex_count, &mut data)?,
+            _ => {
+                for _ in 0..element.count {
+                    for property in &element.properties {
+                        read_property(property.kind, &mut values)?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(data)
+}
+
+fn parse_header<R: BufRead>(reader: &mut R) -> Result<Header, ParseError> {
+    let mut encoding = None;
+    let mut elements: Vec<Element> = Vec::new();
+
+    let mut line = 0;
+
+    loop {
+        let mut bytes = Vec::new();
+
+        let read = reader
+            .read_until(b'\n', &mut bytes)
+            .map_err(|error| ParseError::new(line + 1, error.to_string()))?;
+
+        line += 1;
+
+        if read == 0 {
+            return Err(ParseError::new(line, "missing 'end_header'"));
+        }
+
+        let text = String::from_utf8_lossy(&bytes);
+        let tokens: Vec<&str> = text.split_whitespace().collect();
+
+        if line == 1 {
+            if tokens != ["ply"] {
+                return Err(ParseError::new(line, "not a PLY file"));
+            }
+
+            continue;
+        }
+
+        match tokens.as_slice() {
+            ["format", name, _version] => {
+                encoding = Some(match *name {
+                    "ascii" => Encoding::Ascii,
+                    "binary_little_endian" => Encoding::LittleEndian,
+                    "binary_big_endian" => Encoding::BigEndian,
+                    _ => return Err(ParseError::new(line, format!("unknown format '{}'", name))),
+                });
+            }
+            ["element", name, count] => {
+                let count = count.parse().map_err(|_| {
+                    ParseError::new(line, format!("invalid element count '{}'", count))
+                })?;
+
+                elements.push(Element {
+                    name: name.to_string(),
+                    count,
+                    properties: Vec::new(),
+                });
+            }
+            ["property", rest @ ..] => {
+                let element = elements
+                    .last_mut()
+                    .ok_or_else(|| ParseError::new(line, "property declared before any element"))?;
+
+                let scalar = |name: &str| {
+                    ScalarType::from_name(name).ok_or_else(|| {
+                        ParseError::new(line, format!("unknown property type '{}'", name))
+                    })
+                };
+
+                let (kind, name) = match rest {
+                    ["list", count, item, name] => (
+                        PropertyType::List {
+                            count: scalar(count)?,
+                            item: scalar(item)?,
+                        },
+                        name,
+                    ),
+                    [kind, name] => (PropertyType::Scalar(scalar(kind)?), name),
+                    _ => return Err(ParseError::new(line, "invalid property declaration")),
+                };
+
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    kind,
+                });
+            }
+            ["end_header"] => break,
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            [keyword, ..] => {
+                return Err(ParseError::new(
+                    line,
+                    format!("unexpected '{}' in header", keyword),
+                ))
+            }
+        }
+    }
+
+    let encoding = encoding.ok_or_else(|| ParseError::new(line, "missing 'format'"))?;
+
+    Ok(Header {
+        encoding,
+        elements,
+        lines: line,
+    })
+}
+
+fn read_property<R: BufRead>(
+    kind: PropertyType,
+    values: &mut Values<R>,
+) -> Result<Vec<f64>, ParseError> {
+    match kind {
+        PropertyType::Scalar(scalar) => Ok(vec![values.read(scalar)?]),
+        PropertyType::List { count, item } => {
+            let position = values.position();
+            let count = values.read(count)?;
+
+            if count < 0.0 || count.fract() != 0.0 {
+                return Err(ParseError::at(
+                    position,
+                    format!("invalid list length {}", count),
+                ));
+            }
+
+            (0..count as usize).map(|_| values.read(item)).collect()
+        }
+    }
+}
+
+fn read_vertices<R: BufRead>(
+    element: &Element,
+    values: &mut Values<R>,
+    data: &mut MeshData,
+) -> Result<(), ParseError> {
+    // Only scalar properties are vertex attributes
+    let find = |names: &[&str]| {
+        element.properties.iter().position(|property| {
+            names.contains(&property.name.as_str())
+                && matches!(property.kind, PropertyType::Scalar(_))
+        })
+    };
+
+    let find_all = |names: &[&[&str]]| {
+        names
+            .iter()
+            .map(|names| find(names))
+            .collect::<Option<Vec<_>>>()
+    };
+
+    let position = find_all(&[&["x"], &["y"], &["z"]]).ok_or_else(|| {
+        ParseError::at(
+            values.position(),
+            "vertices need 'x', 'y' and 'z' properties",
+        )
+    })?;
+
+    let normal = find_all(&[&["nx"], &["ny"], &["nz"]]);
+    let texture_coord = find_all(&[&["u", "s", "texture_u"], &["v", "t", "texture_v"]]);
+    let color = find_all(&[&["red"], &["green"], &["blue"]]);
+
+    let color_scales = color.as_ref().map(|indices| {
+        indices
+            .iter()
+            .map(|&index| match element.properties[index].kind {
+                PropertyType::Scalar(kind) => kind.color_scale(),
+                PropertyType::List { .. } => unreachable!("Colors are scalar properties"),
+            })
+            .collect::<Vec<_>>()
+    });
+
+    let mut normals = Vec::new();
+    let mut texture_coords = Vec::new();
+    let mut colors = Vec::new();
+
+    // Coordinates must be finite, other properties are read as they are
+    let coordinates: Vec<usize> = position
+        .iter()
+        .chain(normal.iter().flatten())
+        .chain(texture_coord.iter().flatten())
+        .copied()
+        .collect();
+
+    for _ in 0..element.count {
+        let mut scalars = Vec::with_capacity(element.properties.len());
+
+        for (index, property) in element.properties.iter().enumerate() {
+            let value = match property.kind {
+                PropertyType::Scalar(kind) if coordinates.contains(&index) => {
+                    values.read_finite(kind)?
+                }
+                kind => read_property(kind, values)?.first().copied().unwrap_or(0.0),
+            };
+
+            scalars.push(value);
+        }
+
+        let get = |indices: &[usize]| -> Vec<f64> {
+            indices.iter().map(|&index| scalars[index]).collect()
+        };
+
+        let p = get(&position);
+        data.positions.push(Point3(p[0], p[1], p[2]));
+
+        if let Some(indices) = &normal {
+            let n = get(indices);
+            normals.push(Vec3(n[0], n[1], n[2]));
+        }
+
+        if let Some(indices) = &texture_coord {
+            let uv = get(indices);
+            texture_coords.push(Vec2(uv[0], uv[1]));
+        }
+
+        if let (Some(indices), Some(scales)) = (&color, &color_scales) {
+            let c: Vec<f64> = get(indices)
+                .iter()
+                .zip(scales)
+                .map(|(value, scale)| TransferFunction::Srgb.decode(value / scale))
+                .collect();
+
+            colors.push(Color(c[0], c[1], c[2]));
+        }
+    }
+
+    data.normals = normal.map(|_| normals);
+    data.texture_coords = texture_coord.map(|_| texture_coords);
+    data.colors = color.map(|_| colors);
+
+    Ok(())
+}
+
+fn read_faces<R: BufRead>(
+    element: &Element,
+    values: &mut Values<R>,
+    vertex_count: usize,
+    data: &mut MeshData,
+) -> Result<(), ParseError> {
+    let indices_property = element
+        .properties
+        .iter()
+        .position(|property| {
+            matches!(property.name.as_str(), "vertex_indices" | "vertex_index")
+                && matches!(property.kind, PropertyType::List { .. })
+        })
+        .ok_or_else(|| ParseError::at(values.position(), "faces need a 'vertex_indices' list"))?;
+
+    for _ in 0..element.count {
+        for (index, property) in element.properties.iter().enumerate() {
+            let list = read_property(property.kind, values)?;
+
+            if index != indices_property {
+                continue;
+            }
+
+            let position = values.position();
+
+            if list.len() < 3 {
+                return Err(ParseError::at(
+                    position,
+                    "a face needs at least three vertices",
+                ));
+            }
+
+            let corners = list
+                .iter()
+                .map(|&value| {
+                    if value < 0.0 || value.fract() != 0.0 || value as usize >= vertex_count {
+                        Err(ParseError::at(
+                            position,
+                            format!(
+                                "vertex index {} out of range ({} vertices)",
+                                value, vertex_count
+                            ),
+                        ))
+                    } else {
+                        Ok(value as usize)
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            for pair in corners[1..].windows(2) {
+                data.indices.push([corners[0], pair[0], pair[1]]);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EPSILON: f64 = 1e-6;
+
+    const HEADER: &str = "\
+element vertex 4
+property float x
+property float y
+property float z
+property uchar red
+property uchar green
+property uchar blue
+element face 1
+property list uchar int vertex_indices
+end_header
+";
+
+    fn binary(big_endian: bool) -> Vec<u8> {
+        let format = if big_endian {
+            "binary_big_endian"
+        } else {
+            "binary_little_endian"
+        };
+
+        let mut bytes = format!("ply\nformat {} 1.0\n{}", format, HEADER).into_bytes();
+
+        let vertices = [
+            [0.0_f32, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+
+        for (index, vertex) in vertices.iter().enumerate() {
+            for value in vertex {
+                bytes.extend(if big_endian {
+                    value.to_be_bytes()
+                } else {
+                    value.to_le_bytes()
+                });
+            }
+
+            bytes.extend([255 * (index == 1) as u8, 0, 255]);
+        }
+
+        bytes.push(4);
+
+        for index in 0..4_i32 {
+            bytes.extend(if big_endian {
+                index.to_be_bytes()
+            } else {
+                index.to_le_bytes()
+            });
+        }
+
+        bytes
+    }
+
+    fn assert_square(data: &MeshData) {
+        assert_eq!(4, data.positions.len());
+        assert_eq!(Point3(1.0, 1.0, 0.0), data.positions[2]);
+        assert_eq!(vec![[0, 1, 2], [0, 2, 3]], data.indices);
+
+        let colors = data.colors.as_ref().unwrap();
+
+        assert!((colors[1].x() - 1.0).abs() < EPSILON);
+        assert!(colors[0].x().abs() < EPSILON);
+        assert!((colors[0].z() - 1.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn must_read_ascii() {
+        let source = format!(
+            "ply\nformat ascii 1.0\ncomment square\n{}\
+0 0 0 0 0 255\n1 0 0 255 0 255\n1 1 0 0 0 255\n0 1 0 0 0 255\n4 0 1 2 3\n",
+            HEADER
+        );
+
+        let data = parse_ply(source.as_bytes()).unwrap();
+
+        assert_square(&data);
+        assert!(data.normals.is_none());
+    }
+
+    #[test]
+    fn must_read_binary() {
+        assert_square(&parse_ply(binary(false).as_slice()).unwrap());
+        assert_square(&parse_ply(binary(true).as_slice()).unwrap());
+    }
+
+    #[test]
+    fn must_report_errors() {
+        assert_eq!(
+            Err(ParseError::new(4, "unknown property type 'float16'")),
+            parse_ply("ply\nformat ascii 1.0\nelement vertex 1\nproperty float16 x\n".as_bytes())
+        );
+
+        let source = format!(
+            "ply\nformat ascii 1.0\n{}0 0 0 0 0 0\n0 0 0 0 0 0\n0 0 0 0 0 0\n0 0 0 0 0 0\n3 0 1 4\n",
+            HEADER
+        );
+
+        assert_eq!(
+            Err(ParseError::new(
+                17,
+                "vertex index 4 out of range (4 vertices)"
+            )),
+            parse_ply(source.as_bytes())
+        );
+
+        let mut bytes = binary(false);
+        let length = bytes.len();
+        bytes.truncate(length - 2);
+
+        assert_eq!(
+            Err(ParseError::at(
+                Position::Byte(4 * 15 + 1 + 12),
+                "unexpected end of file"
+            )),
+            parse_ply(bytes.as_slice())
+        );
+    }
+
+    #[test]
+    fn must_reject_non_finite_coordinates() {
+        let source = format!(
+            "ply\nformat ascii 1.0\n{}0 0 0 0 0 0\n0 inf 0 0 0 0\n0 0 0 0 0 0\n0 0 0 0 0 0\n3 0 1 2\n",
+            HEADER
+        );
+
+        assert_eq!(
+            Err(ParseError::new(14, "invalid number inf")),
+            parse_ply(source.as_bytes())
+        );
+
+        // NaN as the y coordinate of the second vertex
+        let mut bytes = binary(false);
+        let start = bytes.len() - (4 * 15 + 1 + 4 * 4) + 15 + 4;
+        bytes[start..start + 4].copy_from_slice(&f32::NAN.to_le_bytes());
+
+        assert_eq!(
+            Err(ParseError::at(Position::Byte(15 + 4), "invalid number NaN")),
+            parse_ply(bytes.as_slice())
+        );
+    }
+}
@@ -0,0 +1,2143 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{
+    parse_json, ImportError, Json, JsonValue, MeshFormat, ParseError, DEFAULT_MESH_ALBEDO,
+};
+use crate::core::geometry::{Matrix4, Quaternion, Transform, Vec2, Vec3, Vector};
+use crate::core::math::easing::Easing;
+use crate::core::time::{Interval, Keyframe, Pose, TransformTrack};
+use crate::image::read_image;
+use crate::materials::{Dielectric, DiffuseLight, Lambertian, Material, Metal};
+use crate::scene::camera::{
+    ApertureMask, ApertureShape, Bokeh, Convergence, CubeFace, FisheyeMapping, Focus, LensElement,
+    LensPrescription, Projection, RealisticLens, StereoRig,
+};
+use crate::scene::object::movingsphere::MovingSphere;
+use crate::scene::object::sphere::Sphere;
+use crate::scene::object::{
+    Animated, AxisRect, BvhOptions, Cuboid, Instance, Plane, Quad, Transformed, Triangle, BVH,
+};
+use crate::scene::{Background, Hit, HitList, Shutter, ShutterCurve};
+use crate::scenes::{Animation, AnimationOptions, ImageSettings, Scene, View, ViewKeyframe};
+use crate::textures::{Checker, ImageTexture, SolidColor, Texture, VertexColor};
+
+/// Value of a scene file, with the path of the field holding it, such as
+/// `objects[2].center`.
+#[derive(Clone)]
+struct Field<'a> {
+    json: &'a Json,
+    path: String,
+}
+
+impl<'a> Field<'a> {
+    fn error<M: Display>(&self, message: M) -> ParseError {
+        let message = if self.path.is_empty() {
+            message.to_string()
+        } else {
+            format!("{}: {}", self.path, message)
+        };
+
+        ParseError::at(self.json.position(), message)
+    }
+
+    fn expected(&self, kind: &str) -> ParseError {
+        self.error(format!("expected {}, found {}", kind, self.json.kind()))
+    }
+
+    fn child(&self, json: &'a Json, name: &str) -> Self {
+        let path = if self.path.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}.{}", self.path, name)
+        };
+
+        Self { json, path }
+    }
+
+    fn members(&self) -> Result<Vec<(&'a str, Self)>, ParseError> {
+        match &self.json.value {
+            JsonValue::Object(members) => Ok(members
+                .iter()
+                .map(|(name, json)| (name.as_str(), self.child(json, name)))
+                .collect()),
+            _ => Err(self.expected("an object")),
+        }
+    }
+
+    /// Checks that the object only has the `allowed` members, to catch typos.
+    fn check_members(&self, allowed: &[&str]) -> Result<(), ParseError> {
+        for (name, member) in self.members()? {
+            if !allowed.contains(&name) {
+                return Err(member.error(format!(
+                    "unknown field (expected one of {})",
+                    allowed.join(", ")
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn optional(&self, name: &str) -> Result<Option<Self>, ParseError> {
+        Ok(self
+            .members()?
+            .into_iter()
+            .find(|(member, _)| *member == name)
+            .map(|(_, field)| field))
+    }
+
+    fn required(&self, name: &str) -> Result<Self, ParseError> {
+        self.optional(name)?
+            .ok_or_else(|| self.error(format!("missing field '{}'", name)))
+    }
+
+    fn items(&self) -> Result<Vec<Self>, ParseError> {
+        match &self.json.value {
+            JsonValue::Array(items) => Ok(items
+                .iter()
+                .enumerate()
+                .map(|(index, json)| Self {
+                    json,
+                    path: format!("{}[{}]", self.path, index),
+                })
+                .collect()),
+            _ => Err(self.expected("an array")),
+        }
+    }
+
+    fn number(&self) -> Result<f64, ParseError> {
+        match self.json.value {
+            JsonValue::Number(number) => Ok(number),
+            _ => Err(self.expected("a number")),
+        }
+    }
+
+    fn number_where(
+        &self,
+        condition: impl FnOnce(f64) -> bool,
+        requirement: &str,
+    ) -> Result<f64, ParseError> {
+        let number = self.number()?;
+
+        if condition(number) {
+            Ok(number)
+        } else {
+            Err(self.error(format!("{} {}", requirement, number)))
+        }
+    }
+
+    fn count(&self, min: u32) -> Result<u32, ParseError> {
+        let number = self.number()?;
+
+        if number.fract() == 0.0 && number >= min as f64 && number <= u32::MAX as f64 {
+            Ok(number as u32)
+        } else {
+            Err(self.error(format!("expected an integer of at least {}", min)))
+        }
+    }
+
+    fn boolean(&self) -> Result<bool, ParseError> {
+        match self.json.value {
+            JsonValue::Bool(value) => Ok(value),
+            _ => Err(self.expected("a boolean")),
+        }
+    }
+
+    fn string(&self) -> Result<&'a str, ParseError> {
+        match &self.json.value {
+            JsonValue::String(string) => Ok(string),
+            _ => Err(self.expected("a string")),
+        }
+    }
+
+    fn numbers<const N: usize>(&self) -> Result<[f64; N], ParseError> {
+        let items = self.items()?;
+
+        if items.len() != N {
+            return Err(self.error(format!("expected {} numbers, found {}", N, items.len())));
+        }
+
+        let mut numbers = [0.0; N];
+
+        for (number, item) in numbers.iter_mut().zip(&items) {
+            *number = item.number()?;
+        }
+
+        Ok(numbers)
+    }
+
+    fn vector(&self) -> Result<Vec3, ParseError> {
+        let [x, y, z] = self.numbers()?;
+
+        Ok(Vec3(x, y, z))
+    }
+
+    fn range(&self) -> Result<(f64, f64), ParseError> {
+        let [start, end] = self.numbers()?;
+
+        Ok((start, end))
+    }
+}
+
+/// Textures and materials of a scene file, by id.
+struct Library<'a> {
+    directory: &'a Path,
+    textures: HashMap<String, Arc<dyn Texture>>,
+    materials: HashMap<String, Arc<dyn Material>>,
+    /// Options of the hierarchies of mesh files
+    bvh: &'a BvhOptions,
+    /// Hierarchies of the mesh files already loaded, by file
+    meshes: RefCell<HashMap<String, Arc<dyn Hit>>>,
+}
+
+/// Builds a scene from the JSON description in `source`. Files referenced by
+/// the scene (images and meshes) are looked up relative to `directory`.
+///
+/// Textures and materials are declared once, by id, in the `textures` and
+/// `materials` objects; a texture can only reference the textures declared
+/// before it. Errors report the line, the column and the path of the field.
+#[allow(dead_code)]
+pub fn parse_scene(source: &str, directory: &Path) -> Result<Scene, ParseError> {
+    parse_scene_with(
+        source,
+        directory,
+        &BvhOptions::default(),
+        &AnimationOptions::default(),
+    )
+}
+
+/// Builds a scene like [`parse_scene`], with the hierarchies of mesh files
+/// built with `bvh` and the `animation` options applied over the animation
+/// of the scene.
+pub fn parse_scene_with(
+    source: &str,
+    directory: &Path,
+    bvh: &BvhOptions,
+    animation: &AnimationOptions,
+) -> Result<Scene, ParseError> {
+    let json = parse_json(source)?;
+    let root = Field {
+        json: &json,
+        path: String::new(),
+    };
+
+    root.check_members(&[
+        "camera",
+        "time",
+        "image",
+        "background",
+        "textures",
+        "materials",
+        "objects",
+        "bvh",
+        "animation",
+    ])?;
+
+    let camera = match root.optional("camera")? {
+        Some(camera) => parse_camera(&camera, directory)?,
+        None => CameraSection::default(),
+    };
+
+    let animation = animation.apply(match root.optional("animation")? {
+        Some(animation) => Some(parse_animation(&animation)?),
+        None => None,
+    });
+
+    let time = match (root.optional("time")?, animation) {
+        (Some(time), _) => parse_interval(&time)?,
+        (None, Some(animation)) => animation.span(),
+        (None, None) => Interval::new(0.0, 1.0),
+    };
+
+    let image = match root.optional("image")? {
+        Some(image) => parse_image(&image)?,
+        None => ImageSettings::default(),
+    };
+
+    let background = match root.optional("background")? {
+        Some(background) => parse_background(&background)?,
+        None => Background::sky(),
+    };
+
+    let mut library = Library {
+        directory,
+        textures: HashMap::new(),
+        materials: HashMap::new(),
+        bvh,
+        meshes: RefCell::new(HashMap::new()),
+    };
+
+    if let Some(textures) = root.optional("textures")? {
+        for (id, texture) in textures.members()? {
+            let value = library.parse_texture(&texture)?;
+
+            library.textures.insert(id.to_string(), value);
+        }
+    }
+
+    if let Some(materials) = root.optional("materials")? {
+        for (id, material) in materials.members()? {
+            let value = library.parse_material(&material)?;
+
+            library.materials.insert(id.to_string(), value);
+        }
+    }
+
+    let mut objects: Vec<Box<dyn Hit>> = Vec::new();
+
+    if let Some(list) = root.optional("objects")? {
+        for object in list.items()? {
+            objects.extend(library.parse_object(&object, time)?);
+        }
+    }
+
+    let use_bvh = match root.optional("bvh")? {
+        Some(bvh) => bvh.boolean()?,
+        None => true,
+    };
+
+    let mut world = HitList::new();
+
+    if use_bvh && !objects.is_empty() {
+        let objects: Vec<Arc<dyn Hit>> = objects.into_iter().map(Arc::from).collect();
+
+        // Objects are bounded over all the frames to render
+        let bounds = animation.map_or(time, |animation| animation.span());
+
+        world.add(Box::new(BVH::from_objects(&objects, bounds)));
+    } else {
+        for object in objects {
+            world.add(object);
+        }
+    }
+
+    Ok(Scene {
+        world,
+        background,
+        view: camera.view,
+        image,
+        time,
+        animation,
+        camera_keyframes: camera.keyframes,
+        shutter: camera.shutter,
+        bokeh: camera.bokeh,
+        stereo: camera.stereo,
+        focus: camera.focus,
+    })
+}
+
+/// Reads a scene file, with the hierarchies of mesh files built with `bvh`
+/// and the `animation` options applied; see [`parse_scene`].
+pub fn load_scene<P: AsRef<Path>>(
+    path: P,
+    bvh: &BvhOptions,
+    animation: &AnimationOptions,
+) -> Result<Scene, ImportError> {
+    let path = path.as_ref();
+
+    let source = fs::read_to_string(path).map_err(|error| ImportError::io(path, error))?;
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    parse_scene_with(&source, directory, bvh, animation)
+        .map_err(|error| ImportError::parse(path, error))
+}
+
+const PLACEMENT_MEMBERS: [&str; 3] = ["look_from", "look_at", "up"];
+
+/// Members of the thin lens of perspective cameras.
+const LENS_MEMBERS: [&str; 5] = [
+    "vertical_fov",
+    "aperture",
+    "focus_distance",
+    "tilt",
+    "shift",
+];
+
+/// Members of the camera of a scene file.
+#[derive(Default)]
+struct CameraSection {
+    view: View,
+    keyframes: Vec<ViewKeyframe>,
+    shutter: Shutter,
+    bokeh: Bokeh,
+    stereo: Option<StereoRig>,
+    focus: Option<Focus>,
+}
+
+/// Reads the view of the camera, its optional `keyframes`, whose missing
+/// members keep the values of the camera, its shutter, its bokeh, with an
+/// aperture mask or a lens file relative to `directory`, and its stereo
+/// rig. Keyframes
+/// cannot change the projection.
+fn parse_camera(camera: &Field, directory: &Path) -> Result<CameraSection, ParseError> {
+    let (projection, parameters) = parse_projection(camera, directory)?;
+
+    let mut view_members = PLACEMENT_MEMBERS.to_vec();
+
+    match projection {
+        Projection::Perspective => view_members.extend_from_slice(&LENS_MEMBERS),
+        Projection::Realistic(_) => view_members.push("focus_distance"),
+        _ => {}
+    }
+
+    let mut members = view_members.clone();
+    members.extend_from_slice(&["type", "keyframes", "shutter", "stereo"]);
+    members.extend_from_slice(parameters);
+
+    if projection == Projection::Perspective {
+        members.push("bokeh");
+    }
+
+    if view_members.contains(&"focus_distance") {
+        members.extend_from_slice(&["focus_on", "autofocus"]);
+    }
+
+    camera.check_members(&members)?;
+
+    let view = View {
+        projection,
+        ..parse_view(camera, View::default())?
+    };
+
+    let shutter = match camera.optional("shutter")? {
+        Some(shutter) => parse_shutter(&shutter)?,
+        None => Shutter::default(),
+    };
+
+    let bokeh = match camera.optional("bokeh")? {
+        Some(bokeh) => parse_bokeh(&bokeh, directory)?,
+        None => Bokeh::default(),
+    };
+
+    let focus = parse_focus(camera)?;
+
+    let stereo = match camera.optional("stereo")? {
+        Some(stereo) => Some(parse_stereo(&stereo, &view.projection)?),
+        None => None,
+    };
+
+    let items = match camera.optional("keyframes")? {
+        Some(keyframes) => keyframes.items()?,
+        None => Vec::new(),
+    };
+
+    let mut members = view_members;
+    members.extend_from_slice(&["time", "easing"]);
+
+    let mut keyframes: Vec<ViewKeyframe> = Vec::with_capacity(items.len());
+
+    for item in &items {
+        item.check_members(&members)?;
+
+        let time = parse_keyframe_time(item, keyframes.last().map(|keyframe| keyframe.time))?;
+
+        keyframes.push(ViewKeyframe {
+            time,
+            view: parse_view(item, view.clone())?,
+            easing: parse_easing(item)?,
+        });
+    }
+
+    Ok(CameraSection {
+        view,
+        keyframes,
+        shutter,
+        bokeh,
+        stereo,
+        focus,
+    })
+}
+
+/// Reads the point the camera focuses on, or whether it focuses on what it
+/// sees along its axis.
+fn parse_focus(camera: &Field) -> Result<Option<Focus>, ParseError> {
+    match (camera.optional("focus_on")?, camera.optional("autofocus")?) {
+        (Some(_), Some(autofocus)) if autofocus.boolean()? => {
+            Err(autofocus.error("expected either a point to focus on or autofocus, not both"))
+        }
+        (Some(point), _) => Ok(Some(Focus::Point(point.vector()?))),
+        (None, Some(autofocus)) if autofocus.boolean()? => Ok(Some(Focus::Auto)),
+        _ => Ok(None),
+    }
+}
+
+/// Reads the aperture `shape` of a bokeh, with its parameters, and its
+/// `cat_eye` vignetting.
+fn parse_bokeh(bokeh: &Field, directory: &Path) -> Result<Bokeh, ParseError> {
+    let shape_field = bokeh.required("shape")?;
+    let name = shape_field.string()?;
+
+    let members: &[&str] = match name {
+        "circle" => &["shape", "cat_eye"],
+        "polygon" => &["shape", "blades", "rotation", "cat_eye"],
+        "mask" => &["shape", "file", "cat_eye"],
+        _ => {
+            return Err(shape_field.error(format!(
+                "unknown shape '{}' (expected one of {})",
+                name,
+                ApertureShape::NAMES.join(", ")
+            )))
+        }
+    };
+
+    bokeh.check_members(members)?;
+
+    let shape = match name {
+        "circle" => ApertureShape::Circle,
+        "polygon" => ApertureShape::Polygon {
+            blades: bokeh.required("blades")?.count(3)?,
+            rotation: match bokeh.optional("rotation")? {
+                Some(rotation) => rotation.number()?,
+                None => 0.0,
+            },
+        },
+        _ => {
+            let file = bokeh.required("file")?;
+            let name = file.string()?;
+
+            let image = read_image(directory.join(name))
+                .map_err(|error| file.error(format!("cannot read '{}': {}", name, error)))?;
+
+            let mask = ApertureMask::new(&image)
+                .map_err(|reason| file.error(reason))?
+                .with_source(name);
+
+            ApertureShape::Mask(Arc::new(mask))
+        }
+    };
+
+    let mut result = Bokeh::new(shape).map_err(|reason| bokeh.error(reason))?;
+
+    if let Some(cat_eye) = bokeh.optional("cat_eye")? {
+        result = result.with_cat_eye(cat_eye.number_where(
+            |cat_eye| (0.0..=1.0).contains(&cat_eye),
+            "expected a fraction from 0 to 1, found",
+        )?);
+    }
+
+    Ok(result)
+}
+
+/// Reads the `interaxial` distance and the `convergence` of a stereo rig,
+/// defaulting to the usual rig of the `projection`.
+fn parse_stereo(stereo: &Field, projection: &Projection) -> Result<StereoRig, ParseError> {
+    stereo.check_members(&["interaxial", "convergence"])?;
+
+    let mut rig = StereoRig::for_projection(projection);
+
+    if let Some(interaxial) = stereo.optional("interaxial")? {
+        rig.interaxial = interaxial.number_where(
+            |interaxial| interaxial >= 0.0,
+            "expected a non-negative distance, found",
+        )?;
+    }
+
+    if let Some(convergence) = stereo.optional("convergence")? {
+        let name = convergence.string()?;
+
+        rig.convergence = Convergence::from_name(name).ok_or_else(|| {
+            convergence.error(format!(
+                "unknown convergence '{}' (expected one of {})",
+                name,
+                Convergence::NAMES.join(", ")
+            ))
+        })?;
+    }
+
+    rig.validate(projection)
+        .map_err(|reason| stereo.error(reason))?;
+
+    Ok(rig)
+}
+
+/// Reads the lens system of a realistic camera, given inline as `elements`
+/// or as a `lens_file` relative to `directory`, over the `default` lens.
+fn parse_lens(
+    camera: &Field,
+    directory: &Path,
+    default: RealisticLens,
+) -> Result<RealisticLens, ParseError> {
+    let prescription = match (camera.optional("elements")?, camera.optional("lens_file")?) {
+        (Some(_), Some(file)) => {
+            return Err(file.error("expected either elements or a lens file, not both"))
+        }
+        (Some(elements), None) => {
+            let rows = elements
+                .items()?
+                .iter()
+                .map(|row| {
+                    let numbers = row
+                        .items()?
+                        .iter()
+                        .map(|number| number.number())
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    match numbers.as_slice() {
+                        [radius, thickness, ior, aperture] => Ok(LensElement {
+                            radius: *radius,
+                            thickness: *thickness,
+                            ior: *ior,
+                            aperture: *aperture,
+                        }),
+                        _ => Err(row.error(
+                            "expected the radius, thickness, refractive index and aperture",
+                        )),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Arc::new(LensPrescription::new(rows).map_err(|reason| elements.error(reason))?)
+        }
+        (None, Some(file)) => {
+            let name = file.string()?;
+
+            let source = fs::read_to_string(directory.join(name))
+                .map_err(|error| file.error(format!("cannot read '{}': {}", name, error)))?;
+
+            let prescription = LensPrescription::parse(&source)
+                .map_err(|reason| file.error(format!("invalid lens '{}': {}", name, reason)))?;
+
+            Arc::new(prescription.with_source(name))
+        }
+        (None, None) => default.prescription,
+    };
+
+    let film_diagonal = match camera.optional("film_diagonal")? {
+        Some(field) => field.number_where(
+            |diagonal| diagonal > 0.0,
+            "expected a positive diagonal, found",
+        )?,
+        None => default.film_diagonal,
+    };
+
+    let aperture_stop = match camera.optional("aperture_stop")? {
+        Some(field) => Some(field.number_where(
+            |diameter| diameter > 0.0,
+            "expected a positive diameter, found",
+        )?),
+        None => default.aperture_stop,
+    };
+
+    let millimeters_per_unit = match camera.optional("millimeters_per_unit")? {
+        Some(field) => {
+            field.number_where(|scale| scale > 0.0, "expected a positive scale, found")?
+        }
+        None => default.millimeters_per_unit,
+    };
+
+    Ok(RealisticLens {
+        prescription,
+        film_diagonal,
+        aperture_stop,
+        millimeters_per_unit,
+    })
+}
+
+/// Reads the `type` of a camera, perspective by default, with its
+/// parameters, which are returned along with their names.
+fn parse_projection(
+    camera: &Field,
+    directory: &Path,
+) -> Result<(Projection, &'static [&'static str]), ParseError> {
+    let kind = match camera.optional("type")? {
+        Some(kind) => kind,
+        None => return Ok((Projection::Perspective, &[])),
+    };
+
+    let name = kind.string()?;
+
+    let projection = Projection::from_name(name).ok_or_else(|| {
+        kind.error(format!(
+            "unknown camera type '{}' (expected one of {})",
+            name,
+            Projection::NAMES.join(", ")
+        ))
+    })?;
+
+    match projection {
+        Projection::Orthographic { height } => {
+            let height = match camera.optional("height")? {
+                Some(field) => field
+                    .number_where(|height| height > 0.0, "expected a positive height, found")?,
+                None => height,
+            };
+
+            Ok((Projection::Orthographic { height }, &["height"]))
+        }
+        Projection::Fisheye { mapping, fov } => {
+            let mapping = match camera.optional("mapping")? {
+                Some(field) => {
+                    let name = field.string()?;
+
+                    FisheyeMapping::from_name(name).ok_or_else(|| {
+                        field.error(format!(
+                            "unknown mapping '{}' (expected one of {})",
+                            name,
+                            FisheyeMapping::NAMES.join(", ")
+                        ))
+                    })?
+                }
+                None => mapping,
+            };
+
+            let fov = match camera.optional("fov")? {
+                Some(field) => field.number_where(
+                    |fov| fov > 0.0 && fov <= 360.0,
+                    "expected an angle above 0 and up to 360 degrees, found",
+                )?,
+                None => fov,
+            };
+
+            Ok((Projection::Fisheye { mapping, fov }, &["mapping", "fov"]))
+        }
+        Projection::Cubemap { .. } => {
+            let face = match camera.optional("face")? {
+                Some(field) => {
+                    let name = field.string()?;
+
+                    Some(CubeFace::from_name(name).ok_or_else(|| {
+                        field.error(format!(
+                            "unknown face '{}' (expected one of {})",
+                            name,
+                            CubeFace::NAMES.join(", ")
+                        ))
+                    })?)
+                }
+                None => None,
+            };
+
+            Ok((Projection::Cubemap { face }, &["face"]))
+        }
+        Projection::Realistic(lens) => Ok((
+            Projection::Realistic(parse_lens(camera, directory, lens)?),
+            &[
+                "elements",
+                "lens_file",
+                "film_diagonal",
+                "aperture_stop",
+                "millimeters_per_unit",
+            ],
+        )),
+        _ => Ok((projection, &[])),
+    }
+}
+
+/// Reads the `curve` of a shutter, with its parameters, and the `readout`
+/// fraction of a rolling shutter.
+fn parse_shutter(shutter: &Field) -> Result<Shutter, ParseError> {
+    let curve_field = shutter.required("curve")?;
+    let name = curve_field.string()?;
+
+    let members: &[&str] = match name {
+        "box" | "triangle" => &["curve", "readout"],
+        "trapezoid" => &["curve", "open", "close", "readout"],
+        "tabulated" => &["curve", "values", "readout"],
+        _ => {
+            return Err(curve_field.error(format!(
+                "unknown curve '{}' (expected one of {})",
+                name,
+                ShutterCurve::NAMES.join(", ")
+            )))
+        }
+    };
+
+    shutter.check_members(members)?;
+
+    let curve = match name {
+        "box" => ShutterCurve::Box,
+        "triangle" => ShutterCurve::Triangle,
+        "trapezoid" => ShutterCurve::Trapezoid {
+            open: shutter.required("open")?.number()?,
+            close: shutter.required("close")?.number()?,
+        },
+        _ => ShutterCurve::Tabulated(
+            shutter
+                .required("values")?
+                .items()?
+                .iter()
+                .map(Field::number)
+                .collect::<Result<_, _>>()?,
+        ),
+    };
+
+    let mut result = Shutter::new(curve).map_err(|reason| shutter.error(reason))?;
+
+    if let Some(readout) = shutter.optional("readout")? {
+        result = result.with_readout(readout.number_where(
+            |readout| (0.0..1.0).contains(&readout),
+            "expected a fraction from 0 to below 1, found",
+        )?);
+    }
+
+    Ok(result)
+}
+
+/// Reads the members of a view, over the `default` view.
+fn parse_view(camera: &Field, default: View) -> Result<View, ParseError> {
+    let vector = |name: &str, default: Vec3| match camera.optional(name)? {
+        Some(field) => field.vector(),
+        None => Ok(default),
+    };
+
+    let number = |name: &str,
+                  default: f64,
+                  condition: fn(f64) -> bool,
+                  requirement: &str|
+     -> Result<f64, ParseError> {
+        match camera.optional(name)? {
+            Some(field) => field.number_where(condition, requirement),
+            None => Ok(default),
+        }
+    };
+
+    let view = View {
+        look_from: vector("look_from", default.look_from)?,
+        look_at: vector("look_at", default.look_at)?,
+        up: vector("up", default.up)?,
+        vertical_fov: number(
+            "vertical_fov",
+            default.vertical_fov,
+            |fov| fov > 0.0 && fov < 180.0,
+            "expected an angle between 0 and 180 degrees, found",
+        )?,
+        aperture: number(
+            "aperture",
+            default.aperture,
+            |aperture| aperture >= 0.0,
+            "expected a non-negative aperture, found",
+        )?,
+        focus_distance: number(
+            "focus_distance",
+            default.focus_distance,
+            |distance| distance > 0.0,
+            "expected a positive distance, found",
+        )?,
+        tilt: number(
+            "tilt",
+            default.tilt,
+            |tilt| tilt > -90.0 && tilt < 90.0,
+            "expected an angle between -90 and 90 degrees, found",
+        )?,
+        shift: match camera.optional("shift")? {
+            Some(field) => {
+                let [x, y] = field.numbers()?;
+
+                Vec2(x, y)
+            }
+            None => default.shift,
+        },
+        projection: default.projection,
+    };
+
+    let direction = view.look_at - view.look_from;
+
+    if direction.sq_length() == 0.0 || direction.cross(view.up).sq_length() == 0.0 {
+        return Err(camera.error("the view direction must be non-zero and not parallel to up"));
+    }
+
+    Ok(view)
+}
+
+fn parse_animation(animation: &Field) -> Result<Animation, ParseError> {
+    animation.check_members(&["frames", "fps", "shutter_angle"])?;
+
+    let default = Animation::default();
+
+    let frames = animation.required("frames")?;
+    let items = frames.items()?;
+
+    let (first_frame, last_frame) = match items.as_slice() {
+        [first, last] => (first.count(0)?, last.count(0)?),
+        _ => return Err(frames.error("expected the first and the last frame")),
+    };
+
+    if last_frame < first_frame {
+        return Err(frames.error("the last frame must not come before the first one"));
+    }
+
+    let fps = match animation.optional("fps")? {
+        Some(fps) => fps.number_where(|fps| fps > 0.0, "expected a positive rate, found")?,
+        None => default.fps,
+    };
+
+    let shutter_angle = match animation.optional("shutter_angle")? {
+        Some(angle) => angle.number_where(
+            |angle| angle > 0.0 && angle <= 360.0,
+            "expected an angle above 0 and up to 360 degrees, found",
+        )?,
+        None => default.shutter_angle,
+    };
+
+    Ok(Animation {
+        first_frame,
+        last_frame,
+        fps,
+        shutter_angle,
+    })
+}
+
+fn parse_interval(time: &Field) -> Result<Interval, ParseError> {
+    time.check_members(&["start", "end"])?;
+
+    let start = time.required("start")?.number()?;
+    let end = time.required("end")?.number_where(
+        |end| end >= start,
+        "expected an end not before the start, found",
+    )?;
+
+    Ok(Interval::new(start, end))
+}
+
+fn parse_image(image: &Field) -> Result<ImageSettings, ParseError> {
+    image.check_members(&["width", "height", "samples", "depth"])?;
+
+    let default = ImageSettings::default();
+
+    let height = image
+        .optional("height")?
+        .map(|height| height.count(2))
+        .transpose()?;
+
+    let image_settings = match image.optional("width")? {
+        Some(width) => default.with_size(width.count(2)?, height),
+        None => ImageSettings {
+            height: height.unwrap_or(default.height),
+            ..default
+        },
+    };
+
+    let count = |name: &str, default: u32| match image.optional(name)? {
+        Some(field) => field.count(1),
+        None => Ok(default),
+    };
+
+    Ok(ImageSettings {
+        samples_per_pixel: count("samples", default.samples_per_pixel)?,
+        max_depth: count("depth", default.max_depth)?,
+        ..image_settings
+    })
+}
+
+fn parse_background(background: &Field) -> Result<Background, ParseError> {
+    match &background.json.value {
+        JsonValue::String(name) if name == "sky" => Ok(Background::sky()),
+        JsonValue::String(name) => {
+            Err(background.error(format!("unknown background '{}' (expected sky)", name)))
+        }
+        JsonValue::Array(_) => Ok(Background::Solid(background.vector()?)),
+        JsonValue::Object(_) => {
+            background.check_members(&["bottom", "top"])?;
+
+            Ok(Background::Gradient {
+                bottom: background.required("bottom")?.vector()?,
+                top: background.required("top")?.vector()?,
+            })
+        }
+        _ => Err(background.expected("sky, a color or a gradient")),
+    }
+}
+
+fn type_of<'a>(field: &Field<'a>, types: &[&str]) -> Result<&'a str, ParseError> {
+    let kind = field.required("type")?;
+    let name = kind.string()?;
+
+    if types.contains(&name) {
+        Ok(name)
+    } else {
+        Err(kind.error(format!(
+            "unknown type '{}' (expected one of {})",
+            name,
+            types.join(", ")
+        )))
+    }
+}
+
+impl Library<'_> {
+    /// Texture given by id or, for a plain color, by value.
+    fn texture(&self, field: &Field) -> Result<Arc<dyn Texture>, ParseError> {
+        match &field.json.value {
+            JsonValue::String(id) => self
+                .textures
+                .get(id)
+                .cloned()
+                .ok_or_else(|| field.error(format!("unknown texture '{}'", id))),
+            JsonValue::Array(_) => Ok(Arc::new(SolidColor::new(field.vector()?))),
+            _ => Err(field.expected("a texture id or a color")),
+        }
+    }
+
+    fn material(&self, field: &Field) -> Result<Arc<dyn Material>, ParseError> {
+        let id = field.string()?;
+
+        self.materials
+            .get(id)
+            .cloned()
+            .ok_or_else(|| field.error(format!("unknown material '{}'", id)))
+    }
+
+    fn parse_texture(&self, texture: &Field) -> Result<Arc<dyn Texture>, ParseError> {
+        let kind = type_of(texture, &["solid", "checker", "image", "vertex_color"])?;
+
+        let texture: Arc<dyn Texture> = match kind {
+            "solid" => {
+                texture.check_members(&["type", "color"])?;
+
+                Arc::new(SolidColor::new(texture.required("color")?.vector()?))
+            }
+            "checker" => {
+                texture.check_members(&["type", "odd", "even"])?;
+
+                Arc::new(Checker::new(
+                    self.texture(&texture.required("odd")?)?,
+                    self.texture(&texture.required("even")?)?,
+                ))
+            }
+            "image" => {
+                texture.check_members(&["type", "file"])?;
+
+                let file = texture.required("file")?;
+                let name = file.string()?;
+
+                let image = read_image(self.directory.join(name))
+                    .map_err(|error| file.error(format!("cannot read '{}': {}", name, error)))?;
+
+                Arc::new(ImageTexture::new(image).with_source(name))
+            }
+            _ => {
+                texture.check_members(&["type", "fallback"])?;
+
+                let fallback = match texture.optional("fallback")? {
+                    Some(fallback) => fallback.vector()?,
+                    None => DEFAULT_MESH_ALBEDO,
+                };
+
+                Arc::new(VertexColor::new(fallback))
+            }
+        };
+
+        Ok(texture)
+    }
+
+    fn parse_material(&self, material: &Field) -> Result<Arc<dyn Material>, ParseError> {
+        let kind = type_of(
+            material,
+            &["lambertian", "metal", "dielectric", "diffuse_light"],
+        )?;
+
+        let material: Arc<dyn Material> = match kind {
+            "lambertian" => {
+                material.check_members(&["type", "albedo"])?;
+
+                Arc::new(Lambertian::new(
+                    self.texture(&material.required("albedo")?)?,
+                ))
+            }
+            "metal" => {
+                material.check_members(&["type", "albedo", "fuzz"])?;
+
+                let fuzz = match material.optional("fuzz")? {
+                    Some(fuzz) => fuzz.number_where(
+                        |fuzz| (0.0..=1.0).contains(&fuzz),
+                        "expected a fuzz between 0 and 1, found",
+                    )?,
+                    None => 0.0,
+                };
+
+                Arc::new(Metal::new(material.required("albedo")?.vector()?, fuzz))
+            }
+            "dielectric" => {
+                material.check_members(&["type", "refractive_index"])?;
+
+                Arc::new(Dielectric::new(
+                    material.required("refractive_index")?.number_where(
+                        |index| index > 0.0,
+                        "expected a positive refractive index, found",
+                    )?,
+                ))
+            }
+            _ => {
+                material.check_members(&["type", "emit"])?;
+
+                Arc::new(DiffuseLight::new(
+                    self.texture(&material.required("emit")?)?,
+                ))
+            }
+        };
+
+        Ok(material)
+    }
+
+    fn parse_object(
+        &self,
+        object: &Field,
+        time: Interval,
+    ) -> Result<Vec<Box<dyn Hit>>, ParseError> {
+        let kind = type_of(
+            object,
+            &[
+                "sphere",
+                "moving_sphere",
+                "rect",
+                "quad",
+                "box",
+                "triangle",
+                "mesh",
+            ],
+        )?;
+
+        let material = || self.material(&object.required("material")?);
+
+        let positive = |name: &str| {
+            object
+                .required(name)?
+                .number_where(|value| value > 0.0, "expected a positive value, found")
+        };
+
+        let hit: Box<dyn Hit> = match kind {
+            "sphere" => {
+                object.check_members(&[
+                    "type",
+                    "center",
+                    "radius",
+                    "material",
+                    "transform",
+                    "animation",
+                ])?;
+
+                Box::new(Sphere::new(
+                    object.required("center")?.vector()?,
+                    positive("radius")?,
+                    material()?,
+                ))
+            }
+            "moving_sphere" => {
+                object.check_members(&[
+                    "type",
+                    "start_center",
+                    "end_center",
+                    "time",
+                    "radius",
+                    "material",
+                    "transform",
+                    "animation",
+                ])?;
+
+                let interval = match object.optional("time")? {
+                    Some(interval) => parse_interval(&interval)?,
+                    None => time,
+                };
+
+                Box::new(MovingSphere::new(
+                    object.required("start_center")?.vector()?,
+                    object.required("end_center")?.vector()?,
+                    interval,
+                    positive("radius")?,
+                    material()?,
+                ))
+            }
+            "rect" => {
+                object.check_members(&[
+                    "type",
+                    "plane",
+                    "a",
+                    "b",
+                    "k",
+                    "flipped",
+                    "material",
+                    "transform",
+                    "animation",
+                ])?;
+
+                let plane_field = object.required("plane")?;
+
+                let plane = match plane_field.string()? {
+                    "xy" => Plane::XY,
+                    "xz" => Plane::XZ,
+                    "yz" => Plane::YZ,
+                    name => {
+                        return Err(plane_field
+                            .error(format!("unknown plane '{}' (expected xy, xz or yz)", name)))
+                    }
+                };
+
+                let rect = AxisRect::new(
+                    plane,
+                    object.required("a")?.range()?,
+                    object.required("b")?.range()?,
+                    object.required("k")?.number()?,
+                    material()?,
+                );
+
+                let flipped = match object.optional("flipped")? {
+                    Some(flipped) => flipped.boolean()?,
+                    None => false,
+                };
+
+                Box::new(if flipped { rect.flipped() } else { rect })
+            }
+            "quad" => {
+                object.check_members(&[
+                    "type",
+                    "origin",
+                    "u",
+                    "v",
+                    "material",
+                    "transform",
+                    "animation",
+                ])?;
+
+                Box::new(Quad::new(
+                    object.required("origin")?.vector()?,
+                    object.required("u")?.vector()?,
+                    object.required("v")?.vector()?,
+                    material()?,
+                ))
+            }
+            "box" => {
+                object.check_members(&[
+                    "type",
+                    "min",
+                    "max",
+                    "material",
+                    "transform",
+                    "animation",
+                ])?;
+
+                Box::new(Cuboid::new(
+                    object.required("min")?.vector()?,
+                    object.required("max")?.vector()?,
+                    material()?,
+                ))
+            }
+            "triangle" => {
+                object.check_members(&[
+                    "type",
+                    "vertices",
+                    "material",
+                    "transform",
+                    "animation",
+                ])?;
+
+                let field = object.required("vertices")?;
+                let items = field.items()?;
+
+                if items.len() != 3 {
+                    return Err(field.error(format!("expected 3 vertices, found {}", items.len())));
+                }
+
+                let vertices = [items[0].vector()?, items[1].vector()?, items[2].vector()?];
+
+                Box::new(Triangle::new(vertices, material()?))
+            }
+            _ => {
+                object.check_members(&["type", "file", "material", "transform", "animation"])?;
+
+                self.parse_mesh(object, time)?
+            }
+        };
+
+        let hit: Box<dyn Hit> = match object.optional("transform")? {
+            // Meshes are instanced, which places them already
+            Some(transform) if kind != "mesh" => {
+                Box::new(Transformed::new(hit, parse_transform(&transform)?))
+            }
+            _ => hit,
+        };
+
+        match object.optional("animation")? {
+            Some(animation) => Ok(vec![Box::new(Animated::new(hit, parse_track(&animation)?))]),
+            None => Ok(vec![hit]),
+        }
+    }
+
+    /// Places a mesh file. Files used by several objects are loaded once and
+    /// instanced, each object with its own material if it has one.
+    fn parse_mesh(&self, object: &Field, time: Interval) -> Result<Box<dyn Hit>, ParseError> {
+        let file = object.required("file")?;
+        let name = file.string()?;
+
+        let format = MeshFormat::from_path(name)
+            .ok_or_else(|| file.error("expected an .obj, .ply or .stl file"))?;
+
+        let material = match object.optional("material")? {
+            Some(material) => Some(self.material(&material)?),
+            None => None,
+        };
+
+        let transform = match object.optional("transform")? {
+            Some(transform) => parse_transform(&transform)?,
+            None => Transform::identity(),
+        };
+
+        let cached = self.meshes.borrow().get(name).cloned();
+
+        let blas = match cached {
+            Some(blas) => blas,
+            None => {
+                let path = self.directory.join(name);
+                let meshes = format
+                    .load(&path, None)
+                    .map_err(|error| file.error(format!("cannot load the mesh: {}", error)))?;
+
+                // Exported scenes refer to the file from anywhere
+                let source = fs::canonicalize(&path).unwrap_or(path);
+                let triangles: Vec<Arc<dyn Hit>> = meshes
+                    .into_iter()
+                    .flat_map(|imported| Arc::new(imported.mesh.with_source(&source)).triangles())
+                    .collect();
+
+                let blas: Arc<dyn Hit> = Arc::new(BVH::build(&triangles, time, self.bvh));
+
+                self.meshes
+                    .borrow_mut()
+                    .insert(name.to_string(), blas.clone());
+
+                blas
+            }
+        };
+
+        let instance = Instance::new(blas, transform);
+
+        Ok(Box::new(match material {
+            Some(material) => instance.with_material(material),
+            None => instance,
+        }))
+    }
+}
+
+/// Reads a transform made of a scale, a rotation, a look-at placement and a
+/// translation, applied in this order, or given as a 4x4 `matrix` by rows.
+fn parse_transform(transform: &Field) -> Result<Transform, ParseError> {
+    transform.check_members(&["scale", "rotate", "look_at", "translate", "matrix"])?;
+
+    if let Some(matrix) = transform.optional("matrix")? {
+        for (name, _) in transform.members()? {
+            if name != "matrix" {
+                return Err(matrix.error(format!("cannot be combined with '{}'", name)));
+            }
+        }
+
+        return parse_matrix(&matrix);
+    }
+
+    let mut result = Transform::identity();
+
+    if let Some(scale) = transform.optional("scale")? {
+        result = result.then(&Transform::scaling(parse_scale(&scale)?));
+    }
+
+    if let Some(rotate) = transform.optional("rotate")? {
+        let (axis, angle) = parse_rotation(&rotate)?;
+
+        result = result.then(&Transform::rotation(axis, angle));
+    }
+
+    if let Some(look_at) = transform.optional("look_at")? {
+        look_at.check_members(&["from", "to", "up"])?;
+
+        let from = look_at.required("from")?.vector()?;
+        let to_field = look_at.required("to")?;
+        let to = to_field.vector()?;
+
+        let up = match look_at.optional("up")? {
+            Some(up) => up.vector()?,
+            None => Vec3(0.0, 1.0, 0.0),
+        };
+
+        if from == to {
+            return Err(to_field.error("the target must differ from the position"));
+        }
+
+        if up.cross(to - from).sq_length() == 0.0 {
+            return Err(look_at.error("the up vector must not be parallel to the view"));
+        }
+
+        result = result.then(&Transform::look_at(from, to, up));
+    }
+
+    if let Some(translate) = transform.optional("translate")? {
+        result = result.then(&Transform::translation(translate.vector()?));
+    }
+
+    Ok(result)
+}
+
+/// Reads scale factors, given as a single number or one per axis.
+fn parse_scale(scale: &Field) -> Result<Vec3, ParseError> {
+    let factors = match &scale.json.value {
+        JsonValue::Number(factor) => Vec3(*factor, *factor, *factor),
+        _ => scale.vector()?,
+    };
+
+    if factors.x() == 0.0 || factors.y() == 0.0 || factors.z() == 0.0 {
+        return Err(scale.error("scale factors must not be zero"));
+    }
+
+    Ok(factors)
+}
+
+/// Reads a rotation axis and an angle in degrees.
+fn parse_rotation(rotate: &Field) -> Result<(Vec3, f64), ParseError> {
+    rotate.check_members(&["axis", "angle"])?;
+
+    let axis_field = rotate.required("axis")?;
+    let axis = axis_field.vector()?;
+
+    if axis.sq_length() == 0.0 {
+        return Err(axis_field.error("the rotation axis must be non-zero"));
+    }
+
+    Ok((axis, rotate.required("angle")?.number()?))
+}
+
+/// Reads keyframes, each with a `time` and optionally a `scale`, a `rotate`,
+/// a `translate` and the `easing` of the motion towards the next keyframe.
+/// Missing parts of a pose are left untransformed.
+fn parse_track(animation: &Field) -> Result<TransformTrack, ParseError> {
+    let items = animation.items()?;
+
+    if items.is_empty() {
+        return Err(animation.error("expected at least one keyframe"));
+    }
+
+    let mut keyframes: Vec<Keyframe> = Vec::with_capacity(items.len());
+
+    for item in &items {
+        item.check_members(&["time", "scale", "rotate", "translate", "easing"])?;
+
+        let time = parse_keyframe_time(item, keyframes.last().map(|keyframe| keyframe.time))?;
+
+        let mut pose = Pose::default();
+
+        if let Some(scale) = item.optional("scale")? {
+            pose.scale = parse_scale(&scale)?;
+        }
+
+        if let Some(rotate) = item.optional("rotate")? {
+            let (axis, angle) = parse_rotation(&rotate)?;
+
+            pose.rotation = Quaternion::from_axis_angle(axis, angle);
+        }
+
+        if let Some(translate) = item.optional("translate")? {
+            pose.translation = translate.vector()?;
+        }
+
+        keyframes.push(Keyframe::new(time, pose).with_easing(parse_easing(item)?));
+    }
+
+    Ok(TransformTrack::new(keyframes).expect("keyframes are checked"))
+}
+
+/// Reads the `time` of a keyframe, which must come after the `previous`
+/// keyframe.
+fn parse_keyframe_time(keyframe: &Field, previous: Option<f64>) -> Result<f64, ParseError> {
+    let field = keyframe.required("time")?;
+    let time = field.number()?;
+
+    match previous {
+        Some(previous) if time <= previous => Err(field.error("keyframe times must increase")),
+        _ => Ok(time),
+    }
+}
+
+/// Reads the `easing` of a keyframe, linear by default.
+fn parse_easing(keyframe: &Field) -> Result<Easing, ParseError> {
+    let easing = match keyframe.optional("easing")? {
+        Some(easing) => easing,
+        None => return Ok(Easing::Linear),
+    };
+
+    let name = easing.string()?;
+
+    Easing::from_name(name).ok_or_else(|| {
+        easing.error(format!(
+            "unknown easing '{}' (expected one of {})",
+            name,
+            Easing::NAMES.join(", ")
+        ))
+    })
+}
+
+/// Reads an invertible 4x4 matrix, given as 4 rows of 4 numbers.
+fn parse_matrix(matrix: &Field) -> Result<Transform, ParseError> {
+    let rows = matrix.items()?;
+
+    if rows.len() != 4 {
+        return Err(matrix.error(format!("expected 4 rows, found {}", rows.len())));
+    }
+
+    let mut values = [[0.0; 4]; 4];
+
+    for (row, field) in values.iter_mut().zip(&rows) {
+        let items = field.items()?;
+
+        if items.len() != 4 {
+            return Err(field.error(format!("expected 4 numbers, found {}", items.len())));
+        }
+
+        for (value, item) in row.iter_mut().zip(&items) {
+            *value = item.number()?;
+        }
+    }
+
+    // Projections would not keep rays straight
+    if values[3] != [0.0, 0.0, 0.0, 1.0] {
+        return Err(rows[3].error("the last row must be [0, 0, 0, 1]"));
+    }
+
+    Transform::new(Matrix4(values)).ok_or_else(|| matrix.error("the matrix is not invertible"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::color::Color;
+    use crate::core::geometry::Point3;
+    use crate::core::time::TimeRay3;
+    use crate::import::Position;
+    use crate::scene::object::SplitMethod;
+
+    const SCENE: &str = r#"{
+  "camera": { "look_from": [0, 0, 5], "look_at": [0, 0, 0], "vertical_fov": 40 },
+  "time": { "start": 0, "end": 0.5 },
+  "image": { "width": 200, "samples": 8 },
+  "background": [0, 0, 0],
+  "textures": {
+    "checker": { "type": "checker", "odd": [0, 0, 0], "even": [1, 1, 1] }
+  },
+  "materials": {
+    "ground": { "type": "lambertian", "albedo": "checker" },
+    "mirror": { "type": "metal", "albedo": [0.9, 0.9, 0.9], "fuzz": 0.1 },
+    "light": { "type": "diffuse_light", "emit": [4, 4, 4] }
+  },
+  "objects": [
+    { "type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "mirror" },
+    { "type": "rect", "plane": "xz", "a": [-5, 5], "b": [-5, 5], "k": -1, "material": "ground" },
+    { "type": "quad", "origin": [-1, 3, -1], "u": [2, 0, 0], "v": [0, 0, 2], "material": "light" }
+  ]
+}"#;
+
+    fn error(source: &str) -> ParseError {
+        match parse_scene(source, Path::new("")) {
+            Ok(_) => panic!("Expected an error"),
+            Err(error) => error,
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_build_scene() {
+        let scene = parse_scene(SCENE, Path::new("")).unwrap();
+
+        assert_eq!(Point3(0.0, 0.0, 5.0), scene.view.look_from);
+        assert_eq!(40.0, scene.view.vertical_fov);
+        assert_eq!(View::default().aperture, scene.view.aperture);
+        assert_eq!(0.5, scene.time.end());
+        assert_eq!(
+            (200, 113, 8),
+            (
+                scene.image.width,
+                scene.image.height,
+                scene.image.samples_per_pixel
+            )
+        );
+        assert_eq!(Background::Solid(Color(0.0, 0.0, 0.0)), scene.background);
+
+        let ray = TimeRay3::new(Point3(0.0, 0.0, 5.0), Vec3(0.0, 0.0, -1.0), 0.0);
+        let hit = scene.world.hit(ray, 0.001, f64::INFINITY).unwrap();
+
+        assert!((hit.t() - 4.0).abs() < 1e-9);
+
+        let ray = TimeRay3::new(Point3(3.0, 0.0, 0.0), Vec3(0.0, -1.0, 0.0), 0.0);
+        let hit = scene.world.hit(ray, 0.001, f64::INFINITY).unwrap();
+
+        assert!((hit.t() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn must_instance_meshes() {
+        let directory = std::env::temp_dir().join("raytracing-rs-instances");
+        fs::create_dir_all(&directory).unwrap();
+
+        // Unit square on the z = 0 plane
+        fs::write(
+            directory.join("square.stl"),
+            "solid square\n\
+             facet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nvertex 1 1 0\nendloop\nendfacet\n\
+             facet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 1 0\nvertex 0 1 0\nendloop\nendfacet\n\
+             endsolid square\n",
+        )
+        .unwrap();
+
+        let source = r#"{
+  "materials": {
+    "red": { "type": "lambertian", "albedo": [0.8, 0.1, 0.1] },
+    "blue": { "type": "lambertian", "albedo": [0.1, 0.1, 0.8] }
+  },
+  "objects": [
+    { "type": "mesh", "file": "square.stl", "material": "red" },
+    {
+      "type": "mesh",
+      "file": "square.stl",
+      "material": "blue",
+      "transform": { "scale": 2, "rotate": { "axis": [0, 1, 0], "angle": 90 }, "translate": [5, 0, 0] }
+    },
+    { "type": "mesh", "file": "square.stl", "material": "red", "transform": { "translate": [0, 0, -10] } }
+  ]
+}"#;
+
+        let bvh = BvhOptions {
+            split: SplitMethod::Median,
+            max_leaf_size: 1,
+        };
+        let scene =
+            parse_scene_with(source, &directory, &bvh, &AnimationOptions::default()).unwrap();
+
+        let hit_x = |x: f64, z: f64| {
+            let ray = TimeRay3::new(Point3(x, 0.5, z), Vec3(0.0, 0.0, -1.0), 0.0);
+
+            scene
+                .world
+                .hit(ray, 0.001, f64::INFINITY)
+                .map(|hit| hit.point())
+        };
+
+        assert_eq!(Some(Point3(0.5, 0.5, 0.0)), hit_x(0.5, 5.0));
+        assert_eq!(None, hit_x(1.5, 5.0));
+
+        // The rotated copy stands on the x = 5 plane, from z = 0 to z = -2
+        let ray = TimeRay3::new(Point3(10.0, 1.0, -1.0), Vec3(-1.0, 0.0, 0.0), 0.0);
+        let hit = scene.world.hit(ray, 0.001, f64::INFINITY).unwrap();
+
+        assert!((hit.point() - Point3(5.0, 1.0, -1.0)).length() < 1e-9);
+
+        // Each instance of the shared file keeps its own material
+        let material = |origin: Point3, direction: Vec3| {
+            let ray = TimeRay3::new(origin, direction, 0.0);
+
+            scene
+                .world
+                .hit(ray, 0.001, f64::INFINITY)
+                .unwrap()
+                .material()
+        };
+
+        let red = material(Point3(0.5, 0.5, 5.0), Vec3(0.0, 0.0, -1.0));
+        let blue = material(Point3(10.0, 1.0, -1.0), Vec3(-1.0, 0.0, 0.0));
+        let other_red = material(Point3(0.5, 0.5, -5.0), Vec3(0.0, 0.0, -1.0));
+
+        assert!(!Arc::ptr_eq(&red, &blue));
+        assert!(Arc::ptr_eq(&red, &other_red));
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 79),
+                "objects[0].transform.scale: scale factors must not be zero"
+            ),
+            error(
+                r#"{ "objects": [{ "type": "mesh", "file": "square.stl", "transform": { "scale": [1, 0, 1] } }] }"#
+            )
+        );
+    }
+
+    #[test]
+    fn must_transform_objects() {
+        let source = r#"{
+  "materials": { "grey": { "type": "lambertian", "albedo": [0.5, 0.5, 0.5] } },
+  "objects": [
+    {
+      "type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "grey",
+      "transform": { "matrix": [[1, 0, 0, 3], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0, 1]] }
+    },
+    {
+      "type": "box", "min": [-1, -1, -1], "max": [1, 1, 1], "material": "grey",
+      "transform": { "scale": [1, 1, 2], "look_at": { "from": [-3, 0, 0], "to": [-3, 0, 5] } }
+    }
+  ]
+}"#;
+
+        let scene = parse_scene(source, Path::new("")).unwrap();
+
+        let hit = |x: f64| {
+            let ray = TimeRay3::new(Point3(x, 0.0, 5.0), Vec3(0.0, 0.0, -1.0), 0.0);
+
+            scene.world.hit(ray, 0.001, f64::INFINITY).unwrap()
+        };
+
+        let sphere = hit(3.0);
+
+        assert!((sphere.point() - Point3(3.0, 0.0, 1.0)).length() < 1e-9);
+        assert!((sphere.normal() - Vec3(0.0, 0.0, 1.0)).length() < 1e-9);
+
+        // The box is stretched along z, which the look-at keeps along z
+        let cuboid = hit(-3.0);
+
+        assert!((cuboid.point() - Point3(-3.0, 0.0, 2.0)).length() < 1e-9);
+        assert!((cuboid.normal() - Vec3(0.0, 0.0, 1.0)).length() < 1e-9);
+
+        let object = |transform: &str| {
+            format!(
+                r#"{{ "materials": {{ "a": {{ "type": "lambertian", "albedo": [1, 1, 1] }} }}, "objects": [{{ "type": "box", "min": [0, 0, 0], "max": [1, 1, 1], "material": "a", "transform": {} }}] }}"#,
+                transform
+            )
+        };
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 179),
+                "objects[0].transform.matrix: the matrix is not invertible"
+            ),
+            error(&object(
+                r#"{ "matrix": [[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 0, 0], [0, 0, 0, 1]] }"#
+            ))
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 222),
+                "objects[0].transform.matrix[3]: the last row must be [0, 0, 0, 1]"
+            ),
+            error(&object(
+                r#"{ "matrix": [[1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0], [0, 0, 0.5, 1]] }"#
+            ))
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 191),
+                "objects[0].transform.matrix: cannot be combined with 'scale'"
+            ),
+            error(&object(r#"{ "scale": 2, "matrix": [] }"#))
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 180),
+                "objects[0].transform.look_at: the up vector must not be parallel to the view"
+            ),
+            error(&object(
+                r#"{ "look_at": { "from": [0, 0, 0], "to": [0, 2, 0] } }"#
+            ))
+        );
+    }
+
+    #[test]
+    fn must_animate_objects() {
+        let source = r#"{
+  "materials": { "grey": { "type": "lambertian", "albedo": [0.5, 0.5, 0.5] } },
+  "objects": [
+    {
+      "type": "box", "min": [-2, -0.1, -0.1], "max": [2, 0.1, 0.1], "material": "grey",
+      "animation": [
+        { "time": 0 },
+        { "time": 1, "rotate": { "axis": [0, 0, 1], "angle": 90 }, "translate": [0, 0, 1], "easing": "hold" }
+      ]
+    }
+  ]
+}"#;
+
+        let scene = parse_scene(source, Path::new("")).unwrap();
+
+        let hit = |x: f64, y: f64, time: f64| {
+            let ray = TimeRay3::new(Point3(x, y, 5.0), Vec3(0.0, 0.0, -1.0), time);
+
+            scene
+                .world
+                .hit(ray, 0.001, f64::INFINITY)
+                .map(|hit| hit.point())
+        };
+
+        assert!((hit(1.5, 0.0, 0.0).unwrap() - Point3(1.5, 0.0, 0.1)).length() < 1e-9);
+        assert_eq!(None, hit(0.0, 1.5, 0.0));
+
+        // Turned and moved up at the last keyframe, then held
+        assert!((hit(0.0, 1.5, 1.0).unwrap() - Point3(0.0, 1.5, 1.1)).length() < 1e-9);
+        assert!((hit(0.0, 1.5, 3.0).unwrap() - Point3(0.0, 1.5, 1.1)).length() < 1e-9);
+
+        let object = |animation: &str| {
+            format!(
+                r#"{{ "materials": {{ "a": {{ "type": "lambertian", "albedo": [1, 1, 1] }} }}, "objects": [{{ "type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "a", "animation": {} }}] }}"#,
+                animation
+            )
+        };
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 194),
+                "objects[0].animation[1].time: keyframe times must increase"
+            ),
+            error(&object(r#"[{ "time": 1 }, { "time": 0.5 }]"#))
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 192),
+                "objects[0].animation[0].easing: unknown easing 'bounce' (expected one of linear, ease_in, ease_out, ease_in_out, hold)"
+            ),
+            error(&object(r#"[{ "time": 0, "easing": "bounce" }]"#))
+        );
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_parse_animation() {
+        let source = r#"{
+  "camera": {
+    "look_from": [0, 0, 10],
+    "vertical_fov": 40,
+    "keyframes": [
+      { "time": 0 },
+      { "time": 2, "look_from": [10, 0, 0], "vertical_fov": 20, "easing": "ease_out" }
+    ]
+  },
+  "animation": { "frames": [0, 47], "fps": 24 }
+}"#;
+
+        let scene = parse_scene(source, Path::new("")).unwrap();
+
+        assert_eq!(
+            Some(Animation {
+                first_frame: 0,
+                last_frame: 47,
+                fps: 24.0,
+                shutter_angle: 180.0,
+            }),
+            scene.animation
+        );
+
+        // The scene is bounded until the shutter of the last frame closes
+        assert_eq!(0.0, scene.time.start());
+        assert!((scene.time.end() - (47.5 / 24.0)).abs() < 1e-12);
+
+        assert_eq!(2, scene.camera_keyframes.len());
+        assert_eq!(scene.view, scene.view_at(-1.0));
+
+        let halfway = scene.view_at(1.0);
+
+        assert_eq!(Point3(5.0, 0.0, 5.0), halfway.look_from);
+        assert_eq!(30.0, halfway.vertical_fov);
+        assert_eq!(Point3(10.0, 0.0, 0.0), scene.view_at(5.0).look_from);
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 28),
+                "animation.frames: the last frame must not come before the first one"
+            ),
+            error(r#"{ "animation": { "frames": [10, 1] } }"#)
+        );
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_bound_scenes_over_the_frames_to_render() {
+        // A sphere moving 10 units per second along x, followed by the camera,
+        // rendered after the time interval of the scene
+        let source = r#"{
+  "time": { "start": 0, "end": 1 },
+  "camera": {
+    "look_from": [0, 0, 10],
+    "aperture": 0,
+    "keyframes": [{ "time": 0 }, { "time": 4, "look_from": [40, 0, 10], "look_at": [40, 0, 0] }]
+  },
+  "materials": { "grey": { "type": "lambertian", "albedo": [0.5, 0.5, 0.5] } },
+  "objects": [
+    {
+      "type": "sphere", "center": [0, 0, 0], "radius": 1, "material": "grey",
+      "animation": [{ "time": 0 }, { "time": 4, "translate": [40, 0, 0] }]
+    }
+  ]
+}"#;
+
+        let options = AnimationOptions {
+            frames: Some((48, 72)),
+            ..AnimationOptions::default()
+        };
+        let scene =
+            parse_scene_with(source, Path::new(""), &BvhOptions::default(), &options).unwrap();
+        let animation = scene.animation.unwrap();
+
+        let bounds = scene.bounds();
+
+        assert_eq!(2.0, bounds.start());
+        assert!((bounds.end() - 72.5 / 24.0).abs() < 1e-12);
+
+        for frame in animation.frames() {
+            let interval = animation.frame_interval(frame);
+            let time = 0.5 * (interval.start() + interval.end());
+            let camera =
+                scene
+                    .view_at(time)
+                    .camera(1.0, interval, scene.shutter.clone(), &scene.bokeh);
+            let ray = camera.ray(0.5, 0.5).unwrap();
+
+            assert!(scene.world.hit(ray, 0.001, f64::INFINITY).is_some());
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_parse_shutter() {
+        let source = r#"{ "camera": { "shutter": { "curve": "tabulated", "values": [0, 1, 0], "readout": 0.25 } } }"#;
+        let shutter = parse_scene(source, Path::new("")).unwrap().shutter;
+
+        assert_eq!(
+            &ShutterCurve::Tabulated(vec![0.0, 1.0, 0.0]),
+            shutter.curve()
+        );
+        assert_eq!(0.25, shutter.readout());
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 26),
+                "camera.shutter: the opening and closing fractions must be non-negative and add up to at most 1"
+            ),
+            error(r#"{ "camera": { "shutter": { "curve": "trapezoid", "open": 0.5, "close": 0.6 } } }"#)
+        );
+    }
+
+    #[test]
+    fn must_parse_camera_types() {
+        let projection = |camera: &str| {
+            let source = format!(r#"{{ "camera": {} }}"#, camera);
+
+            parse_scene(&source, Path::new("")).unwrap().view.projection
+        };
+
+        assert_eq!(Projection::Perspective, projection("{}"));
+        assert_eq!(
+            Projection::Orthographic { height: 3.0 },
+            projection(r#"{ "type": "orthographic", "height": 3 }"#)
+        );
+        assert_eq!(
+            Projection::Fisheye {
+                mapping: FisheyeMapping::Equisolid,
+                fov: 180.0
+            },
+            projection(r#"{ "type": "fisheye", "mapping": "equisolid" }"#)
+        );
+        assert_eq!(
+            Projection::Cubemap {
+                face: Some(CubeFace::PositiveY)
+            },
+            projection(r#"{ "type": "cubemap", "face": "py" }"#)
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 23),
+                "camera.type: unknown camera type 'pinhole' (expected one of perspective, orthographic, fisheye, equirectangular, cubemap, realistic)"
+            ),
+            error(r#"{ "camera": { "type": "pinhole" } }"#)
+        );
+
+        // Lens members only apply to perspective cameras
+        assert!(parse_scene(
+            r#"{ "camera": { "type": "equirectangular", "vertical_fov": 90 } }"#,
+            Path::new("")
+        )
+        .is_err());
+        assert!(parse_scene(
+                r#"{ "camera": { "type": "orthographic", "keyframes": [{ "time": 0, "height": 1 }] } }"#,
+                Path::new("")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn must_parse_stereo_rigs() {
+        let source =
+            r#"{ "camera": { "type": "equirectangular", "stereo": { "interaxial": 0.5 } } }"#;
+        let stereo = parse_scene(source, Path::new("")).unwrap().stereo;
+
+        assert_eq!(
+            Some(StereoRig {
+                interaxial: 0.5,
+                convergence: Convergence::Omnidirectional
+            }),
+            stereo
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 25),
+                "camera.stereo: omni-directional stereo needs an equirectangular camera"
+            ),
+            error(r#"{ "camera": { "stereo": { "convergence": "ods" } } }"#)
+        );
+    }
+
+    #[test]
+    fn must_parse_bokeh() {
+        let directory = std::env::temp_dir().join("raytracing-rs-bokeh");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("slit.ppm"),
+            "P3 3 1 255 0 0 0 255 255 255 0 0 0",
+        )
+        .unwrap();
+
+        let source =
+            r#"{ "camera": { "bokeh": { "shape": "mask", "file": "slit.ppm", "cat_eye": 0.2 } } }"#;
+        let bokeh = parse_scene(source, &directory).unwrap().bokeh;
+
+        assert_eq!(0.2, bokeh.cat_eye());
+        assert!(
+            matches!(bokeh.shape(), ApertureShape::Mask(mask) if mask.source() == Some(Path::new("slit.ppm")))
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 56),
+                "camera.bokeh.blades: expected an integer of at least 3"
+            ),
+            error(r#"{ "camera": { "bokeh": { "shape": "polygon", "blades": 2 } } }"#)
+        );
+
+        // Only perspective cameras have a lens
+        assert!(parse_scene(
+            r#"{ "camera": { "type": "fisheye", "bokeh": { "shape": "circle" } } }"#,
+            Path::new("")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn must_parse_focus() {
+        let source = r#"{ "camera": { "focus_on": [1, 2, 3], "tilt": 20, "shift": [0, 0.1],
+            "keyframes": [{ "time": 0 }, { "time": 1, "tilt": 0 }] } }"#;
+        let scene = parse_scene(source, Path::new("")).unwrap();
+
+        assert_eq!(Some(Focus::Point(Point3(1.0, 2.0, 3.0))), scene.focus);
+        assert_eq!((20.0, Vec2(0.0, 0.1)), (scene.view.tilt, scene.view.shift));
+        assert_eq!(10.0, scene.view_at(0.5).tilt);
+
+        let source = r#"{ "camera": { "type": "realistic", "autofocus": true } }"#;
+        assert_eq!(
+            Some(Focus::Auto),
+            parse_scene(source, Path::new("")).unwrap().focus
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 51),
+                "camera.autofocus: expected either a point to focus on or autofocus, not both"
+            ),
+            error(r#"{ "camera": { "focus_on": [0, 0, 0], "autofocus": true } }"#)
+        );
+
+        // Only cameras with a focus distance focus
+        assert!(parse_scene(
+            r#"{ "camera": { "type": "fisheye", "autofocus": true } }"#,
+            Path::new("")
+        )
+        .is_err());
+        assert!(parse_scene(
+            r#"{ "camera": { "type": "realistic", "tilt": 10 } }"#,
+            Path::new("")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn must_parse_realistic_cameras() {
+        let directory = std::env::temp_dir().join("raytracing-rs-lens");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(
+            directory.join("singlet.dat"),
+            "# radius thickness ior aperture\n50 5 1.5 20\n0 2 0 12\n-50 90 1 20\n",
+        )
+        .unwrap();
+
+        let source = r#"{ "camera": { "type": "realistic", "lens_file": "singlet.dat", "aperture_stop": 8,
+            "focus_distance": 2, "keyframes": [{ "time": 1, "focus_distance": 3 }] } }"#;
+        let scene = parse_scene(source, &directory).unwrap();
+
+        match &scene.view.projection {
+            Projection::Realistic(lens) => {
+                assert_eq!(3, lens.prescription.elements().len());
+                assert_eq!(Some(Path::new("singlet.dat")), lens.prescription.source());
+                assert_eq!(Some(8.0), lens.aperture_stop);
+                assert_eq!(43.27, lens.film_diagonal);
+            }
+            projection => panic!("unexpected projection {:?}", projection),
+        }
+
+        // The focus distance animates
+        assert_eq!(3.0, scene.view_at(1.0).focus_distance);
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 49),
+                "camera.elements[0]: expected the radius, thickness, refractive index and aperture"
+            ),
+            error(r#"{ "camera": { "type": "realistic", "elements": [[50, 5, 1.5]] } }"#)
+        );
+        assert!(parse_scene(
+            r#"{ "camera": { "type": "realistic", "lens_file": "missing.dat" } }"#,
+            &directory
+        )
+        .is_err());
+
+        // Realistic cameras have no thin lens
+        assert!(parse_scene(
+            r#"{ "camera": { "type": "realistic", "aperture": 0.1 } }"#,
+            Path::new("")
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn must_load_example_scene() {
+        let scene = load_scene(
+            "scenes/cornell-box.json",
+            &BvhOptions::default(),
+            &AnimationOptions::default(),
+        )
+        .unwrap();
+
+        assert_eq!((600, 600), (scene.image.width, scene.image.height));
+    }
+
+    #[test]
+    fn must_report_field_paths() {
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(17, 90),
+                "objects[2].material: unknown material 'lamp'"
+            ),
+            error(&SCENE.replace("\"material\": \"light\"", "\"material\": \"lamp\""))
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(2, 77),
+                "camera.vertical_fov: expected a number, found a string"
+            ),
+            error(&SCENE.replace("\"vertical_fov\": 40", "\"vertical_fov\": \"40\""))
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 16),
+                "objects[0]: missing field 'radius'"
+            ),
+            error(
+                r#"{ "objects": [ { "type": "sphere", "center": [0, 0, 0], "material": "a" } ] }"#
+            )
+        );
+
+        assert_eq!(
+            ParseError::at(
+                Position::LineColumn(1, 35),
+                "materials.a.colour: unknown field (expected one of type, albedo)"
+            ),
+            error(r#"{ "materials": { "a": { "colour": [1, 1, 1], "type": "lambertian" } } }"#)
+        );
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::io::Read;
+
+use super::{MeshData, ParseError, Position};
+use crate::core::geometry::Point3;
+
+const HEADER_SIZE: usize = 80;
+const TRIANGLE_SIZE: usize = 50;
+
+/// Reads an STL mesh, in binary or ASCII encoding.
+///
+/// STL stores every triangle on its own, so vertices with the same position
+/// are merged. Facet normals are ignored: the orientation of the triangles
+/// is given by the order of their vertices.
+pub fn parse_stl<R: Read>(mut reader: R) -> Result<MeshData, ParseError> {
+    let mut bytes = Vec::new();
+
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(|error| ParseError::at(Position::Byte(0), error.to_string()))?;
+
+    // Binary files may also start with "solid", so the size decides first
+    if is_binary(&bytes) {
+        return parse_binary(&bytes);
+    }
+
+    let start = bytes.iter().position(|byte| !byte.is_ascii_whitespace());
+
+    if start.map_or(false, |start| bytes[start..].starts_with(b"solid")) {
+        parse_ascii(&String::from_utf8_lossy(&bytes))
+    } else {
+        parse_binary(&bytes)
+    }
+}
+
+fn is_binary(bytes: &[u8]) -> bool {
+    bytes.len() >= HEADER_SIZE + 4
+        && bytes.len() == HEADER_SIZE + 4 + triangle_count(bytes) * TRIANGLE_SIZE
+}
+
+fn triangle_count(bytes: &[u8]) -> usize {
+    let count = &bytes[HEADER_SIZE..HEADER_SIZE + 4];
+
+    u32::from_le_bytes([count[0], count[1], count[2], count[3]]) as usize
+}
+
+fn parse_binary(bytes: &[u8]) -> Result<MeshData, ParseError> {
+    if bytes.len() < HEADER_SIZE + 4 {
+        return Err(ParseError::at(
+            Position::Byte(bytes.len() as u64),
+            "unexpected end of file",
+        ));
+    }
+
+    let count = triangle_count(bytes);
+    let expected = HEADER_SIZE + 4 + count * TRIANGLE_SIZE;
+
+    if bytes.len() != expected {
+        return Err(ParseError::at(
+            Position::Byte(HEADER_SIZE as u64),
+            format!(
+                "{} triangles need {} bytes, found {}",
+                count,
+                expected,
+                bytes.len()
+            ),
+        ));
+    }
+
+    let mut vertices = VertexMerger::default();
+
+    let triangles = bytes[HEADER_SIZE + 4..].chunks_exact(TRIANGLE_SIZE);
+
+    for (number, triangle) in triangles.enumerate() {
+        let value = |index: usize| {
+            let offset = 4 * index;
+            let value = f32::from_le_bytes([
+                triangle[offset],
+                triangle[offset + 1],
+                triangle[offset + 2],
+                triangle[offset + 3],
+            ]);
+
+            if value.is_finite() {
+                Ok(f64::from(value))
+            } else {
+                let start = HEADER_SIZE + 4 + number * TRIANGLE_SIZE + offset;
+
+                Err(ParseError::at(
+                    Position::Byte(start as u64),
+                    format!("invalid number {}", value),
+                ))
+            }
+        };
+
+        // The normal comes first, then the three vertices
+        let mut corners = [Point3(0.0, 0.0, 0.0); 3];
+
+        for (corner, first) in corners.iter_mut().zip([3, 6, 9]) {
+            *corner = Point3(value(first)?, value(first + 1)?, value(first + 2)?);
+        }
+
+        vertices.add_triangle(corners);
+    }
+
+    Ok(vertices.into_data())
+}
+
+fn parse_ascii(source: &str) -> Result<MeshData, ParseError> {
+    let mut vertices = VertexMerger::default();
+
+    let mut corners = Vec::with_capacity(3);
+    let mut in_facet = false;
+    let mut last_line = 0;
+
+    for (index, line) in source.lines().enumerate() {
+        let number = index + 1;
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+
+        last_line = number;
+
+        match tokens.as_slice() {
+            ["facet", ..] => {
+                if in_facet {
+                    return Err(ParseError::new(number, "missing 'endfacet'"));
+                }
+
+                in_facet = true;
+                corners.clear();
+            }
+            ["vertex", x, y, z] => {
+                if !in_facet {
+                    return Err(ParseError::new(number, "'vertex' outside of a facet"));
+                }
+
+                let parse = |value: &str| {
+                    value
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|value| value.is_finite())
+                        .ok_or_else(|| {
+                            ParseError::new(
+                                number,
+                                format!("invalid number '{}' in 'vertex'", value),
+                            )
+                        })
+                };
+
+                corners.push(Point3(parse(x)?, parse(y)?, parse(z)?));
+            }
+            ["vertex", ..] => {
+                return Err(ParseError::new(number, "'vertex' expects 3 values"));
+            }
+            ["endfacet"] => {
+                if !in_facet {
+                    return Err(ParseError::new(number, "'endfacet' outside of a facet"));
+                }
+
+                match corners.as_slice() {
+                    [a, b, c] => vertices.add_triangle([*a, *b, *c]),
+                    _ => {
+                        return Err(ParseError::new(
+                            number,
+                            format!("a facet needs 3 vertices, found {}", corners.len()),
+                        ))
+                    }
+                }
+
+                in_facet = false;
+            }
+            // solid, outer loop, endloop and endsolid carry no geometry
+            _ => {}
+        }
+    }
+
+    if in_facet {
+        return Err(ParseError::new(last_line, "missing 'endfacet'"));
+    }
+
+    Ok(vertices.into_data())
+}
+
+/// Shares the vertices of triangles having the same position.
+#[derive(Default)]
+struct VertexMerger {
+    indices: HashMap<[u64; 3], usize>,
+    data: MeshData,
+}
+
+impl VertexMerger {
+    fn add_triangle(&mut self, corners: [Point3; 3]) {
+        let mut triangle = [0; 3];
+
+        for (index, corner) in triangle.iter_mut().zip(corners) {
+            // Adding zero turns -0.0 into 0.0, so both share a vertex
+            let key = [corner.x(), corner.y(), corner.z()].map(|value| (value + 0.0).to_bits());
+            let positions = &mut self.data.positions;
+
+            *index = *self.indices.entry(key).or_insert_with(|| {
+                positions.push(corner);
+                positions.len() - 1
+            });
+        }
+
+        self.data.indices.push(triangle);
+    }
+
+    fn into_data(self) -> MeshData {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TETRAHEDRON: [[f32; 9]; 4] = [
+        [0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+        [1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0],
+    ];
+
+    fn binary(header: &[u8]) -> Vec<u8> {
+        let mut bytes = header.to_vec();
+        bytes.resize(HEADER_SIZE, b' ');
+        bytes.extend((TETRAHEDRON.len() as u32).to_le_bytes());
+
+        for triangle in &TETRAHEDRON {
+            bytes.extend([0; 12]);
+
+            for value in triangle {
+                bytes.extend(value.to_le_bytes());
+            }
+
+            bytes.extend([0; 2]);
+        }
+
+        bytes
+    }
+
+    fn assert_tetrahedron(data: &MeshData) {
+        assert_eq!(4, data.positions.len());
+        assert_eq!(4, data.indices.len());
+        assert_eq!([0, 1, 2], data.indices[0]);
+        assert_eq!(Point3(0.0, 0.0, 1.0), data.positions[data.indices[1][1]]);
+    }
+
+    #[test]
+    fn must_read_binary() {
+        assert_tetrahedron(&parse_stl(binary(b"exported by cad").as_slice()).unwrap());
+
+        // Binary files whose header starts like an ASCII file
+        assert_tetrahedron(&parse_stl(binary(b"solid part").as_slice()).unwrap());
+    }
+
+    #[test]
+    fn must_read_ascii() {
+        let mut source = String::from("solid tetrahedron\n");
+
+        for triangle in &TETRAHEDRON {
+            source += "  facet normal 0 0 0\n    outer loop\n";
+
+            for vertex in triangle.chunks(3) {
+                source += &format!("      vertex {} {} {}\n", vertex[0], vertex[1], vertex[2]);
+            }
+
+            source += "    endloop\n  endfacet\n";
+        }
+
+        source += "endsolid tetrahedron\n";
+
+        assert_tetrahedron(&parse_stl(source.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn must_report_errors() {
+        let source = "solid a\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\nendloop\nendfacet\n";
+
+        assert_eq!(
+            Err(ParseError::new(7, "a facet needs 3 vertices, found 2")),
+            parse_stl(source.as_bytes())
+        );
+
+        let mut bytes = binary(b"cad");
+        bytes.pop();
+
+        assert_eq!(
+            Err(ParseError::at(
+                Position::Byte(80),
+                "4 triangles need 284 bytes, found 283"
+            )),
+            parse_stl(bytes.as_slice())
+        );
+    }
+
+    #[test]
+    fn must_reject_non_finite_coordinates() {
+        let source = "solid a\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 nan 0\n";
+
+        assert_eq!(
+            Err(ParseError::new(5, "invalid number 'nan' in 'vertex'")),
+            parse_stl(source.as_bytes())
+        );
+
+        // Infinity as the z coordinate of the first vertex of the second
+        // triangle
+        let mut bytes = binary(b"cad");
+        let start = HEADER_SIZE + 4 + TRIANGLE_SIZE + 4 * 5;
+        bytes[start..start + 4].copy_from_slice(&f32::INFINITY.to_le_bytes());
+
+        assert_eq!(
+            Err(ParseError::at(
+                Position::Byte(start as u64),
+                "invalid number inf"
+            )),
+            parse_stl(bytes.as_slice())
+        );
+
+        // Normals are not read
+        let mut bytes = binary(b"cad");
+        let start = HEADER_SIZE + 4;
+        bytes[start..start + 4].copy_from_slice(&f32::NAN.to_le_bytes());
+
+        assert!(parse_stl(bytes.as_slice()).is_ok());
+    }
+}
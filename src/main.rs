@@ -1,94 +1,290 @@
-use std::io::stdout;
+use std::fs::{self, File};
+use std::io::{stdout, BufWriter, Write};
+use std::path::Path;
+use std::process;
+use std::thread;
 
+mod cli;
 mod core;
+mod export;
+mod image;
+mod import;
 mod materials;
+mod render;
 mod scene;
 mod scenes;
 mod textures;
 
-use self::core::math::constants::INFINITY;
-use crate::core::color::write_color;
-use crate::core::color::Color;
-use crate::core::geometry::{Point3, Ray, Vec3, Vector};
-use crate::core::math::rand::rand;
-use crate::core::time::{Interval, TimeRay3};
-use crate::scene::camera::Options;
-use crate::scene::{Hit, HitList};
-use crate::scenes::generate_random_scene;
-use scene::camera::Camera;
-
-fn ray_color(ray: TimeRay3, world: &HitList, depth: i32) -> Color {
-    // Stop recursion at ray bounce limit
-    if depth <= 0 {
-        return Color(0.0, 0.0, 0.0);
+#[cfg(test)]
+mod testing;
+
+use crate::cli::{CliError, Command, USAGE};
+use crate::core::math::rand::{self, rand_between};
+use crate::core::time::Interval;
+use crate::export::export_scene;
+use crate::image::{read_image, Framebuffer};
+use crate::import::{load_scene, MeshFormat};
+use crate::render::{render, RenderSettings};
+use crate::scenes::generate_scene;
+use scene::camera::{ApertureMask, Camera, Eye, StereoLayout};
+
+fn main() {
+    let settings = match cli::parse(std::env::args().skip(1)) {
+        Ok(Command::Render(settings)) => settings,
+        Ok(Command::Help) => {
+            print!("{}", USAGE);
+            return;
+        }
+        Err(error) => {
+            eprintln!("error: {}\n\n{}", error, USAGE);
+            process::exit(2);
+        }
+    };
+
+    let seed = settings.seed.unwrap_or_else(|| rand_between(0, u64::MAX));
+
+    rand::seed(seed);
+
+    let animation_options = settings.animation_options();
+
+    let mut scene = match &settings.scene_file {
+        Some(path) => match load_scene(path, &settings.bvh, &animation_options) {
+            Ok(scene) => scene,
+            Err(error) => {
+                eprintln!("error: {}", error);
+                process::exit(1);
+            }
+        },
+        None => generate_scene(&settings.scene, &animation_options)
+            .expect("Scene name was validated by the CLI"),
+    };
+
+    for path in &settings.meshes {
+        let format = MeshFormat::from_path(path).expect("Mesh format was validated by the CLI");
+
+        match format.load(path, None) {
+            Ok(meshes) => {
+                let source = fs::canonicalize(path).unwrap_or_else(|_| path.clone());
+
+                for imported in meshes {
+                    let triangles = imported.mesh.len();
+                    let bvh = imported
+                        .mesh
+                        .with_source(&source)
+                        .into_bvh(scene.bounds(), &settings.bvh);
+
+                    eprintln!(
+                        "Loaded {} triangles from {} ({}): {}",
+                        triangles,
+                        path.display(),
+                        imported.name,
+                        bvh.stats()
+                    );
+
+                    scene.world.add(Box::new(bvh));
+                }
+            }
+            Err(error) => {
+                eprintln!("error: {}", error);
+                process::exit(1);
+            }
+        }
     }
 
-    match world.hit(ray, 0.001, INFINITY) {
-        Some(material_hit) => {
-            let scatter_record = material_hit.material().scatter(ray, material_hit.hit());
+    let background = settings.background.unwrap_or(scene.background);
+    let image_settings = settings.image(scene.image);
 
-            scatter_record.map_or(Color(0.0, 0.0, 0.0), |scr| {
-                scr.attenuation * ray_color(scr.ray, world, depth - 1)
+    let animation = match settings.animation(scene.animation) {
+        Ok(animation) => animation,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            process::exit(2);
+        }
+    };
+
+    let shutter = settings.shutter(scene.shutter.clone());
+
+    let exit_on_error = |error: CliError| -> ! {
+        eprintln!("error: {}", error);
+        process::exit(2);
+    };
+
+    let mask = settings.aperture_mask.as_ref().map(|path| {
+        read_image(path)
+            .map_err(|error| error.to_string())
+            .and_then(|image| ApertureMask::new(&image).map_err(|reason| reason.to_string()))
+            .map(|mask| mask.with_source(path))
+            .unwrap_or_else(|error| {
+                eprintln!("error: cannot read '{}': {}", path.display(), error);
+                process::exit(1);
             })
+    });
+
+    let bokeh = settings.bokeh(scene.bokeh.clone(), mask);
+
+    // Keyframes cannot change the projection
+    let projection = settings
+        .view(scene.view.clone())
+        .unwrap_or_else(|error| exit_on_error(error))
+        .projection;
+
+    let stereo = settings
+        .stereo(scene.stereo, &projection)
+        .unwrap_or_else(|error| exit_on_error(error));
+
+    let focus = settings.focus(scene.focus);
+
+    // Cameras are placed, and focused, as at the middle of their shutter
+    // interval
+    let camera = |interval: Interval, eye: Option<Eye>| -> Result<Box<dyn Camera>, CliError> {
+        let time = 0.5 * (interval.start() + interval.end());
+        let mut view = settings.view(scene.view_at(time))?;
+
+        if let Some(focus) = &focus {
+            view = view.focused(focus, &scene.world, time);
         }
-        None => {
-            let unit: Vec3 = ray.direction().normalized();
 
-            let t: f64 = 0.5 * (unit.y() + 1.0);
+        let aspect_ratio = image_settings.aspect_ratio();
 
-            ((1.0 - t) * Color(1.0, 1.0, 1.0)) + (t * Color(0.5, 0.7, 1.0))
+        Ok(match (stereo, eye) {
+            (Some((rig, _)), Some(eye)) => {
+                view.eye_camera(&rig, eye, aspect_ratio, interval, shutter.clone(), &bokeh)
+            }
+            _ => view.camera(aspect_ratio, interval, shutter.clone(), &bokeh),
+        })
+    };
+
+    if let Some(path) = &settings.export_scene {
+        let camera = camera(scene.time, None).unwrap_or_else(|error| exit_on_error(error));
+        let rig = stereo.map(|(rig, _)| rig);
+
+        let written = export_scene(
+            &scene.world,
+            camera.as_ref(),
+            rig.as_ref(),
+            focus.as_ref(),
+            &background,
+            &image_settings,
+        )
+        .map_err(|error| error.to_string())
+        .and_then(|source| {
+            fs::write(path, source)
+                .map_err(|error| format!("cannot write '{}': {}", path.display(), error))
+        });
+
+        if let Err(error) = written {
+            eprintln!("error: {}", error);
+            process::exit(1);
         }
+
+        return;
     }
-}
 
-fn main() {
-    let aspect_ratio: f64 = 16.0 / 9.0;
+    let render_settings = RenderSettings {
+        image_width: image_settings.width,
+        image_height: image_settings.height,
+        samples_per_pixel: image_settings.samples_per_pixel,
+        max_depth: image_settings.max_depth,
+        seed,
+        threads: settings
+            .threads
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |threads| threads.get())),
+        tile_size: settings.tile_size,
+    };
 
-    // let image_width: i32 = 400;
-    let image_width: i32 = 1280;
-    let image_height: i32 = ((image_width as f64) / aspect_ratio) as i32;
+    let render_image = |camera: &dyn Camera, seed: u64| {
+        let render_settings = RenderSettings {
+            seed,
+            ..render_settings
+        };
 
-    let max_depth = 50;
-    // let samples_per_pixel: i32 = 100;
-    let samples_per_pixel: i32 = 500;
+        let pixels = render(&scene.world, &background, camera, &render_settings);
 
-    let world = generate_random_scene();
+        Framebuffer::from_samples(
+            image_settings.width,
+            image_settings.height,
+            pixels,
+            image_settings.samples_per_pixel,
+        )
+    };
 
-    let position = Point3(13.0, 2.0, 3.0);
-    let look_at = Point3(0.0, 0.0, 0.0);
-    let up = Point3(0.0, 1.0, 0.0);
+    let write_image = |image: &Framebuffer, out: &mut dyn Write| {
+        if let Err(error) = settings.format.encoder(settings.display).encode(image, out) {
+            eprintln!("\nerror: cannot write the image: {}", error);
+            process::exit(1);
+        }
 
-    let distance_to_focus = 10.0;
-    let aperture = 0.1;
+        out.flush().unwrap();
+    };
 
-    let options = Options {
-        vertical_fov: 20.0,
-        aspect_ratio,
-        aperture,
-        focus_distance: distance_to_focus,
+    let create = |path: &Path| match File::create(path) {
+        Ok(file) => BufWriter::new(file),
+        Err(error) => {
+            eprintln!("error: cannot create '{}': {}", path.display(), error);
+            process::exit(1);
+        }
     };
 
-    let camera = Camera::new(position, look_at, up, options, Interval::new(0.0, 1.0));
+    // Single images go to the output, frames and separate eyes to files
+    // named after it
+    let save = |image: &Framebuffer, frame: Option<u32>, eye: Option<Eye>| {
+        if frame.is_none() && eye.is_none() {
+            let mut out: Box<dyn Write> = match &settings.output {
+                Some(path) => Box::new(create(path)),
+                None => Box::new(BufWriter::new(stdout())),
+            };
 
-    println!("P3\n{} {}\n255", image_width, image_height);
+            write_image(image, &mut out);
+        } else {
+            let path = settings.image_path(frame, eye);
 
-    for line in (0..image_height).rev() {
-        eprint!("\rScanlines remaining: {} ", line);
+            write_image(image, &mut create(&path));
 
-        for column in 0..image_width {
-            let mut pixel_color = Color(0.0, 0.0, 0.0);
+            eprintln!("\nImage written to {}", path.display());
+        }
+    };
 
-            for _sample in 0..samples_per_pixel {
-                let u = (column as f64 + rand()) / (image_width - 1) as f64;
-                let v = (line as f64 + rand()) / (image_height - 1) as f64;
+    // Both eyes share the seed, so that their noise matches
+    let render_exposure = |interval: Interval, seed: u64, frame: Option<u32>| {
+        let render_eye = |eye: Option<Eye>| {
+            let camera = camera(interval, eye).unwrap_or_else(|error| exit_on_error(error));
 
-                let ray = camera.ray(u, v);
+            render_image(camera.as_ref(), seed)
+        };
 
-                pixel_color += ray_color(ray, &world, max_depth);
+        match stereo {
+            None => save(&render_eye(None), frame, None),
+            Some((_, StereoLayout::Separate)) => {
+                for eye in Eye::BOTH.iter() {
+                    save(&render_eye(Some(*eye)), frame, Some(*eye));
+                }
             }
+            Some((_, layout)) => {
+                let left = render_eye(Some(Eye::Left));
+                let right = render_eye(Some(Eye::Right));
 
-            write_color(&mut stdout(), pixel_color, samples_per_pixel);
+                let image = match layout {
+                    StereoLayout::OverUnder => left.above(&right),
+                    _ => left.beside(&right),
+                };
+
+                save(&image, frame, None);
+            }
+        }
+    };
+
+    match animation {
+        Some(animation) => {
+            for frame in animation.frames() {
+                // Noise changes between frames, like film grain
+                render_exposure(
+                    animation.frame_interval(frame),
+                    seed.wrapping_add(frame as u64),
+                    Some(frame),
+                );
+            }
         }
+        None => render_exposure(scene.time, seed, None),
     }
 
     eprintln!("\nDone.");
@@ -7,7 +7,7 @@ use crate::scene::BasicHitRecord;
 
 use crate::core::math::rand::rand;
 
-use super::material::{Material, ScatterRecord};
+use super::material::{Material, MaterialKind, ScatterRecord};
 use crate::core::time::TimeRay3;
 
 pub struct Dielectric {
@@ -77,4 +77,8 @@ impl Material for Dielectric {
 
         Some(scatter_record)
     }
+
+    fn kind(&self) -> MaterialKind<'_> {
+        MaterialKind::Dielectric(self)
+    }
 }
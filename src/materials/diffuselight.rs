@@ -0,0 +1,65 @@
+use crate::core::color::Color;
+use crate::core::time::TimeRay3;
+use crate::scene::BasicHitRecord;
+use crate::textures::{SolidColor, Texture};
+
+use super::material::{Material, MaterialKind, ScatterRecord};
+use std::sync::Arc;
+
+/// Light-emitting material, which does not scatter incoming rays.
+pub struct DiffuseLight {
+    emit: Arc<dyn Texture>,
+}
+
+#[allow(dead_code)]
+impl DiffuseLight {
+    pub fn new(emit: Arc<dyn Texture>) -> Self {
+        Self { emit }
+    }
+
+    pub fn from_color(color: Color) -> Self {
+        Self {
+            emit: Arc::new(SolidColor::new(color)),
+        }
+    }
+
+    pub fn emit(&self) -> Arc<dyn Texture> {
+        self.emit.clone()
+    }
+}
+
+impl Material for DiffuseLight {
+    fn scatter(&self, _in_ray: TimeRay3, _hit: BasicHitRecord) -> Option<ScatterRecord> {
+        None
+    }
+
+    fn emitted(&self, hit: &BasicHitRecord) -> Color {
+        self.emit.hit_value(hit)
+    }
+
+    fn kind(&self) -> MaterialKind<'_> {
+        MaterialKind::DiffuseLight(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::{Point3, Ray3, Vec2, Vec3};
+    use crate::textures::{Checker, VertexColor};
+
+    #[test]
+    fn must_emit_the_color_of_the_hit() {
+        let fallback = Arc::new(VertexColor::new(Color(1.0, 1.0, 1.0)));
+        let light = DiffuseLight::new(Arc::new(Checker::new(fallback.clone(), fallback)));
+
+        let point = Point3(0.05, 0.05, 0.05);
+        let ray = Ray3::new(Point3(0.05, 0.05, 1.0), Vec3(0.0, 0.0, -1.0));
+        let hit = BasicHitRecord::new(point, 0.95, Vec2(0.0, 0.0), ray, Vec3(0.0, 0.0, 1.0));
+
+        assert_eq!(Color(1.0, 1.0, 1.0), light.emitted(&hit));
+
+        let red = Color(1.0, 0.0, 0.0);
+        assert_eq!(red, light.emitted(&hit.with_vertex_color(red)));
+    }
+}
@@ -2,28 +2,28 @@ use crate::core::color::Color;
 use crate::core::math::rand::rand_unit_vector;
 use crate::scene::BasicHitRecord;
 
-use super::material::{Material, ScatterRecord};
+use super::material::{Material, MaterialKind, ScatterRecord};
 use crate::core::time::TimeRay3;
 use crate::textures::{SolidColor, Texture};
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub struct Lambertian {
-    albedo: Rc<dyn Texture>,
+    albedo: Arc<dyn Texture>,
 }
 
 #[allow(dead_code)]
 impl Lambertian {
-    pub fn new(color: Rc<dyn Texture>) -> Self {
+    pub fn new(color: Arc<dyn Texture>) -> Self {
         Self { albedo: color }
     }
 
     pub fn from_color(color: Color) -> Self {
-        let albedo = Rc::new(SolidColor::new(color));
+        let albedo = Arc::new(SolidColor::new(color));
 
         Self { albedo }
     }
 
-    pub fn albedo(&self) -> Rc<dyn Texture> {
+    pub fn albedo(&self) -> Arc<dyn Texture> {
         self.albedo.clone()
     }
 }
@@ -34,9 +34,13 @@ impl Material for Lambertian {
 
         let scatter_record = ScatterRecord {
             ray: TimeRay3::new(hit.point(), scatter_direction, in_ray.time()),
-            attenuation: self.albedo.value(hit.texture_coordinate(), hit.point()),
+            attenuation: self.albedo.hit_value(&hit),
         };
 
         Some(scatter_record)
     }
+
+    fn kind(&self) -> MaterialKind<'_> {
+        MaterialKind::Lambertian(self)
+    }
 }
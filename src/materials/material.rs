@@ -1,12 +1,34 @@
 use crate::core::color::Color;
 use crate::core::time::TimeRay3;
+use crate::materials::{Dielectric, DiffuseLight, Lambertian, Metal};
 use crate::scene::BasicHitRecord;
+use std::any::type_name;
 
 pub struct ScatterRecord {
     pub attenuation: Color,
     pub ray: TimeRay3,
 }
 
-pub trait Material {
+pub trait Material: Send + Sync {
     fn scatter(&self, in_ray: TimeRay3, hit: BasicHitRecord) -> Option<ScatterRecord>;
+
+    fn emitted(&self, _hit: &BasicHitRecord) -> Color {
+        Color(0.0, 0.0, 0.0)
+    }
+
+    /// What the material is, for code handling each kind of material on
+    /// its own, such as the export of scene files.
+    fn kind(&self) -> MaterialKind<'_> {
+        MaterialKind::Other(type_name::<Self>())
+    }
+}
+
+/// Concrete material behind a [`Material`].
+pub enum MaterialKind<'a> {
+    Lambertian(&'a Lambertian),
+    Metal(&'a Metal),
+    Dielectric(&'a Dielectric),
+    DiffuseLight(&'a DiffuseLight),
+    /// Any other material, named by its type
+    Other(&'static str),
 }
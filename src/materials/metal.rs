@@ -5,7 +5,7 @@ use crate::core::optic::Reflect;
 use crate::core::math::rand::rand_unit_vector;
 use crate::scene::BasicHitRecord;
 
-use super::material::{Material, ScatterRecord};
+use super::material::{Material, MaterialKind, ScatterRecord};
 use crate::core::time::TimeRay3;
 
 pub struct Metal {
@@ -46,4 +46,8 @@ impl Material for Metal {
 
         Some(scatter_record)
     }
+
+    fn kind(&self) -> MaterialKind<'_> {
+        MaterialKind::Metal(self)
+    }
 }
@@ -1,9 +1,11 @@
 mod dielectric;
+mod diffuselight;
 mod lambertian;
 mod material;
 mod metal;
 
 pub use dielectric::Dielectric;
+pub use diffuselight::DiffuseLight;
 pub use lambertian::Lambertian;
-pub use material::Material;
+pub use material::{Material, MaterialKind};
 pub use metal::Metal;
@@ -0,0 +1,198 @@
+mod tiles;
+
+pub use tiles::*;
+
+use std::sync::mpsc;
+use std::thread;
+
+use crate::core::color::Color;
+use crate::core::geometry::Vector;
+use crate::core::math::constants::INFINITY;
+use crate::core::math::rand::{self, rand};
+use crate::core::time::TimeRay3;
+use crate::scene::camera::Camera;
+use crate::scene::{Background, Hit};
+
+pub struct RenderSettings {
+    pub image_width: u32,
+    pub image_height: u32,
+    pub samples_per_pixel: u32,
+    pub max_depth: u32,
+    pub seed: u64,
+    pub threads: usize,
+    pub tile_size: u32,
+}
+
+pub fn ray_color(ray: TimeRay3, world: &dyn Hit, background: &Background, depth: u32) -> Color {
+    // Stop recursion at ray bounce limit
+    if depth == 0 {
+        return Color(0.0, 0.0, 0.0);
+    }
+
+    match world.hit(ray, 0.001, INFINITY) {
+        Some(material_hit) => {
+            let hit = material_hit.hit();
+            let material = material_hit.material();
+
+            let emitted = material.emitted(&hit);
+
+            material.scatter(ray, hit).map_or(emitted, |scr| {
+                emitted + scr.attenuation * ray_color(scr.ray, world, background, depth - 1)
+            })
+        }
+        None => background.color(ray),
+    }
+}
+
+/// Renders the image in tiles spread over `settings.threads` workers.
+///
+/// Returns the sum of the samples of every pixel, row by row, starting from
+/// the top of the image. Each pixel reseeds the random generator from
+/// `settings.seed` and its position, so the result does not depend on the
+/// number of threads.
+pub fn render(
+    world: &dyn Hit,
+    background: &Background,
+    camera: &dyn Camera,
+    settings: &RenderSettings,
+) -> Vec<Color> {
+    let width = settings.image_width as usize;
+    let height = settings.image_height as usize;
+
+    let mut pixels = vec![Color::zero(); width * height];
+
+    let tiles = Tile::split(
+        settings.image_width,
+        settings.image_height,
+        settings.tile_size,
+    );
+    let tile_count = tiles.len();
+    let queue = TileQueue::new(tiles, settings.threads);
+
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for worker in 0..queue.workers() {
+            let sender = sender.clone();
+            let queue = &queue;
+
+            scope.spawn(move || {
+                while let Some(tile) = queue.next(worker) {
+                    let colors = render_tile(world, background, camera, settings, tile);
+
+                    if sender.send((tile, colors)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+
+        drop(sender);
+
+        for (done, (tile, colors)) in receiver.iter().enumerate() {
+            eprint!("\rTiles remaining: {} ", tile_count - done - 1);
+
+            for (row, tile_row) in colors.chunks(tile.width as usize).enumerate() {
+                let start = (tile.y as usize + row) * width + tile.x as usize;
+
+                pixels[start..start + tile_row.len()].copy_from_slice(tile_row);
+            }
+        }
+    });
+
+    pixels
+}
+
+fn render_tile(
+    world: &dyn Hit,
+    background: &Background,
+    camera: &dyn Camera,
+    settings: &RenderSettings,
+    tile: Tile,
+) -> Vec<Color> {
+    let mut colors = Vec::with_capacity(tile.pixel_count());
+
+    let image_width = settings.image_width;
+    let image_height = settings.image_height;
+
+    for row in tile.y..(tile.y + tile.height) {
+        // Rows go from the top of the image, lines from the bottom
+        let line = image_height - 1 - row;
+
+        for column in tile.x..(tile.x + tile.width) {
+            rand::seed(pixel_seed(settings.seed, column, row));
+
+            let mut pixel_color = Color(0.0, 0.0, 0.0);
+
+            for _sample in 0..settings.samples_per_pixel {
+                let u = (column as f64 + rand()) / (image_width - 1) as f64;
+                let v = (line as f64 + rand()) / (image_height - 1) as f64;
+
+                // Parts of the image the camera does not see stay black
+                if let Some(ray) = camera.ray(u, v) {
+                    pixel_color += ray_color(ray, world, background, settings.max_depth);
+                }
+            }
+
+            colors.push(pixel_color);
+        }
+    }
+
+    colors
+}
+
+// SplitMix64 finalizer over the seed and the pixel position
+fn pixel_seed(seed: u64, column: u32, row: u32) -> u64 {
+    let mut z = seed ^ ((u64::from(row) << 32) | u64::from(column));
+
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::time::Interval;
+    use crate::scene::camera::Bokeh;
+    use crate::scene::Shutter;
+    use crate::scenes::{generate_scene_two_spheres, View};
+
+    fn render_with_threads(threads: usize) -> Vec<Color> {
+        let world = generate_scene_two_spheres();
+
+        let camera = View::default().camera(
+            2.0,
+            Interval::new(0.0, 1.0),
+            Shutter::default(),
+            &Bokeh::default(),
+        );
+
+        let settings = RenderSettings {
+            image_width: 20,
+            image_height: 10,
+            samples_per_pixel: 4,
+            max_depth: 5,
+            seed: 7,
+            threads,
+            tile_size: 3,
+        };
+
+        render(&world, &Background::sky(), camera.as_ref(), &settings)
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_not_depend_on_thread_count() {
+        let single = render_with_threads(1);
+        let multiple = render_with_threads(4);
+
+        assert_eq!(single.len(), multiple.len());
+
+        for (a, b) in single.iter().zip(multiple.iter()) {
+            assert_eq!((a.x(), a.y(), a.z()), (b.x(), b.y(), b.z()));
+        }
+    }
+}
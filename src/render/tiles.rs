@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// Rectangular region of the image, in pixels, with the origin on the
+/// top-left corner.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tile {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Tile {
+    pub fn split(image_width: u32, image_height: u32, tile_size: u32) -> Vec<Self> {
+        let mut tiles = Vec::new();
+
+        for y in (0..image_height).step_by(tile_size as usize) {
+            for x in (0..image_width).step_by(tile_size as usize) {
+                tiles.push(Self {
+                    x,
+                    y,
+                    width: tile_size.min(image_width - x),
+                    height: tile_size.min(image_height - y),
+                });
+            }
+        }
+
+        tiles
+    }
+
+    pub const fn pixel_count(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+}
+
+/// Work-stealing queue of tiles. Every worker owns a deque and takes work
+/// from its front; once it is empty, the worker steals from the back of the
+/// other deques.
+pub struct TileQueue {
+    deques: Vec<Mutex<VecDeque<Tile>>>,
+}
+
+impl TileQueue {
+    pub fn new(tiles: Vec<Tile>, workers: usize) -> Self {
+        let workers = workers.max(1);
+        let mut deques = vec![VecDeque::new(); workers];
+
+        for (index, tile) in tiles.into_iter().enumerate() {
+            deques[index % workers].push_back(tile);
+        }
+
+        Self {
+            deques: deques.into_iter().map(Mutex::new).collect(),
+        }
+    }
+
+    pub fn workers(&self) -> usize {
+        self.deques.len()
+    }
+
+    pub fn next(&self, worker: usize) -> Option<Tile> {
+        if let Some(tile) = self.deques[worker].lock().unwrap().pop_front() {
+            return Some(tile);
+        }
+
+        let workers = self.deques.len();
+
+        (1..workers)
+            .map(|offset| (worker + offset) % workers)
+            .find_map(|victim| self.deques[victim].lock().unwrap().pop_back())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn must_cover_image_with_tiles() {
+        let tiles = Tile::split(70, 33, 16);
+
+        assert_eq!(15, tiles.len());
+        assert_eq!(70 * 33, tiles.iter().map(Tile::pixel_count).sum::<usize>());
+        assert_eq!(
+            Tile {
+                x: 64,
+                y: 32,
+                width: 6,
+                height: 1
+            },
+            tiles[14]
+        );
+    }
+
+    #[test]
+    fn must_steal_from_other_workers() {
+        let queue = TileQueue::new(Tile::split(64, 16, 16), 2);
+
+        let mut taken = 0;
+
+        while queue.next(0).is_some() {
+            taken += 1;
+        }
+
+        assert_eq!(4, taken);
+        assert!(queue.next(1).is_none());
+    }
+}
@@ -0,0 +1,36 @@
+use crate::core::color::Color;
+use crate::core::geometry::{Ray, Vector};
+use crate::core::time::TimeRay3;
+
+/// Radiance of the rays that escape the scene.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Background {
+    Solid(Color),
+    /// Vertical blend between two colors, following the ray direction
+    Gradient {
+        bottom: Color,
+        top: Color,
+    },
+}
+
+impl Background {
+    pub const fn sky() -> Self {
+        Self::Gradient {
+            bottom: Color(1.0, 1.0, 1.0),
+            top: Color(0.5, 0.7, 1.0),
+        }
+    }
+
+    pub fn color(&self, ray: TimeRay3) -> Color {
+        match *self {
+            Self::Solid(color) => color,
+            Self::Gradient { bottom, top } => {
+                let unit = ray.direction().normalized();
+
+                let t: f64 = 0.5 * (unit.y() + 1.0);
+
+                ((1.0 - t) * bottom) + (t * top)
+            }
+        }
+    }
+}
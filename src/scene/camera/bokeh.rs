@@ -0,0 +1,354 @@
+use std::f64::consts::PI;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::core::geometry::{Vec2, Vector};
+use crate::image::Framebuffer;
+
+/// Shape of the opening of a thin lens, which out-of-focus highlights take.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ApertureShape {
+    #[default]
+    Circle,
+    /// Regular polygon of `blades` sides, turned by `rotation` degrees from
+    /// a corner pointing up
+    Polygon { blades: u32, rotation: f64 },
+    /// Opening given by the brightness of an image
+    Mask(Arc<ApertureMask>),
+}
+
+impl ApertureShape {
+    /// Names of the shapes, as used in scene files.
+    pub const NAMES: [&'static str; 3] = ["circle", "polygon", "mask"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Circle => "circle",
+            Self::Polygon { .. } => "polygon",
+            Self::Mask(_) => "mask",
+        }
+    }
+
+    /// Point of the shape, within the unit disk, for uniform random numbers
+    /// between 0 and 1. Every part of the opening is equally likely; polygons
+    /// have their corners on the circle, and masks the corners of the image.
+    pub fn sample(&self, u: Vec2) -> Vec2 {
+        match self {
+            Self::Circle => {
+                let radius = u.x().sqrt();
+                let (sin, cos) = (2.0 * PI * u.y()).sin_cos();
+
+                Vec2(radius * cos, radius * sin)
+            }
+            Self::Polygon { blades, rotation } => {
+                // Picks one of the triangles between the center and a side
+                let position = u.x() * *blades as f64;
+                let side = (position as u32).min(blades - 1);
+                let along = position - side as f64;
+
+                let corner = |index: u32| {
+                    let angle =
+                        0.5 * PI + rotation.to_radians() + 2.0 * PI * index as f64 / *blades as f64;
+
+                    Vec2(angle.cos(), angle.sin())
+                };
+
+                let edge = (1.0 - u.y()) * corner(side) + u.y() * corner(side + 1);
+
+                along.sqrt() * edge
+            }
+            Self::Mask(mask) => mask.sample(u),
+        }
+    }
+}
+
+/// Aperture drawn as an image: brighter pixels let more light through.
+///
+/// The image is inscribed in the unit disk, its corners on the circle, and
+/// is sampled by inverting the cumulative brightness of its rows, then of the pixels of
+/// the chosen row.
+#[derive(Debug, PartialEq)]
+pub struct ApertureMask {
+    width: u32,
+    height: u32,
+    /// Brightness of the rows before each row, from the top
+    rows: Vec<f64>,
+    /// Brightness of the pixels before each pixel of every row, each row
+    /// starting at 0 and holding `width + 1` values
+    pixels: Vec<f64>,
+    /// File the image was read from, needed to export the mask
+    source: Option<PathBuf>,
+}
+
+#[allow(dead_code)]
+impl ApertureMask {
+    /// Mask with the luminance of the `image`, which must not be all black.
+    pub fn new(image: &Framebuffer) -> Result<Self, &'static str> {
+        let width = image.width();
+        let height = image.height();
+
+        let mut rows = vec![0.0];
+        let mut pixels = Vec::with_capacity(((width + 1) * height) as usize);
+
+        for y in 0..height {
+            let mut sum = 0.0;
+            pixels.push(sum);
+
+            for x in 0..width {
+                let color = image.pixel(x, y);
+                let luminance = 0.2126 * color.x() + 0.7152 * color.y() + 0.0722 * color.z();
+
+                sum += luminance.max(0.0);
+                pixels.push(sum);
+            }
+
+            rows.push(rows.last().unwrap() + sum);
+        }
+
+        let total = *rows.last().unwrap();
+
+        if !(total.is_finite() && total > 0.0) {
+            return Err("the aperture mask must not be black");
+        }
+
+        Ok(Self {
+            width,
+            height,
+            rows,
+            pixels,
+            source: None,
+        })
+    }
+
+    pub fn with_source<P: AsRef<Path>>(self, path: P) -> Self {
+        Self {
+            source: Some(path.as_ref().to_path_buf()),
+            ..self
+        }
+    }
+
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    fn sample(&self, u: Vec2) -> Vec2 {
+        // Index of the bin of `cdf` holding `target`, and how far into it
+        let invert = |cdf: &[f64], target: f64| {
+            let next = cdf
+                .partition_point(|value| *value <= target)
+                .clamp(1, cdf.len() - 1);
+            let weight = cdf[next] - cdf[next - 1];
+
+            let along = if weight > 0.0 {
+                (target - cdf[next - 1]) / weight
+            } else {
+                0.5
+            };
+
+            (next - 1, along.clamp(0.0, 1.0))
+        };
+
+        let (row, y) = invert(
+            &self.rows,
+            u.y().clamp(0.0, 1.0) * self.rows.last().unwrap(),
+        );
+
+        let start = row * (self.width as usize + 1);
+        let pixels = &self.pixels[start..=start + self.width as usize];
+        let (column, x) = invert(pixels, u.x().clamp(0.0, 1.0) * pixels.last().unwrap());
+
+        let diagonal = (self.width as f64).hypot(self.height as f64);
+
+        Vec2(
+            (2.0 * (column as f64 + x) - self.width as f64) / diagonal,
+            (self.height as f64 - 2.0 * (row as f64 + y)) / diagonal,
+        )
+    }
+}
+
+/// Out-of-focus highlights of a thin lens: the shape of its aperture, and
+/// the cat's eye vignetting of the lens barrel.
+///
+/// Towards the edges of the image, the barrel hides part of the aperture,
+/// as a second circle moving away from it. The highlights get the shape of
+/// a cat's eye, and the corners darken.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Bokeh {
+    shape: ApertureShape,
+    /// Shift of the circle of the barrel at the corners of the image, as a
+    /// fraction of the aperture diameter
+    cat_eye: f64,
+}
+
+#[allow(dead_code)]
+impl Bokeh {
+    /// Bokeh of the `shape`, which must be valid.
+    pub fn new(shape: ApertureShape) -> Result<Self, &'static str> {
+        if let ApertureShape::Polygon { blades, .. } = shape {
+            if blades < 3 {
+                return Err("expected at least 3 blades");
+            }
+        }
+
+        Ok(Self {
+            shape,
+            cat_eye: 0.0,
+        })
+    }
+
+    /// Adds cat's eye vignetting, from 0 (none) to 1, where the corners of
+    /// the image get no light.
+    pub fn with_cat_eye(self, cat_eye: f64) -> Self {
+        Self { cat_eye, ..self }
+    }
+
+    pub const fn shape(&self) -> &ApertureShape {
+        &self.shape
+    }
+
+    pub const fn cat_eye(&self) -> f64 {
+        self.cat_eye
+    }
+
+    /// Point of the aperture, within the unit disk, for uniform random
+    /// numbers `u`, as seen from the point `image` of the image, centered
+    /// and scaled so that the corners are at a distance of 1. `None` when the
+    /// barrel hides the point.
+    pub fn sample(&self, u: Vec2, image: Vec2) -> Option<Vec2> {
+        let point = self.shape.sample(u);
+
+        if self.cat_eye == 0.0 {
+            return Some(point);
+        }
+
+        // The barrel moves towards the center of the image
+        let barrel = -2.0 * self.cat_eye * image;
+
+        if (point - barrel).sq_length() <= 1.0 {
+            Some(point)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.shape == ApertureShape::Circle && self.cat_eye == 0.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::color::Color;
+
+    /// Evenly spread random numbers, `count` by `count`.
+    fn grid(count: usize) -> impl Iterator<Item = Vec2> {
+        (0..count * count).map(move |index| {
+            Vec2(
+                ((index % count) as f64 + 0.5) / count as f64,
+                ((index / count) as f64 + 0.5) / count as f64,
+            )
+        })
+    }
+
+    #[test]
+    fn must_sample_inside_polygons() {
+        let square = ApertureShape::Polygon {
+            blades: 4,
+            rotation: 45.0,
+        };
+
+        let half = 0.5_f64.sqrt();
+        let mut quadrants = [0; 4];
+
+        for u in grid(100) {
+            let point = square.sample(u);
+
+            // Corners at 45°: the sides are axis aligned
+            assert!(point.x().abs() <= half + 1e-12 && point.y().abs() <= half + 1e-12);
+
+            let quadrant = (point.x() > 0.0) as usize + 2 * (point.y() > 0.0) as usize;
+            quadrants[quadrant] += 1;
+        }
+
+        assert_eq!([2500; 4], quadrants);
+    }
+
+    #[test]
+    fn must_sample_masks_by_brightness() {
+        // Left half black, right column twice as bright as the middle one
+        let mut image = Framebuffer::new(4, 2);
+
+        for y in 0..2 {
+            image.set_pixel(2, y, Color(1.0, 1.0, 1.0));
+            image.set_pixel(3, y, Color(2.0, 2.0, 2.0));
+        }
+
+        let mask = ApertureMask::new(&image).unwrap();
+        let pixel = 2.0 / 20.0_f64.sqrt();
+        let mut right = 0;
+
+        for u in grid(60) {
+            let point = mask.sample(u);
+
+            assert!((0.0..=2.0 * pixel).contains(&point.x()), "{}", point.x());
+            assert!((-pixel..=pixel).contains(&point.y()), "{}", point.y());
+
+            if point.x() > pixel {
+                right += 1;
+            }
+        }
+
+        assert_eq!(2400, right);
+
+        assert!(ApertureMask::new(&Framebuffer::new(2, 2)).is_err());
+    }
+
+    #[test]
+    fn must_keep_the_corners_of_masks() {
+        // Only the corners of the image let light through
+        let mut image = Framebuffer::new(8, 8);
+
+        for (x, y) in [(0, 0), (7, 0), (0, 7), (7, 7)] {
+            image.set_pixel(x, y, Color(1.0, 1.0, 1.0));
+        }
+
+        let shape = ApertureShape::Mask(Arc::new(ApertureMask::new(&image).unwrap()));
+        let bokeh = Bokeh::new(shape.clone()).unwrap();
+
+        for u in grid(50) {
+            let point = shape.sample(u);
+
+            assert!(point.sq_length() <= 1.0 + 1e-12, "{}", point.length());
+            assert_eq!(Some(point), bokeh.sample(u, Vec2(1.0, 0.0)));
+        }
+    }
+
+    #[test]
+    fn must_hide_the_aperture_towards_the_corners() {
+        let bokeh = Bokeh::default().with_cat_eye(0.25);
+
+        let visible = |image: Vec2| {
+            grid(50)
+                .filter(|u| bokeh.sample(*u, image).is_some())
+                .count()
+        };
+
+        assert_eq!(2500, visible(Vec2(0.0, 0.0)));
+
+        let corner = visible(Vec2(0.6, 0.8));
+        let edge = visible(Vec2(0.3, 0.4));
+
+        assert!(corner < edge && edge < 2500, "{} {}", corner, edge);
+
+        // Only the part of the aperture towards the center remains
+        assert!(bokeh.sample(Vec2(0.99, 0.0), Vec2(1.0, 0.0)).is_none());
+        assert!(bokeh.sample(Vec2(0.99, 0.5), Vec2(1.0, 0.0)).is_some());
+
+        assert!(Bokeh::new(ApertureShape::Polygon {
+            blades: 2,
+            rotation: 0.0
+        })
+        .is_err());
+    }
+}
@@ -0,0 +1,174 @@
+use super::{Camera, CameraKind, Placement};
+use crate::core::geometry::Vec3;
+use crate::core::time::TimeRay3;
+
+/// Face of a cube map, named after the axis of the camera space it looks
+/// along.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl CubeFace {
+    /// Faces in the order of a horizontal strip.
+    pub const ALL: [Self; 6] = [
+        Self::PositiveX,
+        Self::NegativeX,
+        Self::PositiveY,
+        Self::NegativeY,
+        Self::PositiveZ,
+        Self::NegativeZ,
+    ];
+
+    /// Names accepted by [`CubeFace::from_name`].
+    pub const NAMES: [&'static str; 6] = ["px", "nx", "py", "ny", "pz", "nz"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .position(|candidate| *candidate == name)
+            .map(|index| Self::ALL[index])
+    }
+
+    pub fn name(&self) -> &'static str {
+        Self::NAMES[Self::ALL.iter().position(|face| face == self).unwrap()]
+    }
+
+    /// Direction in the camera space through the point (`a`, `b`) of the
+    /// face, both from -1 to 1. Faces are seen from inside the cube, upright
+    /// for the sides, and as if tilting the head from -z for +y and -y.
+    fn direction(&self, a: f64, b: f64) -> Vec3 {
+        match self {
+            Self::PositiveX => Vec3(1.0, b, a),
+            Self::NegativeX => Vec3(-1.0, b, -a),
+            Self::PositiveY => Vec3(a, 1.0, b),
+            Self::NegativeY => Vec3(a, -1.0, -b),
+            Self::PositiveZ => Vec3(-a, b, 1.0),
+            Self::NegativeZ => Vec3(a, b, -1.0),
+        }
+    }
+}
+
+/// Panorama as the faces of a cube around the camera: a single face, or the
+/// six of them side by side, in the order of [`CubeFace::ALL`].
+pub struct CubemapCamera {
+    placement: Placement,
+    face: Option<CubeFace>,
+}
+
+#[allow(dead_code)]
+impl CubemapCamera {
+    pub const fn new(placement: Placement, face: Option<CubeFace>) -> Self {
+        Self { placement, face }
+    }
+
+    pub const fn face(&self) -> Option<CubeFace> {
+        self.face
+    }
+}
+
+impl Camera for CubemapCamera {
+    fn ray(&self, s: f64, t: f64) -> Option<TimeRay3> {
+        let (face, s) = match self.face {
+            Some(face) => (face, s),
+            None => {
+                let position = s.clamp(0.0, 1.0) * 6.0;
+                let index = (position as usize).min(5);
+
+                (CubeFace::ALL[index], position - index as f64)
+            }
+        };
+
+        let direction = face.direction(2.0 * s - 1.0, 2.0 * t - 1.0);
+
+        Some(TimeRay3::new(
+            self.placement.position(),
+            self.placement.to_scene(direction),
+            self.placement.time(t),
+        ))
+    }
+
+    fn placement(&self) -> &Placement {
+        &self.placement
+    }
+
+    fn kind(&self) -> CameraKind<'_> {
+        CameraKind::Cubemap(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::{Point3, Vector};
+    use crate::core::time::Interval;
+
+    fn placement() -> Placement {
+        Placement::new(
+            Point3(0.0, 0.0, 0.0),
+            Point3(0.0, 0.0, -1.0),
+            Vec3(0.0, 1.0, 0.0),
+            Interval::new(0.0, 0.0),
+        )
+    }
+
+    #[test]
+    fn must_look_along_each_face() {
+        let strip = CubemapCamera::new(placement(), None);
+
+        let expected = [
+            Vec3(1.0, 0.0, 0.0),
+            Vec3(-1.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+            Vec3(0.0, -1.0, 0.0),
+            Vec3(0.0, 0.0, 1.0),
+            Vec3(0.0, 0.0, -1.0),
+        ];
+
+        for (index, expected) in expected.iter().enumerate() {
+            let s = (index as f64 + 0.5) / 6.0;
+            let direction = strip.ray(s, 0.5).unwrap().direction();
+
+            assert!((direction - *expected).length() < 1e-12, "{}", index);
+
+            let face = CubemapCamera::new(placement(), Some(CubeFace::ALL[index]));
+            let direction = face.ray(0.5, 0.5).unwrap().direction();
+
+            assert!((direction - *expected).length() < 1e-12, "{}", index);
+        }
+    }
+
+    #[test]
+    fn must_join_faces_on_their_edges() {
+        let strip = CubemapCamera::new(placement(), None);
+
+        // Turning right from the view direction goes from -Z to +X
+        let front = CubemapCamera::new(placement(), Some(CubeFace::NegativeZ));
+        let right = CubemapCamera::new(placement(), Some(CubeFace::PositiveX));
+
+        let front_edge = front.ray(1.0, 0.3).unwrap().direction();
+        let right_edge = right.ray(0.0, 0.3).unwrap().direction();
+
+        assert!((front_edge - right_edge).length() < 1e-12);
+
+        // Looking up from -Z, the top of +Y is behind the camera
+        let up = CubemapCamera::new(placement(), Some(CubeFace::PositiveY));
+        let top = up.ray(0.5, 1.0).unwrap().direction();
+        let bottom = up.ray(0.5, 0.0).unwrap().direction();
+
+        assert!((top - Vec3(0.0, 1.0, 1.0)).length() < 1e-12);
+        assert!((bottom - front.ray(0.5, 1.0).unwrap().direction()).length() < 1e-12);
+
+        // The last column stays on the last face
+        let last = strip.ray(1.0, 0.5).unwrap().direction();
+        assert!((last - Vec3(1.0, 0.0, -1.0)).length() < 1e-12);
+
+        assert_eq!(Some(CubeFace::NegativeY), CubeFace::from_name("ny"));
+        assert_eq!("pz", CubeFace::PositiveZ.name());
+    }
+}
@@ -0,0 +1,108 @@
+use std::f64::consts::PI;
+
+use super::{Camera, CameraKind, Placement};
+use crate::core::geometry::Vec3;
+use crate::core::time::TimeRay3;
+
+/// 360° panorama: the width of the image spans the longitudes and the
+/// height the latitudes, with the view direction at the center. Images
+/// usually have an aspect ratio of 2.
+pub struct EquirectangularCamera {
+    placement: Placement,
+    /// Signed radius of the circle the rays start from, for one eye of an
+    /// omni-directional stereo pair; negative for the left eye
+    eye_offset: f64,
+}
+
+#[allow(dead_code)]
+impl EquirectangularCamera {
+    pub const fn new(placement: Placement) -> Self {
+        Self {
+            placement,
+            eye_offset: 0.0,
+        }
+    }
+
+    /// Starts each ray on the circle of the eyes, tangent to it, as seen by
+    /// the eye `eye_offset` to the right of the position.
+    pub fn with_eye_offset(self, eye_offset: f64) -> Self {
+        Self { eye_offset, ..self }
+    }
+}
+
+impl Camera for EquirectangularCamera {
+    fn ray(&self, s: f64, t: f64) -> Option<TimeRay3> {
+        let longitude = (s - 0.5) * 2.0 * PI;
+        let latitude = (t - 0.5) * PI;
+
+        let (sin_longitude, cos_longitude) = longitude.sin_cos();
+        let (sin_latitude, cos_latitude) = latitude.sin_cos();
+
+        let direction = Vec3(
+            cos_latitude * sin_longitude,
+            sin_latitude,
+            -cos_latitude * cos_longitude,
+        );
+
+        // Right of the horizontal part of the direction
+        let side = Vec3(cos_longitude, 0.0, sin_longitude);
+        let origin = self.placement.position() + self.placement.to_scene(self.eye_offset * side);
+
+        Some(TimeRay3::new(
+            origin,
+            self.placement.to_scene(direction),
+            self.placement.time(t),
+        ))
+    }
+
+    fn placement(&self) -> &Placement {
+        &self.placement
+    }
+
+    fn kind(&self) -> CameraKind<'_> {
+        CameraKind::Equirectangular(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::{Point3, Vector};
+    use crate::core::time::Interval;
+
+    #[test]
+    fn must_cover_the_sphere() {
+        let placement = Placement::new(
+            Point3(1.0, 2.0, 3.0),
+            Point3(1.0, 2.0, 2.0),
+            Vec3(0.0, 1.0, 0.0),
+            Interval::new(0.0, 0.0),
+        );
+        let camera = EquirectangularCamera::new(placement);
+
+        let direction = |s: f64, t: f64| camera.ray(s, t).unwrap().direction();
+
+        let expected = [
+            ((0.5, 0.5), Vec3(0.0, 0.0, -1.0)),
+            ((0.75, 0.5), Vec3(1.0, 0.0, 0.0)),
+            ((0.25, 0.5), Vec3(-1.0, 0.0, 0.0)),
+            ((0.0, 0.5), Vec3(0.0, 0.0, 1.0)),
+            ((0.3, 1.0), Vec3(0.0, 1.0, 0.0)),
+            ((0.9, 0.0), Vec3(0.0, -1.0, 0.0)),
+        ];
+
+        for ((s, t), expected) in expected.iter() {
+            assert!(
+                (direction(*s, *t) - *expected).length() < 1e-12,
+                "({}, {})",
+                s,
+                t
+            );
+        }
+
+        assert_eq!(
+            Point3(1.0, 2.0, 3.0),
+            camera.ray(0.1, 0.1).unwrap().origin()
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use super::{Camera, CameraKind, Placement};
+use crate::core::geometry::Vec3;
+use crate::core::time::TimeRay3;
+
+/// How the angle of a direction from the axis of a fisheye lens maps to
+/// the distance from the center of the image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum FisheyeMapping {
+    /// Distance proportional to the angle
+    Equidistant,
+    /// Equal areas of the image cover equal solid angles
+    Equisolid,
+}
+
+impl FisheyeMapping {
+    /// Names accepted by [`FisheyeMapping::from_name`].
+    pub const NAMES: [&'static str; 2] = ["equidistant", "equisolid"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "equidistant" => Some(Self::Equidistant),
+            "equisolid" => Some(Self::Equisolid),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Equidistant => "equidistant",
+            Self::Equisolid => "equisolid",
+        }
+    }
+}
+
+/// Circular fisheye: the field of view spans the circle inscribed in the
+/// image, and the corners outside it stay black.
+pub struct FisheyeCamera {
+    placement: Placement,
+    mapping: FisheyeMapping,
+    /// In degrees, up to 360
+    fov: f64,
+    /// Half widths of the image, with the shorter side being 1
+    extent: (f64, f64),
+}
+
+#[allow(dead_code)]
+impl FisheyeCamera {
+    pub fn new(placement: Placement, mapping: FisheyeMapping, fov: f64, aspect_ratio: f64) -> Self {
+        let extent = if aspect_ratio >= 1.0 {
+            (aspect_ratio, 1.0)
+        } else {
+            (1.0, 1.0 / aspect_ratio)
+        };
+
+        Self {
+            placement,
+            mapping,
+            fov,
+            extent,
+        }
+    }
+
+    pub const fn mapping(&self) -> FisheyeMapping {
+        self.mapping
+    }
+
+    pub const fn fov(&self) -> f64 {
+        self.fov
+    }
+
+    /// Direction in the camera space seen at the point (`x`, `y`) of the
+    /// image, centered and scaled so that the image circle has radius 1.
+    fn direction(&self, x: f64, y: f64) -> Option<Vec3> {
+        let radius = x.hypot(y);
+
+        if radius > 1.0 {
+            return None;
+        }
+
+        let max_angle = (self.fov / 2.0).to_radians();
+
+        let angle = match self.mapping {
+            FisheyeMapping::Equidistant => radius * max_angle,
+            FisheyeMapping::Equisolid => 2.0 * (radius * (max_angle / 2.0).sin()).asin(),
+        };
+
+        let (sin, cos) = angle.sin_cos();
+        let (x, y) = if radius > 0.0 {
+            (x / radius, y / radius)
+        } else {
+            (0.0, 0.0)
+        };
+
+        Some(Vec3(sin * x, sin * y, -cos))
+    }
+}
+
+impl Camera for FisheyeCamera {
+    fn ray(&self, s: f64, t: f64) -> Option<TimeRay3> {
+        let x = (2.0 * s - 1.0) * self.extent.0;
+        let y = (2.0 * t - 1.0) * self.extent.1;
+
+        let direction = self.placement.to_scene(self.direction(x, y)?);
+
+        Some(TimeRay3::new(
+            self.placement.position(),
+            direction,
+            self.placement.time(t),
+        ))
+    }
+
+    fn placement(&self) -> &Placement {
+        &self.placement
+    }
+
+    fn kind(&self) -> CameraKind<'_> {
+        CameraKind::Fisheye(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::{Point3, Vector};
+    use crate::core::time::Interval;
+
+    fn camera(mapping: FisheyeMapping, fov: f64) -> FisheyeCamera {
+        let placement = Placement::new(
+            Point3(0.0, 0.0, 0.0),
+            Point3(0.0, 0.0, -1.0),
+            Vec3(0.0, 1.0, 0.0),
+            Interval::new(0.0, 0.0),
+        );
+
+        FisheyeCamera::new(placement, mapping, fov, 2.0)
+    }
+
+    fn angle_from_axis(camera: &FisheyeCamera, s: f64, t: f64) -> f64 {
+        let direction = camera.ray(s, t).unwrap().direction().normalized();
+
+        direction.dot(Vec3(0.0, 0.0, -1.0)).acos().to_degrees()
+    }
+
+    #[test]
+    fn must_map_angles_to_radii() {
+        let equidistant = camera(FisheyeMapping::Equidistant, 180.0);
+
+        // The image circle touches the top of the wide image
+        assert!((angle_from_axis(&equidistant, 0.5, 0.5)).abs() < 1e-9);
+        assert!((angle_from_axis(&equidistant, 0.5, 1.0) - 90.0).abs() < 1e-9);
+        assert!((angle_from_axis(&equidistant, 0.5, 0.75) - 45.0).abs() < 1e-9);
+
+        let equisolid = camera(FisheyeMapping::Equisolid, 180.0);
+
+        assert!((angle_from_axis(&equisolid, 0.5, 1.0) - 90.0).abs() < 1e-9);
+
+        // Halfway out, sin(angle / 2) is half of sin(45°)
+        let expected = 2.0 * (0.5 * 45.0_f64.to_radians().sin()).asin().to_degrees();
+        assert!((angle_from_axis(&equisolid, 0.5, 0.75) - expected).abs() < 1e-9);
+
+        // Right and up in the image are right and up in the scene
+        let direction = equidistant.ray(0.6, 0.5).unwrap().direction();
+        assert!(direction.x() > 0.0 && direction.y().abs() < 1e-12);
+    }
+
+    #[test]
+    fn must_leave_corners_black() {
+        let camera = camera(FisheyeMapping::Equidistant, 220.0);
+
+        assert!(camera.ray(0.0, 0.0).is_none());
+        assert!(camera.ray(0.1, 0.5).is_none());
+
+        // Wider than a hemisphere at the edge
+        assert!((angle_from_axis(&camera, 0.25, 0.5) - 110.0).abs() < 1e-9);
+    }
+}
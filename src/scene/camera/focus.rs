@@ -0,0 +1,72 @@
+use crate::core::geometry::{Point3, Vector};
+use crate::core::time::{TimeRay3, Timestamp};
+use crate::scene::Hit;
+
+/// What a camera focuses on, instead of a focus distance given by hand.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Focus {
+    /// Point of the scene
+    Point(Point3),
+    /// First object along the axis of the camera, found with a probe ray
+    Auto,
+}
+
+#[allow(dead_code)]
+impl Focus {
+    /// Distance along the axis of a camera at `look_from`, looking at
+    /// `look_at`, to the plane in focus at `time`, or `None` when there is
+    /// nothing in front of the camera to focus on.
+    pub fn distance(
+        &self,
+        look_from: Point3,
+        look_at: Point3,
+        world: &dyn Hit,
+        time: Timestamp,
+    ) -> Option<f64> {
+        let axis = (look_at - look_from).normalized();
+
+        let distance = match self {
+            Self::Point(point) => (*point - look_from).dot(axis),
+            Self::Auto => world
+                .hit(TimeRay3::new(look_from, axis, time), 0.001, f64::INFINITY)?
+                .t(),
+        };
+
+        Some(distance).filter(|distance| distance.is_finite() && *distance > 0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::scene::object::sphere::Sphere;
+    use crate::scene::HitList;
+    use crate::testing::grey_material;
+
+    #[test]
+    fn must_focus_along_the_axis() {
+        let look_from = Point3(0.0, 0.0, 0.0);
+        let look_at = Point3(0.0, 0.0, -1.0);
+
+        let mut world = HitList::new();
+        world.add(Box::new(Sphere::new(
+            Point3(0.0, 0.0, -5.0),
+            1.0,
+            grey_material(),
+        )));
+
+        // Points off the axis focus at the distance of their plane
+        let point = Focus::Point(Point3(3.0, 1.0, -6.0));
+        assert_eq!(Some(6.0), point.distance(look_from, look_at, &world, 0.0));
+
+        let behind = Focus::Point(Point3(0.0, 0.0, 2.0));
+        assert_eq!(None, behind.distance(look_from, look_at, &world, 0.0));
+
+        let auto = Focus::Auto.distance(look_from, look_at, &world, 0.0);
+        assert!((auto.unwrap() - 4.0).abs() < 1e-9);
+
+        let sideways = Focus::Auto.distance(look_from, Point3(1.0, 0.0, 0.0), &world, 0.0);
+        assert_eq!(None, sideways);
+    }
+}
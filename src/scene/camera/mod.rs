@@ -0,0 +1,181 @@
+mod bokeh;
+mod cubemap;
+mod equirectangular;
+mod fisheye;
+mod focus;
+mod orthographic;
+mod perspective;
+mod realistic;
+mod stereo;
+
+pub use bokeh::*;
+pub use cubemap::*;
+pub use equirectangular::*;
+pub use fisheye::*;
+pub use focus::*;
+pub use orthographic::*;
+pub use perspective::*;
+pub use realistic::*;
+pub use stereo::*;
+
+use crate::core::geometry::{Point3, Vec3, Vector};
+use crate::core::math::rand::rand;
+use crate::core::time::{Interval, TimeRay3, Timestamp};
+use crate::scene::Shutter;
+
+/// Source of the rays of an image.
+pub trait Camera: Send + Sync {
+    /// Ray through the point (`s`, `t`) of the image, both from 0 to 1 from
+    /// the bottom left corner, or `None` where the camera sees nothing.
+    fn ray(&self, s: f64, t: f64) -> Option<TimeRay3>;
+
+    fn placement(&self) -> &Placement;
+
+    /// What the camera is, for code handling each kind of camera on its
+    /// own, such as the export of scene files.
+    fn kind(&self) -> CameraKind<'_>;
+}
+
+/// Concrete camera behind a [`Camera`].
+#[allow(dead_code)]
+pub enum CameraKind<'a> {
+    Perspective(&'a PerspectiveCamera),
+    Orthographic(&'a OrthographicCamera),
+    Fisheye(&'a FisheyeCamera),
+    Equirectangular(&'a EquirectangularCamera),
+    Cubemap(&'a CubemapCamera),
+    Realistic(&'a RealisticCamera),
+}
+
+/// Position, orientation and shutter, shared by every camera.
+///
+/// The camera space has x to the right, y up and z backwards, so cameras
+/// look along -z.
+#[derive(Clone)]
+pub struct Placement {
+    position: Point3,
+    look_at: Point3,
+    up: Vec3,
+
+    u: Vec3,
+    v: Vec3,
+    w: Vec3,
+
+    time_interval: Interval,
+    shutter: Shutter,
+}
+
+#[allow(dead_code)]
+impl Placement {
+    pub fn new(position: Point3, look_at: Point3, up: Vec3, time_interval: Interval) -> Self {
+        let w = (position - look_at).normalized();
+        let u = up.cross(w).normalized();
+        let v = w.cross(u);
+
+        Self {
+            position,
+            look_at,
+            up,
+            u,
+            v,
+            w,
+            time_interval,
+            shutter: Shutter::default(),
+        }
+    }
+
+    pub fn with_shutter(self, shutter: Shutter) -> Self {
+        Self { shutter, ..self }
+    }
+
+    pub const fn position(&self) -> Point3 {
+        self.position
+    }
+
+    pub const fn look_at(&self) -> Point3 {
+        self.look_at
+    }
+
+    pub const fn up(&self) -> Vec3 {
+        self.up
+    }
+
+    /// Right, up and backward axes of the camera.
+    pub const fn basis(&self) -> (Vec3, Vec3, Vec3) {
+        (self.u, self.v, self.w)
+    }
+
+    pub const fn time_interval(&self) -> Interval {
+        self.time_interval
+    }
+
+    pub const fn shutter(&self) -> &Shutter {
+        &self.shutter
+    }
+
+    /// Direction in the scene of a `direction` in the camera space.
+    pub fn to_scene(&self, direction: Vec3) -> Vec3 {
+        direction.x() * self.u + direction.y() * self.v + direction.z() * self.w
+    }
+
+    /// Random time of a ray through the row at height `t` of the image.
+    pub fn time(&self, t: f64) -> Timestamp {
+        self.shutter.time(self.time_interval, t, rand())
+    }
+}
+
+/// How a camera maps the directions of the scene to the image.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum Projection {
+    /// Thin lens, with the field of view and the aperture of the view
+    #[default]
+    Perspective,
+    /// Parallel rays through a window `height` high
+    Orthographic { height: f64 },
+    /// Circular fisheye covering `fov` degrees across the shorter side
+    Fisheye { mapping: FisheyeMapping, fov: f64 },
+    /// Whole sphere of directions, as longitude and latitude
+    Equirectangular,
+    /// One face of a cube map, or the six faces side by side
+    Cubemap { face: Option<CubeFace> },
+    /// System of lens elements, focused at the focus distance of the view
+    Realistic(RealisticLens),
+}
+
+impl Projection {
+    /// Names accepted by [`Projection::from_name`].
+    pub const NAMES: [&'static str; 6] = [
+        "perspective",
+        "orthographic",
+        "fisheye",
+        "equirectangular",
+        "cubemap",
+        "realistic",
+    ];
+
+    /// Projection of the given name, with default parameters.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "perspective" => Some(Self::Perspective),
+            "orthographic" => Some(Self::Orthographic { height: 2.0 }),
+            "fisheye" => Some(Self::Fisheye {
+                mapping: FisheyeMapping::Equidistant,
+                fov: 180.0,
+            }),
+            "equirectangular" => Some(Self::Equirectangular),
+            "cubemap" => Some(Self::Cubemap { face: None }),
+            "realistic" => Some(Self::Realistic(RealisticLens::default())),
+            _ => None,
+        }
+    }
+
+    /// Usual aspect ratio of the images, if the projection has one.
+    pub fn aspect_ratio(&self) -> Option<f64> {
+        match self {
+            Self::Equirectangular => Some(2.0),
+            Self::Cubemap { face: None } => Some(6.0),
+            Self::Cubemap { face: Some(_) } => Some(1.0),
+            _ => None,
+        }
+    }
+}
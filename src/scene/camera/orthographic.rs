@@ -0,0 +1,72 @@
+use super::{Camera, CameraKind, Placement};
+use crate::core::geometry::Vec3;
+use crate::core::time::TimeRay3;
+
+/// Camera with parallel rays, keeping the sizes of objects regardless of
+/// their distance.
+pub struct OrthographicCamera {
+    placement: Placement,
+    /// Size of the window the rays go through, in scene units
+    width: f64,
+    height: f64,
+}
+
+#[allow(dead_code)]
+impl OrthographicCamera {
+    pub fn new(placement: Placement, height: f64, aspect_ratio: f64) -> Self {
+        Self {
+            placement,
+            width: aspect_ratio * height,
+            height,
+        }
+    }
+
+    pub const fn height(&self) -> f64 {
+        self.height
+    }
+}
+
+impl Camera for OrthographicCamera {
+    fn ray(&self, s: f64, t: f64) -> Option<TimeRay3> {
+        let offset = Vec3((s - 0.5) * self.width, (t - 0.5) * self.height, 0.0);
+
+        let origin = self.placement.position() + self.placement.to_scene(offset);
+        let direction = self.placement.to_scene(Vec3(0.0, 0.0, -1.0));
+
+        Some(TimeRay3::new(origin, direction, self.placement.time(t)))
+    }
+
+    fn placement(&self) -> &Placement {
+        &self.placement
+    }
+
+    fn kind(&self) -> CameraKind<'_> {
+        CameraKind::Orthographic(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::{Point3, Vector};
+    use crate::core::time::Interval;
+
+    #[test]
+    fn must_cast_parallel_rays() {
+        let placement = Placement::new(
+            Point3(0.0, 0.0, 5.0),
+            Point3(0.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+            Interval::new(0.0, 0.0),
+        );
+        let camera = OrthographicCamera::new(placement, 2.0, 2.0);
+
+        let corner = camera.ray(0.0, 1.0).unwrap();
+        let center = camera.ray(0.5, 0.5).unwrap();
+
+        assert!((corner.origin() - Point3(-2.0, 1.0, 5.0)).length() < 1e-12);
+        assert!((center.origin() - Point3(0.0, 0.0, 5.0)).length() < 1e-12);
+        assert!((corner.direction() - center.direction()).length() < 1e-12);
+        assert!((center.direction() - Vec3(0.0, 0.0, -1.0)).length() < 1e-12);
+    }
+}
@@ -0,0 +1,192 @@
+use super::{Bokeh, Camera, CameraKind, Placement};
+use crate::core::geometry::Vector;
+use crate::core::geometry::{Point3, Vec2, Vec3};
+use crate::core::time::TimeRay3;
+
+/// Thin lens camera, with depth of field, and the tilt and shift of view
+/// camera lenses.
+pub struct PerspectiveCamera {
+    placement: Placement,
+    options: Options,
+    bokeh: Bokeh,
+
+    lower_left_corner: Point3,
+
+    horizontal: Vec3,
+    vertical: Vec3,
+
+    lens_radius: f64,
+
+    /// Normal of the plane in focus, when tilted
+    focal_normal: Option<Vec3>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Options {
+    pub vertical_fov: f64,
+    pub aspect_ratio: f64,
+    pub aperture: f64,
+    pub focus_distance: f64,
+    /// Angle of the plane in focus around the horizontal axis, in degrees;
+    /// positive angles bring it closer below the axis, like the ground
+    pub tilt: f64,
+    /// Offset of the image, in image widths and heights, which moves the
+    /// framing without turning the camera
+    pub shift: Vec2,
+}
+
+#[allow(dead_code)]
+impl PerspectiveCamera {
+    pub fn new(placement: Placement, options: Options) -> Self {
+        let theta: f64 = options.vertical_fov.to_radians();
+        let h: f64 = (theta / 2.0).tan();
+
+        let viewport_height: f64 = 2.0 * h;
+        let viewport_width: f64 = options.aspect_ratio * viewport_height;
+
+        let (u, v, w) = placement.basis();
+
+        let horizontal = options.focus_distance * viewport_width * u;
+        let vertical = options.focus_distance * viewport_height * v;
+
+        let lower_left_corner = placement.position()
+            - (horizontal / 2.0)
+            - (vertical / 2.0)
+            - (options.focus_distance * w)
+            + (options.shift.x() * horizontal)
+            + (options.shift.y() * vertical);
+
+        let lens_radius = options.aperture / 2.0;
+
+        let tilt = options.tilt.to_radians();
+        let focal_normal = if tilt != 0.0 {
+            Some(tilt.cos() * w + tilt.sin() * v)
+        } else {
+            None
+        };
+
+        Self {
+            placement,
+            options,
+            bokeh: Bokeh::default(),
+            lower_left_corner,
+            horizontal,
+            vertical,
+            lens_radius,
+            focal_normal,
+        }
+    }
+
+    pub fn with_bokeh(self, bokeh: Bokeh) -> Self {
+        Self { bokeh, ..self }
+    }
+
+    pub const fn options(&self) -> Options {
+        self.options
+    }
+
+    pub const fn bokeh(&self) -> &Bokeh {
+        &self.bokeh
+    }
+}
+
+impl Camera for PerspectiveCamera {
+    fn ray(&self, s: f64, t: f64) -> Option<TimeRay3> {
+        let (u, v, _) = self.placement.basis();
+
+        // Point of the image from its center, the corners being at 1
+        let aspect_ratio = self.options.aspect_ratio;
+        let image = Vec2((2.0 * s - 1.0) * aspect_ratio, 2.0 * t - 1.0) / aspect_ratio.hypot(1.0);
+
+        let rd = self.lens_radius * self.bokeh.sample(Vec2::rand(), image)?;
+        let offset: Vec3 = (u * rd.x()) + (v * rd.y());
+
+        let position = self.placement.position();
+        let origin = position + offset;
+        let mut target = self.lower_left_corner + (s * self.horizontal) + (t * self.vertical);
+
+        // Rays from the whole lens meet where the ray through its center
+        // crosses the tilted plane in focus, or never when it does not
+        if let Some(normal) = self.focal_normal {
+            let center = target - position;
+            let along = -self.options.focus_distance * self.placement.basis().2.dot(normal)
+                / center.dot(normal);
+
+            target = if along.is_finite() && along > 0.0 {
+                position + along * center
+            } else {
+                origin + center
+            };
+        }
+
+        let direction = target - origin;
+
+        Some(TimeRay3::new(origin, direction, self.placement.time(t)))
+    }
+
+    fn placement(&self) -> &Placement {
+        &self.placement
+    }
+
+    fn kind(&self) -> CameraKind<'_> {
+        CameraKind::Perspective(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::time::Interval;
+
+    fn camera(aperture: f64, tilt: f64, shift: Vec2) -> PerspectiveCamera {
+        let placement = Placement::new(
+            Point3(0.0, 0.0, 0.0),
+            Point3(0.0, 0.0, -1.0),
+            Vec3(0.0, 1.0, 0.0),
+            Interval::new(0.0, 0.0),
+        );
+
+        PerspectiveCamera::new(
+            placement,
+            Options {
+                vertical_fov: 90.0,
+                aspect_ratio: 1.0,
+                aperture,
+                focus_distance: 10.0,
+                tilt,
+                shift,
+            },
+        )
+    }
+
+    #[test]
+    fn must_focus_on_the_tilted_plane() {
+        let camera = camera(1.0, 30.0, Vec2(0.0, 0.0));
+
+        // The plane through the focus point, turned by 30 degrees, crosses
+        // the ray through the center of the lens and the bottom of the image
+        let center = Vec3(0.0, -10.0, -10.0);
+        let normal = Vec3(0.0, 0.5, 0.75_f64.sqrt());
+        let focus = (-10.0 * normal.z() / center.dot(normal)) * center;
+
+        assert!(focus.z() > -10.0);
+
+        for _ in 0..16 {
+            let ray = camera.ray(0.5, 0.0).unwrap();
+            let offset = focus - ray.origin();
+            let miss = offset.cross(ray.direction().normalized()).length();
+
+            assert!(miss < 1e-9, "{}", miss);
+        }
+    }
+
+    #[test]
+    fn must_shift_the_image() {
+        let camera = camera(0.0, 0.0, Vec2(0.0, 0.25));
+        let ray = camera.ray(0.5, 0.5).unwrap();
+
+        // A quarter of the image up, so half way to its top edge
+        assert!(ray.origin().length() < 1e-12);
+        assert!((ray.direction().y() / -ray.direction().z() - 0.5).abs() < 1e-12);
+    }
+}
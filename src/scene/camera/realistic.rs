@@ -0,0 +1,744 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use super::{Camera, CameraKind, Placement};
+use crate::core::geometry::{Point3, Vec2, Vec3, Vector};
+use crate::core::math::rand::rand;
+use crate::core::optic::Refract;
+use crate::core::time::TimeRay3;
+
+/// Interface of a lens system, listed from the scene towards the film, with
+/// lengths in millimeters.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LensElement {
+    /// Radius of curvature, positive when the center is towards the film;
+    /// 0 for the aperture stop
+    pub radius: f64,
+    /// Distance along the axis to the next interface, or to the film
+    pub thickness: f64,
+    /// Refractive index of the medium up to the next interface; 0 or 1 for
+    /// air
+    pub ior: f64,
+    /// Diameter of the opening
+    pub aperture: f64,
+}
+
+impl LensElement {
+    const fn is_stop(&self) -> bool {
+        self.radius == 0.0
+    }
+
+    fn ior(&self) -> f64 {
+        if self.ior == 0.0 {
+            1.0
+        } else {
+            self.ior
+        }
+    }
+}
+
+/// Tabulated lens design: the radius, thickness, refractive index and
+/// aperture of every element, as in the lens files of classic realistic
+/// camera models.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LensPrescription {
+    elements: Vec<LensElement>,
+    /// File the prescription was read from, needed to export it
+    source: Option<PathBuf>,
+}
+
+#[allow(dead_code)]
+impl LensPrescription {
+    /// Prescription of the `elements`, which need positive apertures and
+    /// non-negative thicknesses and refractive indices.
+    pub fn new(elements: Vec<LensElement>) -> Result<Self, &'static str> {
+        if elements.is_empty() {
+            return Err("expected at least one element");
+        }
+
+        for element in &elements {
+            if !(element.aperture > 0.0 && element.thickness >= 0.0 && element.ior >= 0.0) {
+                return Err("elements need a positive aperture, and a non-negative thickness and refractive index");
+            }
+
+            if element.radius != 0.0 && element.radius.abs() < 0.5 * element.aperture {
+                return Err("the aperture of an element must fit within its radius of curvature");
+            }
+        }
+
+        Ok(Self {
+            elements,
+            source: None,
+        })
+    }
+
+    /// Double Gauss 50 mm lens, the usual example of realistic camera
+    /// models, set 40 mm from the film before focusing.
+    pub fn double_gauss() -> Self {
+        let rows = [
+            [29.475, 3.76, 1.67, 25.2],
+            [84.83, 0.12, 1.0, 25.2],
+            [19.275, 4.025, 1.67, 23.0],
+            [40.77, 3.275, 1.699, 23.0],
+            [12.75, 5.705, 1.0, 18.0],
+            [0.0, 4.5, 0.0, 17.1],
+            [-14.495, 1.18, 1.603, 17.0],
+            [40.77, 6.065, 1.658, 20.0],
+            [-20.385, 0.19, 1.0, 20.0],
+            [437.065, 3.22, 1.717, 20.0],
+            [-39.73, 40.0, 1.0, 20.0],
+        ];
+
+        Self::new(rows.iter().map(|row| row_element(*row)).collect())
+            .expect("the double Gauss prescription is valid")
+    }
+
+    /// Reads a prescription with one element per line, as radius,
+    /// thickness, refractive index and aperture, separated by spaces.
+    /// Everything after a `#` is ignored.
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut elements = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("");
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let numbers = line
+                .split_whitespace()
+                .map(|number| number.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|error| format!("line {}: {}", index + 1, error))?;
+
+            match numbers.as_slice() {
+                [radius, thickness, ior, aperture] => {
+                    elements.push(row_element([*radius, *thickness, *ior, *aperture]))
+                }
+                _ => {
+                    return Err(format!(
+                        "line {}: expected the radius, thickness, refractive index and aperture",
+                        index + 1
+                    ))
+                }
+            }
+        }
+
+        Self::new(elements).map_err(|reason| reason.to_string())
+    }
+
+    pub fn with_source<P: AsRef<Path>>(self, path: P) -> Self {
+        Self {
+            source: Some(path.as_ref().to_path_buf()),
+            ..self
+        }
+    }
+
+    pub fn elements(&self) -> &[LensElement] {
+        &self.elements
+    }
+
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+}
+
+fn row_element([radius, thickness, ior, aperture]: [f64; 4]) -> LensElement {
+    LensElement {
+        radius,
+        thickness,
+        ior,
+        aperture,
+    }
+}
+
+/// Lens system of a [`RealisticCamera`] and the film behind it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RealisticLens {
+    pub prescription: Arc<LensPrescription>,
+    /// Diagonal of the film, in millimeters
+    pub film_diagonal: f64,
+    /// Diameter of the aperture stop, in millimeters, instead of the one of
+    /// the prescription
+    pub aperture_stop: Option<f64>,
+    /// Size of a scene unit, in millimeters
+    pub millimeters_per_unit: f64,
+}
+
+impl Default for RealisticLens {
+    /// Double Gauss lens on a full frame film, for scenes in meters.
+    fn default() -> Self {
+        Self {
+            prescription: Arc::new(LensPrescription::double_gauss()),
+            film_diagonal: 43.27,
+            aperture_stop: None,
+            millimeters_per_unit: 1000.0,
+        }
+    }
+}
+
+/// Number of rings of the film with their own exit pupil bounds.
+const PUPIL_RINGS: usize = 32;
+
+/// Samples traced through the lens to bound the exit pupil of each ring.
+const PUPIL_SAMPLES: usize = 4096;
+
+/// Camera tracing rays through the elements of a lens system, which gives
+/// the distortion, the vignetting and the focus breathing of the lens.
+///
+/// Lens space has the film at z = 0 and the elements towards -z, like the
+/// camera space. The film moves to focus at the focus distance, using a
+/// thick lens approximation of the system.
+///
+/// Rays are sampled over bounds of the exit pupil, the part of the rear
+/// element light reaches the film through, computed for rings of the film
+/// when the camera is built. Samples are then kept in proportion to the
+/// light reaching their point of the film, so the image darkens where the
+/// lens lets less light through.
+pub struct RealisticCamera {
+    placement: Placement,
+    lens: RealisticLens,
+    focus_distance: f64,
+    /// Elements with the film at its focused distance and the aperture stop
+    elements: Vec<LensElement>,
+    film_width: f64,
+    film_height: f64,
+    /// Bounds of the exit pupil on the plane of the rear element, for film
+    /// points along +x in each ring
+    pupil_bounds: Vec<(Vec2, Vec2)>,
+    /// Light reaching the center of the film, which gets all its samples
+    center_light: f64,
+}
+
+#[allow(dead_code)]
+impl RealisticCamera {
+    /// Camera focused at `focus_distance` scene units, or at infinity when
+    /// the lens cannot focus that close.
+    pub fn new(
+        placement: Placement,
+        lens: RealisticLens,
+        focus_distance: f64,
+        aspect_ratio: f64,
+    ) -> Self {
+        let mut elements = lens.prescription.elements().to_vec();
+
+        if let Some(diameter) = lens.aperture_stop {
+            for element in elements.iter_mut().filter(|element| element.is_stop()) {
+                element.aperture = diameter;
+            }
+        }
+
+        let diagonal = aspect_ratio.hypot(1.0);
+
+        let mut camera = Self {
+            placement,
+            focus_distance,
+            elements,
+            film_width: lens.film_diagonal * aspect_ratio / diagonal,
+            film_height: lens.film_diagonal / diagonal,
+            lens,
+            pupil_bounds: Vec::new(),
+            center_light: 1.0,
+        };
+
+        camera.focus(focus_distance * camera.lens.millimeters_per_unit);
+        camera.bound_exit_pupil();
+
+        camera
+    }
+
+    pub const fn lens(&self) -> &RealisticLens {
+        &self.lens
+    }
+
+    pub const fn focus_distance(&self) -> f64 {
+        self.focus_distance
+    }
+
+    /// Distance from the film to the rear element, once focused.
+    pub fn film_distance(&self) -> f64 {
+        self.elements.last().unwrap().thickness
+    }
+
+    /// Distance from the film to the front element.
+    fn front_z(&self) -> f64 {
+        self.elements.iter().map(|element| element.thickness).sum()
+    }
+
+    fn rear_radius(&self) -> f64 {
+        0.5 * self.elements.last().unwrap().aperture
+    }
+
+    /// Traces a ray from the film out of the front element, or `None` when
+    /// the lens blocks it.
+    fn trace_from_film(&self, origin: Point3, direction: Vec3) -> Option<(Point3, Vec3)> {
+        let mut z = 0.0;
+        let mut origin = origin;
+        let mut direction = direction.normalized();
+
+        for (index, element) in self.elements.iter().enumerate().rev() {
+            z -= element.thickness;
+
+            // Medium on the scene side of the interface
+            let outside = if index > 0 {
+                self.elements[index - 1].ior()
+            } else {
+                1.0
+            };
+
+            let (point, normal) = intersect(element, z, origin, direction)?;
+            origin = point;
+
+            if let Some(normal) = normal {
+                direction = refract(direction, normal, element.ior() / outside)?;
+            }
+        }
+
+        Some((origin, direction))
+    }
+
+    /// Traces a ray from the scene out of the rear element, or `None` when
+    /// the lens blocks it.
+    fn trace_from_scene(&self, origin: Point3, direction: Vec3) -> Option<(Point3, Vec3)> {
+        let mut z = -self.front_z();
+        let mut origin = origin;
+        let mut direction = direction.normalized();
+
+        for (index, element) in self.elements.iter().enumerate() {
+            let outside = if index > 0 {
+                self.elements[index - 1].ior()
+            } else {
+                1.0
+            };
+
+            let (point, normal) = intersect(element, z, origin, direction)?;
+            origin = point;
+
+            if let Some(normal) = normal {
+                direction = refract(direction, normal, outside / element.ior())?;
+            }
+
+            z += element.thickness;
+        }
+
+        Some((origin, direction))
+    }
+
+    /// Principal plane and focal point, along z, of the paraxial ray coming
+    /// out as `exit` for the ray entering at `entry`.
+    fn cardinal_points(entry: (Point3, Vec3), exit: (Point3, Vec3)) -> (f64, f64) {
+        let (entry_origin, _) = entry;
+        let (origin, direction) = exit;
+
+        let focal = -origin.x() / direction.x();
+        let principal = (entry_origin.x() - origin.x()) / direction.x();
+
+        (
+            origin.z() + principal * direction.z(),
+            origin.z() + focal * direction.z(),
+        )
+    }
+
+    /// Moves the film so that points `distance` millimeters in front of it
+    /// are in focus.
+    fn focus(&mut self, distance: f64) {
+        // Paraxial rays parallel to the axis, from either side
+        let height = 0.001 * self.lens.film_diagonal;
+
+        let scene_ray = (
+            Point3(height, 0.0, -self.front_z() - 1.0),
+            Vec3(0.0, 0.0, 1.0),
+        );
+        let film_ray = (
+            Point3(height, 0.0, -self.film_distance() + 1.0),
+            Vec3(0.0, 0.0, -1.0),
+        );
+
+        let scene_exit = self.trace_from_scene(scene_ray.0, scene_ray.1);
+        let film_exit = self.trace_from_film(film_ray.0, film_ray.1);
+
+        let (scene_exit, film_exit) = match (scene_exit, film_exit) {
+            (Some(scene_exit), Some(film_exit)) => (scene_exit, film_exit),
+            _ => return,
+        };
+
+        let (principal_film, focal_film) = Self::cardinal_points(scene_ray, scene_exit);
+        let (principal_scene, _) = Self::cardinal_points(film_ray, film_exit);
+
+        // Distance from the film side focal point to the film, for a thick
+        // lens of focal length `focal_length` imaging the plane at `z`
+        let focal_length = focal_film - principal_film;
+        let z = -distance;
+
+        let c = (principal_scene - z - principal_film)
+            * (principal_scene - z - 4.0 * focal_length - principal_film);
+
+        let shift = if c >= 0.0 {
+            0.5 * (principal_scene - z + principal_film - c.sqrt())
+        } else {
+            // Too close to focus: the film goes to the focal point
+            focal_film
+        };
+
+        let last = self.elements.last_mut().unwrap();
+        last.thickness = (last.thickness + shift).max(0.0);
+    }
+
+    /// Computes the bounds of the exit pupil of each ring of the film.
+    fn bound_exit_pupil(&mut self) {
+        let radius = 1.5 * self.rear_radius();
+        let rear_z = -self.film_distance();
+        let half_diagonal = 0.5 * self.lens.film_diagonal;
+
+        let side = (PUPIL_SAMPLES as f64).sqrt();
+        let spacing = 2.0 * radius * 2.0_f64.sqrt() / side;
+
+        let rear = |index: usize| {
+            Vec2(
+                -radius + 2.0 * radius * radical_inverse(2, index),
+                -radius + 2.0 * radius * radical_inverse(3, index),
+            )
+        };
+
+        self.pupil_bounds = (0..PUPIL_RINGS)
+            .map(|ring| {
+                let mut bounds: Option<(Vec2, Vec2)> = None;
+
+                for index in 0..PUPIL_SAMPLES {
+                    let along = (ring as f64 + (index as f64 + 0.5) / PUPIL_SAMPLES as f64)
+                        / PUPIL_RINGS as f64;
+                    let film = Point3(along * half_diagonal, 0.0, 0.0);
+                    let point = rear(index);
+
+                    let inside = bounds.map_or(false, |bounds| contains(bounds, point));
+
+                    if inside
+                        || self
+                            .trace_from_film(film, Point3(point.x(), point.y(), rear_z) - film)
+                            .is_some()
+                    {
+                        bounds = Some(match bounds {
+                            Some((min, max)) => (
+                                Vec2(min.x().min(point.x()), min.y().min(point.y())),
+                                Vec2(max.x().max(point.x()), max.y().max(point.y())),
+                            ),
+                            None => (point, point),
+                        });
+                    }
+                }
+
+                match bounds {
+                    Some((min, max)) => {
+                        (min - Vec2(spacing, spacing), max + Vec2(spacing, spacing))
+                    }
+                    None => (Vec2(-radius, -radius), Vec2(radius, radius)),
+                }
+            })
+            .collect();
+
+        // Fraction of the center bounds the light goes through
+        let center = Point3(0.0, 0.0, 0.0);
+        let (min, max) = self.pupil_bounds[0];
+
+        let passing = (0..PUPIL_SAMPLES)
+            .filter(|index| {
+                let point = min
+                    + Vec2(radical_inverse(2, *index), radical_inverse(3, *index)) * (max - min);
+
+                self.trace_from_film(center, Point3(point.x(), point.y(), rear_z) - center)
+                    .is_some()
+            })
+            .count();
+
+        self.center_light =
+            area(self.pupil_bounds[0]) * passing.max(1) as f64 / PUPIL_SAMPLES as f64;
+    }
+}
+
+impl Camera for RealisticCamera {
+    fn ray(&self, s: f64, t: f64) -> Option<TimeRay3> {
+        // The lens turns the image upside down on the film
+        let film = Point3(
+            -(s - 0.5) * self.film_width,
+            -(t - 0.5) * self.film_height,
+            0.0,
+        );
+
+        let distance = film.x().hypot(film.y());
+        let ring = (distance / (0.5 * self.lens.film_diagonal) * PUPIL_RINGS as f64) as usize;
+        let bounds = self.pupil_bounds[ring.min(PUPIL_RINGS - 1)];
+
+        // Bounds are along +x, turned towards the film point
+        let (sin, cos) = if distance > 0.0 {
+            (film.y() / distance, film.x() / distance)
+        } else {
+            (0.0, 1.0)
+        };
+
+        let turn = |point: Vec2| {
+            Point3(
+                cos * point.x() - sin * point.y(),
+                sin * point.x() + cos * point.y(),
+                -self.film_distance(),
+            )
+        };
+
+        let (min, max) = bounds;
+        let point = turn(min + Vec2::rand() * (max - min));
+
+        // Keeps the samples in proportion to the light of this film point:
+        // the area of its pupil bounds, and the cos⁴ falloff of its chief ray
+        let chief = (turn(0.5 * (min + max)) - film).normalized();
+        let light = area(bounds) * chief.z().powi(4) / self.center_light;
+
+        if rand() >= light {
+            return None;
+        }
+
+        let (origin, direction) = self.trace_from_film(film, point - film)?;
+
+        let origin = self.placement.position()
+            + self
+                .placement
+                .to_scene(origin / self.lens.millimeters_per_unit);
+
+        Some(TimeRay3::new(
+            origin,
+            self.placement.to_scene(direction),
+            self.placement.time(t),
+        ))
+    }
+
+    fn placement(&self) -> &Placement {
+        &self.placement
+    }
+
+    fn kind(&self) -> CameraKind<'_> {
+        CameraKind::Realistic(self)
+    }
+}
+
+/// Intersects a ray with the interface of an `element` whose vertex is at
+/// `z`, returning the point and, for curved interfaces, the normal facing
+/// the ray. `None` when the ray misses the opening.
+fn intersect(
+    element: &LensElement,
+    z: f64,
+    origin: Point3,
+    direction: Vec3,
+) -> Option<(Point3, Option<Vec3>)> {
+    let (t, center) = if element.is_stop() {
+        if direction.z() == 0.0 {
+            return None;
+        }
+
+        ((z - origin.z()) / direction.z(), None)
+    } else {
+        let center = Point3(0.0, 0.0, z + element.radius);
+        let offset = origin - center;
+
+        let b = offset.dot(direction);
+        let c = offset.sq_length() - element.radius * element.radius;
+        let discriminant = b * b - c;
+
+        if discriminant < 0.0 {
+            return None;
+        }
+
+        let root = discriminant.sqrt();
+
+        // The vertex is the closer or the farther side of the sphere,
+        // depending on the direction of the ray and of the curvature
+        let closer = (direction.z() > 0.0) != (element.radius < 0.0);
+        let t = if closer { -b - root } else { -b + root };
+
+        (t, Some(center))
+    };
+
+    if t <= 0.0 {
+        return None;
+    }
+
+    let point = origin + t * direction;
+
+    if point.x() * point.x() + point.y() * point.y() > 0.25 * element.aperture * element.aperture {
+        return None;
+    }
+
+    let normal = center.map(|center| {
+        let normal = (point - center).normalized();
+
+        if normal.dot(direction) > 0.0 {
+            -normal
+        } else {
+            normal
+        }
+    });
+
+    Some((point, normal))
+}
+
+/// Refracts the unit `direction` through a surface of unit `normal`, facing
+/// it, or `None` on total internal reflection.
+fn refract(direction: Vec3, normal: Vec3, eta_in_over_eta_out: f64) -> Option<Vec3> {
+    let cos_in = -direction.dot(normal);
+    let sin_out_squared = eta_in_over_eta_out.powi(2) * (1.0 - cos_in * cos_in).max(0.0);
+
+    if sin_out_squared >= 1.0 {
+        return None;
+    }
+
+    Some(direction.refract(normal, eta_in_over_eta_out).normalized())
+}
+
+/// Digits of `index` in `base`, mirrored after the decimal point, which
+/// spreads consecutive indices evenly between 0 and 1.
+fn radical_inverse(base: usize, index: usize) -> f64 {
+    let mut index = index;
+    let mut inverse = 0.0;
+    let mut scale = 1.0 / base as f64;
+
+    while index > 0 {
+        inverse += (index % base) as f64 * scale;
+        index /= base;
+        scale /= base as f64;
+    }
+
+    inverse
+}
+
+fn contains((min, max): (Vec2, Vec2), point: Vec2) -> bool {
+    (min.x()..=max.x()).contains(&point.x()) && (min.y()..=max.y()).contains(&point.y())
+}
+
+fn area((min, max): (Vec2, Vec2)) -> f64 {
+    (max.x() - min.x()) * (max.y() - min.y())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::time::Interval;
+
+    fn camera(focus_distance: f64) -> RealisticCamera {
+        let placement = Placement::new(
+            Point3(0.0, 0.0, 0.0),
+            Point3(0.0, 0.0, -1.0),
+            Vec3(0.0, 1.0, 0.0),
+            Interval::new(0.0, 0.0),
+        );
+
+        RealisticCamera::new(placement, RealisticLens::default(), focus_distance, 1.5)
+    }
+
+    /// Point where the ray crosses the plane at `z`.
+    fn crossing(origin: Point3, direction: Vec3, z: f64) -> Point3 {
+        origin + ((z - origin.z()) / direction.z()) * direction
+    }
+
+    #[test]
+    fn must_focus_at_the_focus_distance() {
+        for distance in [1.0, 3.0, 10.0] {
+            let camera = camera(distance);
+            let z = -distance * camera.lens.millimeters_per_unit;
+
+            // Rays from the center of the film through the pupil meet on the
+            // axis at the focus distance
+            let mut spread: f64 = 0.0;
+
+            for index in 0..64 {
+                let (min, max) = camera.pupil_bounds[0];
+                let point =
+                    min + Vec2(radical_inverse(2, index), radical_inverse(3, index)) * (max - min);
+                let rear = Point3(point.x(), point.y(), -camera.film_distance());
+
+                if let Some((origin, direction)) =
+                    camera.trace_from_film(Point3(0.0, 0.0, 0.0), rear)
+                {
+                    let point = crossing(origin, direction, z);
+
+                    spread = spread.max(point.x().hypot(point.y()));
+                }
+            }
+
+            // Within the spherical aberration of an f/2 lens: a millimeter per
+            // meter
+            assert!(spread < distance, "{} {}", distance, spread);
+        }
+    }
+
+    #[test]
+    fn must_breathe_when_focusing() {
+        let far = camera(100.0);
+        let near = camera(0.5);
+
+        // The film moves away from the lens to focus closer, narrowing the
+        // field of view
+        assert!(near.film_distance() > far.film_distance());
+
+        let angle = |camera: &RealisticCamera| {
+            // Ray through the center of the exit pupil, from the edge of the
+            // film along -x
+            let film = Point3(-0.5 * camera.film_width, 0.0, 0.0);
+            let ring = camera.film_width / camera.lens.film_diagonal * PUPIL_RINGS as f64;
+            let (min, max) = camera.pupil_bounds[ring as usize];
+            let center = 0.5 * (min + max);
+            let rear = Point3(-center.x(), -center.y(), -camera.film_distance());
+
+            let (_, direction) = camera.trace_from_film(film, rear - film).unwrap();
+
+            direction.x().atan2(-direction.z())
+        };
+
+        assert!(angle(&near) > 0.0);
+        assert!(angle(&near) < angle(&far));
+    }
+
+    #[test]
+    fn must_vignette_towards_the_corners() {
+        let camera = camera(10.0);
+
+        let light = |s: f64, t: f64| {
+            (0..4000).filter(|_| camera.ray(s, t).is_some()).count() as f64 / 4000.0
+        };
+
+        let center = light(0.5, 0.5);
+        let corner = light(0.0, 0.0);
+
+        assert!(center > 0.5, "{}", center);
+        assert!(corner < 0.8 * center, "{} {}", center, corner);
+
+        // Rays leave the front of the lens, towards the scene
+        let ray = (0..100).find_map(|_| camera.ray(0.5, 0.5)).unwrap();
+        assert!(ray.direction().z() < 0.0);
+        assert!(ray.origin().z() < 0.0);
+    }
+
+    #[test]
+    fn must_parse_prescriptions() {
+        let lens = LensPrescription::parse(
+            "# radius thickness ior aperture\n35.0 5.0 1.5 20\n\n0 2 0 10 # stop\n-35 30 1 20\n",
+        )
+        .unwrap();
+
+        assert_eq!(3, lens.elements().len());
+        assert_eq!(
+            LensElement {
+                radius: 0.0,
+                thickness: 2.0,
+                ior: 0.0,
+                aperture: 10.0
+            },
+            lens.elements()[1]
+        );
+
+        assert_eq!(
+            Err(
+                "line 2: expected the radius, thickness, refractive index and aperture".to_string()
+            ),
+            LensPrescription::parse("1 1 1 1\n1 1 1\n")
+        );
+        assert!(LensPrescription::parse("5 1 1.5 20\n").is_err());
+    }
+}
@@ -0,0 +1,225 @@
+use super::{Placement, Projection};
+
+/// One of the two eyes of a [`StereoRig`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Eye {
+    Left,
+    Right,
+}
+
+impl Eye {
+    pub const BOTH: [Self; 2] = [Self::Left, Self::Right];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Left => "left",
+            Self::Right => "right",
+        }
+    }
+
+    /// Side of the camera the eye is on, along its right axis.
+    fn side(&self) -> f64 {
+        match self {
+            Self::Left => -1.0,
+            Self::Right => 1.0,
+        }
+    }
+}
+
+/// How the views of the two eyes meet.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Convergence {
+    /// Parallel axes, converging at infinity
+    Parallel,
+    /// Both eyes turned towards the point the camera looks at
+    ToedIn,
+    /// Omni-directional stereo for equirectangular panoramas: every ray
+    /// starts on the circle of the eyes, tangent to it, so the stereo holds
+    /// all around the viewer
+    Omnidirectional,
+}
+
+impl Convergence {
+    /// Names accepted by [`Convergence::from_name`].
+    pub const NAMES: [&'static str; 3] = ["parallel", "toed-in", "ods"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "parallel" => Some(Self::Parallel),
+            "toed-in" => Some(Self::ToedIn),
+            "ods" => Some(Self::Omnidirectional),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Parallel => "parallel",
+            Self::ToedIn => "toed-in",
+            Self::Omnidirectional => "ods",
+        }
+    }
+}
+
+/// Pair of cameras `interaxial` apart, around the position of the view.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StereoRig {
+    /// Distance between the eyes, in scene units
+    pub interaxial: f64,
+    pub convergence: Convergence,
+}
+
+#[allow(dead_code)]
+impl StereoRig {
+    /// Human eyes, for scenes in meters.
+    pub const DEFAULT_INTERAXIAL: f64 = 0.065;
+
+    /// Usual rig of the `projection`: omni-directional for equirectangular
+    /// panoramas, parallel otherwise.
+    pub fn for_projection(projection: &Projection) -> Self {
+        let convergence = match projection {
+            Projection::Equirectangular => Convergence::Omnidirectional,
+            _ => Convergence::Parallel,
+        };
+
+        Self {
+            interaxial: Self::DEFAULT_INTERAXIAL,
+            convergence,
+        }
+    }
+
+    /// Checks that the rig works with the `projection`, returning the reason
+    /// it does not.
+    pub fn validate(&self, projection: &Projection) -> Result<(), &'static str> {
+        if !(self.interaxial.is_finite() && self.interaxial >= 0.0) {
+            Err("the interaxial distance must not be negative")
+        } else if self.convergence == Convergence::Omnidirectional
+            && *projection != Projection::Equirectangular
+        {
+            Err("omni-directional stereo needs an equirectangular camera")
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Placement of the `eye`, moved sideways from the `center` one. With
+    /// omni-directional stereo, the rays move instead; see
+    /// [`StereoRig::eye_offset`].
+    pub fn placement(&self, center: &Placement, eye: Eye) -> Placement {
+        if self.convergence == Convergence::Omnidirectional {
+            return center.clone();
+        }
+
+        let (u, _, _) = center.basis();
+        let shift = 0.5 * self.interaxial * eye.side() * u;
+
+        let look_at = match self.convergence {
+            Convergence::ToedIn => center.look_at(),
+            _ => center.look_at() + shift,
+        };
+
+        Placement::new(
+            center.position() + shift,
+            look_at,
+            center.up(),
+            center.time_interval(),
+        )
+        .with_shutter(center.shutter().clone())
+    }
+
+    /// Signed radius of the circle the rays of the `eye` start from, with
+    /// omni-directional stereo.
+    pub fn eye_offset(&self, eye: Eye) -> f64 {
+        match self.convergence {
+            Convergence::Omnidirectional => 0.5 * self.interaxial * eye.side(),
+            _ => 0.0,
+        }
+    }
+}
+
+/// How the images of the two eyes are written.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum StereoLayout {
+    /// Left eye on the left, in an image twice as wide
+    SideBySide,
+    /// Left eye on top, in an image twice as high
+    OverUnder,
+    /// One file per eye
+    Separate,
+}
+
+impl StereoLayout {
+    /// Names accepted by [`StereoLayout::from_name`].
+    pub const NAMES: [&'static str; 3] = ["side-by-side", "over-under", "separate"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "side-by-side" => Some(Self::SideBySide),
+            "over-under" => Some(Self::OverUnder),
+            "separate" => Some(Self::Separate),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::{Point3, Vec3};
+    use crate::core::time::Interval;
+
+    fn center() -> Placement {
+        Placement::new(
+            Point3(0.0, 0.0, 0.0),
+            Point3(0.0, 0.0, -10.0),
+            Vec3(0.0, 1.0, 0.0),
+            Interval::new(0.0, 1.0),
+        )
+    }
+
+    #[test]
+    fn must_place_eyes() {
+        let parallel = StereoRig {
+            interaxial: 0.5,
+            convergence: Convergence::Parallel,
+        };
+
+        let left = parallel.placement(&center(), Eye::Left);
+        let right = parallel.placement(&center(), Eye::Right);
+
+        assert_eq!(Point3(-0.25, 0.0, 0.0), left.position());
+        assert_eq!(Point3(0.25, 0.0, 0.0), right.position());
+        assert_eq!(center().basis().2, left.basis().2);
+
+        let toed_in = StereoRig {
+            convergence: Convergence::ToedIn,
+            ..parallel
+        };
+
+        let left = toed_in.placement(&center(), Eye::Left);
+        let right = toed_in.placement(&center(), Eye::Right);
+
+        // Both eyes turn inwards, towards the look-at point
+        assert_eq!(Point3(0.0, 0.0, -10.0), left.look_at());
+        assert!(left.basis().2.x() < 0.0 && right.basis().2.x() > 0.0);
+    }
+
+    #[test]
+    fn must_check_projections() {
+        let ods = StereoRig::for_projection(&Projection::Equirectangular);
+
+        assert_eq!(Convergence::Omnidirectional, ods.convergence);
+        assert!(ods.validate(&Projection::Equirectangular).is_ok());
+        assert!(ods.validate(&Projection::Perspective).is_err());
+        assert_eq!(
+            center().position(),
+            ods.placement(&center(), Eye::Left).position()
+        );
+        assert!(ods.eye_offset(Eye::Left) < 0.0);
+
+        let parallel = StereoRig::for_projection(&Projection::Perspective);
+
+        assert_eq!(Convergence::Parallel, parallel.convergence);
+        assert!(parallel.eye_offset(Eye::Right).abs() < 1e-12);
+    }
+}
@@ -3,14 +3,18 @@ use crate::materials::Material;
 
 use super::hitrecord::BasicHitRecord;
 
-use crate::core::time::{Interval, TimeRay3};
-use crate::scene::object::AABB;
+use crate::core::geometry::Transform;
+use crate::core::time::{Interval, TimeRay3, TransformTrack};
+use crate::scene::object::movingsphere::MovingSphere;
+use crate::scene::object::sphere::Sphere;
+use crate::scene::object::{AxisRect, Cuboid, Instance, MeshTriangle, Quad, Triangle, AABB, BVH};
+use std::any::type_name;
 use std::cmp::Ordering;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub struct MaterialHitRecord {
     hit: BasicHitRecord,
-    material: Rc<dyn Material>,
+    material: Arc<dyn Material>,
 }
 
 #[allow(dead_code)]
@@ -21,7 +25,7 @@ impl MaterialHitRecord {
         ray: Ray3,
         text_coord: Vec2,
         outward_normal: Vec3,
-        material: Rc<dyn Material>,
+        material: Arc<dyn Material>,
     ) -> Self {
         Self {
             hit: BasicHitRecord::new(point, t, text_coord, ray, outward_normal),
@@ -29,7 +33,7 @@ impl MaterialHitRecord {
         }
     }
 
-    pub fn from_hit(hit: BasicHitRecord, material: Rc<dyn Material>) -> Self {
+    pub fn from_hit(hit: BasicHitRecord, material: Arc<dyn Material>) -> Self {
         Self { hit, material }
     }
 
@@ -53,12 +57,12 @@ impl MaterialHitRecord {
         self.hit.front_face()
     }
 
-    pub fn material(&self) -> Rc<dyn Material> {
+    pub fn material(&self) -> Arc<dyn Material> {
         self.material.clone()
     }
 }
 
-pub trait Hit {
+pub trait Hit: Send + Sync {
     fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord>;
 
     fn bounding_box(&self, interval: Interval) -> Option<AABB>;
@@ -75,11 +79,66 @@ pub trait Hit {
 
         Ordering::Greater
     }
+
+    /// What the object is, for code handling each kind of object on its
+    /// own, such as the export of scene files.
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::Other(type_name::<Self>())
+    }
+}
+
+/// Concrete object behind a [`Hit`].
+pub enum HitKind<'a> {
+    Sphere(&'a Sphere),
+    MovingSphere(&'a MovingSphere),
+    Triangle(&'a Triangle),
+    MeshTriangle(&'a MeshTriangle),
+    Cuboid(&'a Cuboid),
+    AxisRect(&'a AxisRect),
+    Quad(&'a Quad),
+    /// Object moved by a transform
+    Transformed(&'a Transform, &'a dyn Hit),
+    /// Object moved by an animation
+    Animated(&'a TransformTrack, &'a dyn Hit),
+    Instance(&'a Instance),
+    Bvh(&'a BVH),
+    List(&'a HitList),
+    /// Any other object, named by its type
+    Other(&'static str),
+}
+
+/// Shared objects, e.g. the mesh hierarchies placed by several instances.
+impl<H: Hit + ?Sized> Hit for Arc<H> {
+    fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
+        (**self).hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, interval: Interval) -> Option<AABB> {
+        (**self).bounding_box(interval)
+    }
+
+    fn kind(&self) -> HitKind<'_> {
+        (**self).kind()
+    }
+}
+
+impl<H: Hit + ?Sized> Hit for Box<H> {
+    fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
+        (**self).hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, interval: Interval) -> Option<AABB> {
+        (**self).bounding_box(interval)
+    }
+
+    fn kind(&self) -> HitKind<'_> {
+        (**self).kind()
+    }
 }
 
 // Hit list
 pub struct HitList {
-    objects: Vec<Box<(dyn Hit)>>,
+    objects: Vec<Box<dyn Hit>>,
 }
 
 impl HitList {
@@ -104,6 +163,10 @@ impl HitList {
     pub fn add(&mut self, object: Box<dyn Hit>) {
         self.objects.push(object);
     }
+
+    pub fn objects(&self) -> &[Box<dyn Hit>] {
+        &self.objects
+    }
 }
 
 impl Hit for HitList {
@@ -147,4 +210,8 @@ impl Hit for HitList {
 
         bounding_box
     }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::List(self)
+    }
 }
@@ -1,3 +1,4 @@
+use crate::core::color::Color;
 use crate::core::geometry::{Point3, Ray, Ray3, Vec2, Vec3, Vector};
 
 #[derive(Copy, Clone)]
@@ -7,6 +8,7 @@ pub struct BasicHitRecord {
     t: f64,
     texture_coord: Vec2,
     front_face: bool,
+    vertex_color: Option<Color>,
 }
 
 impl BasicHitRecord {
@@ -30,6 +32,57 @@ impl BasicHitRecord {
             front_face,
             t,
             texture_coord,
+            vertex_color: None,
+        }
+    }
+
+    /// Builds a record whose shading normal differs from the geometric one,
+    /// as in smooth-shaded meshes. The side of the surface is decided by the
+    /// geometric normal, and the shading normal is flipped to that side.
+    pub fn with_shading_normal(
+        point: Point3,
+        t: f64,
+        texture_coord: Vec2,
+        ray: Ray3,
+        geometric_normal: Vec3,
+        shading_normal: Vec3,
+    ) -> Self {
+        let front_face = ray.direction().dot(geometric_normal) < 0.0;
+        let side = if front_face {
+            geometric_normal
+        } else {
+            -geometric_normal
+        };
+
+        let normal = if shading_normal.dot(side) < 0.0 {
+            -shading_normal
+        } else {
+            shading_normal
+        };
+
+        Self {
+            point,
+            normal,
+            front_face,
+            t,
+            texture_coord,
+            vertex_color: None,
+        }
+    }
+
+    /// Sets the color interpolated from the vertices of a mesh.
+    pub const fn with_vertex_color(mut self, color: Color) -> Self {
+        self.vertex_color = Some(color);
+        self
+    }
+
+    /// Moves the hit to another space, e.g. out of the space of an
+    /// instanced object. The side of the surface is kept.
+    pub fn transformed(self, point: Point3, normal: Vec3) -> Self {
+        Self {
+            point,
+            normal: normal.normalized(),
+            ..self
         }
     }
 
@@ -52,4 +105,8 @@ impl BasicHitRecord {
     pub const fn front_face(&self) -> bool {
         self.front_face
     }
+
+    pub const fn vertex_color(&self) -> Option<Color> {
+        self.vertex_color
+    }
 }
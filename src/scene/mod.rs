@@ -1,10 +1,15 @@
+mod background;
 pub mod camera;
 mod hit;
 mod hitrecord;
 pub mod object;
+mod shutter;
 
-pub use hit::Hit;
+pub use background::Background;
 pub use hit::HitList;
 pub use hit::MaterialHitRecord;
+pub use hit::{Hit, HitKind};
 
 pub use hitrecord::BasicHitRecord;
+
+pub use shutter::{Shutter, ShutterCurve};
@@ -1,17 +1,111 @@
-use crate::core::geometry::Point3;
+use crate::core::geometry::{Point3, Transform};
 use crate::core::time::TimeRay3;
 
+/// Ray with the inverse of its direction, shared by the slab tests of many
+/// boxes.
+#[derive(Copy, Clone, Debug)]
+pub struct InverseRay {
+    origin: [f64; 3],
+    inverse_direction: [f64; 3],
+}
+
+impl InverseRay {
+    pub fn new(ray: &TimeRay3) -> Self {
+        let origin = ray.origin();
+        let direction = ray.direction();
+
+        Self {
+            origin: [origin.x(), origin.y(), origin.z()],
+            // Zero components give infinities of the sign of the zero
+            inverse_direction: [
+                1.0 / direction.x(),
+                1.0 / direction.y(),
+                1.0 / direction.z(),
+            ],
+        }
+    }
+
+    /// Whether the direction decreases along `axis`.
+    pub fn is_negative(&self, axis: usize) -> bool {
+        self.inverse_direction[axis].is_sign_negative()
+    }
+
+    /// Intersects the ray with the three slabs between `min` and `max`,
+    /// narrowing `t_min` and `t_max` to the part inside each slab.
+    pub fn hit_slabs(&self, min: [f64; 3], max: [f64; 3], t_min: f64, t_max: f64) -> bool {
+        let mut t_min = t_min;
+        let mut t_max = t_max;
+
+        for axis in 0..3 {
+            let inverse = self.inverse_direction[axis];
+
+            let t0 = (min[axis] - self.origin[axis]) * inverse;
+            let t1 = (max[axis] - self.origin[axis]) * inverse;
+
+            let (near, far) = if self.is_negative(axis) {
+                (t1, t0)
+            } else {
+                (t0, t1)
+            };
+
+            // A ray parallel to the slab and starting on one of its planes
+            // gives 0 * inf = NaN, which `max` and `min` ignore: the ray
+            // stays on the boundary, which counts as inside
+            t_min = near.max(t_min);
+            t_max = far.min(t_max);
+
+            if t_max < t_min {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
 #[derive(Copy, Clone)]
+#[allow(clippy::upper_case_acronyms)]
 pub struct AABB {
     min: Point3,
     max: Point3,
 }
 
 impl AABB {
+    /// Minimum extent given to flat primitives, so they are not missed by
+    /// the bounding box tests.
+    pub const PADDING: f64 = 0.0001;
+
     pub const fn new(min: Point3, max: Point3) -> Self {
         Self { min, max }
     }
 
+    /// Builds the smallest box containing all the `points`.
+    pub fn from_points(points: &[Point3]) -> Self {
+        let first = Self::new(points[0], points[0]);
+
+        points[1..].iter().fold(first, |aabb, &point| {
+            aabb.surrounding_box(&Self::new(point, point))
+        })
+    }
+
+    /// Expands every axis narrower than `delta` to that size, keeping the
+    /// box centered.
+    pub fn padded(&self, delta: f64) -> Self {
+        let mut min = self.min;
+        let mut max = self.max;
+
+        for axis in 0..3 {
+            if max[axis] - min[axis] < delta {
+                let center = 0.5 * (min[axis] + max[axis]);
+
+                min[axis] = center - 0.5 * delta;
+                max[axis] = center + 0.5 * delta;
+            }
+        }
+
+        Self::new(min, max)
+    }
+
     pub const fn min(&self) -> Point3 {
         self.min
     }
@@ -20,22 +114,65 @@ impl AABB {
         self.max
     }
 
-    // TODO Use Ray3 here?
-    #[allow(dead_code)]
-    pub fn hit(&self, ray: &TimeRay3, t_min: f64, t_max: f64) -> bool {
-        for i in 0..3 {
-            let temp0 = (self.min[i] - ray.origin()[i]) / ray.direction()[1];
-            let temp1 = (self.max[i] - ray.origin()[i]) / ray.direction()[1];
-
-            let t_min = temp0.min(temp1).min(t_min);
-            let t_max = temp0.max(temp1).max(t_max);
+    /// The eight corners of the box.
+    pub fn corners(&self) -> [Point3; 8] {
+        let mut corners = [self.min; 8];
 
-            if t_max <= t_min {
-                return false;
+        for (index, corner) in corners.iter_mut().enumerate() {
+            for axis in 0..3 {
+                if index & (1 << axis) != 0 {
+                    corner[axis] = self.max[axis];
+                }
             }
         }
 
-        true
+        corners
+    }
+
+    /// Smallest box containing this one once transformed.
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        let corners: Vec<Point3> = self
+            .corners()
+            .iter()
+            .map(|corner| transform.point(*corner))
+            .collect();
+
+        Self::from_points(&corners)
+    }
+
+    pub fn centroid(&self) -> Point3 {
+        0.5 * (self.min + self.max)
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        let size = self.max - self.min;
+
+        2.0 * (size.x() * size.y() + size.y() * size.z() + size.z() * size.x())
+    }
+
+    /// Axis along which the box is the longest.
+    pub fn longest_axis(&self) -> usize {
+        let size = self.max - self.min;
+
+        if size.x() >= size.y() && size.x() >= size.z() {
+            0
+        } else if size.y() >= size.z() {
+            1
+        } else {
+            2
+        }
+    }
+
+    /// Whether the ray crosses the box between `t_min` and `t_max`. Rays
+    /// touching the box only on its boundary hit it.
+    #[allow(dead_code)]
+    pub fn hit(&self, ray: &InverseRay, t_min: f64, t_max: f64) -> bool {
+        ray.hit_slabs(
+            [self.min.x(), self.min.y(), self.min.z()],
+            [self.max.x(), self.max.y(), self.max.z()],
+            t_min,
+            t_max,
+        )
     }
 
     // TODO Apply DRY principle here
@@ -65,3 +202,196 @@ impl AABB {
         Self::new(small, big)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::Vec3;
+    use crate::core::math::rand::{rand, rand_between, seed};
+
+    fn unit_box() -> AABB {
+        AABB::new(Point3(-1.0, -1.0, -1.0), Point3(1.0, 1.0, 1.0))
+    }
+
+    fn hits(aabb: &AABB, origin: Point3, direction: Vec3, t_min: f64, t_max: f64) -> bool {
+        let ray = TimeRay3::new(origin, direction, 0.0);
+
+        aabb.hit(&InverseRay::new(&ray), t_min, t_max)
+    }
+
+    fn random_point(low: f64, high: f64) -> Point3 {
+        Point3(
+            rand_between(low, high),
+            rand_between(low, high),
+            rand_between(low, high),
+        )
+    }
+
+    #[test]
+    fn must_hit_with_axis_parallel_rays() {
+        let aabb = unit_box();
+
+        for axis in 0..3 {
+            for sign in [-1.0, 1.0] {
+                let mut origin = Point3(0.5, -0.5, 0.25);
+                let mut direction = Vec3(0.0, 0.0, 0.0);
+
+                origin[axis] = -5.0 * sign;
+                direction[axis] = sign;
+
+                assert!(hits(&aabb, origin, direction, 0.0, f64::INFINITY));
+
+                // The box is between t = 4 and t = 6
+                assert!(hits(&aabb, origin, direction, 5.0, 5.5));
+                assert!(!hits(&aabb, origin, direction, 0.0, 3.9));
+                assert!(!hits(&aabb, origin, direction, 6.1, 10.0));
+
+                // Going away from the box
+                assert!(!hits(&aabb, origin, -direction, 0.0, f64::INFINITY));
+
+                // Parallel to the box, outside of it on another axis
+                let other = (axis + 1) % 3;
+                origin[other] = 2.0;
+
+                assert!(!hits(&aabb, origin, direction, 0.0, f64::INFINITY));
+            }
+        }
+    }
+
+    #[test]
+    fn must_hit_from_inside() {
+        let aabb = unit_box();
+
+        assert!(hits(
+            &aabb,
+            Point3(0.0, 0.0, 0.0),
+            Vec3(0.3, -0.2, 0.9),
+            0.0,
+            f64::INFINITY
+        ));
+        assert!(hits(
+            &aabb,
+            Point3(0.9, 0.9, 0.9),
+            Vec3(1.0, 0.0, 0.0),
+            0.0,
+            0.01
+        ));
+    }
+
+    #[test]
+    fn must_hit_grazing_rays() {
+        let aabb = unit_box();
+
+        // Along a face, along an edge, and through a corner only
+        assert!(hits(
+            &aabb,
+            Point3(-5.0, 1.0, 0.0),
+            Vec3(1.0, 0.0, 0.0),
+            0.0,
+            f64::INFINITY
+        ));
+        assert!(hits(
+            &aabb,
+            Point3(-5.0, 1.0, 1.0),
+            Vec3(1.0, 0.0, 0.0),
+            0.0,
+            f64::INFINITY
+        ));
+        assert!(hits(
+            &aabb,
+            Point3(-2.0, 0.0, 0.0),
+            Vec3(1.0, 1.0, 0.0),
+            0.0,
+            f64::INFINITY
+        ));
+
+        // Just beside the face
+        assert!(!hits(
+            &aabb,
+            Point3(-5.0, 1.000001, 0.0),
+            Vec3(1.0, 0.0, 0.0),
+            0.0,
+            f64::INFINITY
+        ));
+    }
+
+    #[test]
+    fn must_handle_zero_components_and_flat_boxes() {
+        // Origin on the plane of a face with a zero component: 0 * inf
+        assert!(hits(
+            &unit_box(),
+            Point3(1.0, -5.0, 0.0),
+            Vec3(0.0, 1.0, -0.0),
+            0.0,
+            f64::INFINITY
+        ));
+
+        let flat = AABB::new(Point3(-1.0, 0.0, -1.0), Point3(1.0, 0.0, 1.0));
+
+        assert!(hits(
+            &flat,
+            Point3(0.2, 3.0, 0.1),
+            Vec3(0.0, -1.0, 0.0),
+            0.0,
+            f64::INFINITY
+        ));
+        assert!(!hits(
+            &flat,
+            Point3(0.2, 3.0, 0.1),
+            Vec3(1.0, 0.0, 0.0),
+            0.0,
+            f64::INFINITY
+        ));
+    }
+
+    #[test]
+    fn must_hit_rays_through_the_box() {
+        seed(11);
+
+        for _ in 0..1000 {
+            let aabb = AABB::from_points(&[random_point(-5.0, 5.0), random_point(-5.0, 5.0)]);
+
+            // Point of the box reached at t = distance
+            let t = rand();
+            let target = Point3(
+                aabb.min().x() + t * (aabb.max().x() - aabb.min().x()),
+                aabb.min().y() + rand() * (aabb.max().y() - aabb.min().y()),
+                aabb.min().z() + rand() * (aabb.max().z() - aabb.min().z()),
+            );
+            let direction = random_point(-1.0, 1.0);
+            let distance = rand_between(0.0, 10.0);
+            let origin = target - distance * direction;
+
+            assert!(hits(&aabb, origin, direction, 0.0, f64::INFINITY));
+            assert!(hits(&aabb, origin, direction, distance, distance));
+        }
+    }
+
+    #[test]
+    fn must_miss_rays_leaving_the_box() {
+        seed(12);
+
+        for _ in 0..1000 {
+            let aabb = AABB::from_points(&[random_point(-5.0, 5.0), random_point(-5.0, 5.0)]);
+
+            // Origin beyond the box on one axis, moving further on that axis
+            let axis = rand_between(0, 3);
+            let mut origin = random_point(-10.0, 10.0);
+            let mut direction = random_point(-1.0, 1.0);
+
+            if rand() < 0.5 {
+                origin[axis] = aabb.max()[axis] + rand_between(0.001, 5.0);
+                direction[axis] = direction[axis].abs();
+            } else {
+                origin[axis] = aabb.min()[axis] - rand_between(0.001, 5.0);
+                direction[axis] = -direction[axis].abs();
+            }
+
+            if rand() < 0.2 {
+                direction[axis] = 0.0;
+            }
+
+            assert!(!hits(&aabb, origin, direction, 0.0, f64::INFINITY));
+        }
+    }
+}
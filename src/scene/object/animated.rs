@@ -0,0 +1,169 @@
+use crate::core::geometry::{Point3, Vec3, Vector};
+use crate::core::time::{Interval, TimeRay3, TransformTrack};
+use crate::scene::object::AABB;
+use crate::scene::{Hit, HitKind, MaterialHitRecord};
+
+/// Object moved by a keyframed [`TransformTrack`], evaluated at the time of
+/// each ray, which blurs any motion: translation, rotation and scale.
+pub struct Animated<H: Hit> {
+    object: H,
+    track: TransformTrack,
+}
+
+#[allow(dead_code)]
+impl<H: Hit> Animated<H> {
+    pub const fn new(object: H, track: TransformTrack) -> Self {
+        Self { object, track }
+    }
+
+    pub const fn object(&self) -> &H {
+        &self.object
+    }
+
+    pub const fn track(&self) -> &TransformTrack {
+        &self.track
+    }
+}
+
+impl<H: Hit> Hit for Animated<H> {
+    fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
+        let transform = self.track.at(ray.time());
+
+        let hit = self
+            .object
+            .hit(ray.inverse_transformed(&transform), t_min, t_max)?;
+
+        let world_hit = hit
+            .hit()
+            .transformed(transform.point(hit.point()), transform.normal(hit.normal()));
+
+        Some(MaterialHitRecord::from_hit(world_hit, hit.material()))
+    }
+
+    /// Bounds the object over the whole motion during `interval`.
+    ///
+    /// Without rotation, the corners of the box move along straight lines
+    /// and the boxes at both ends of each piece of motion are enough. While
+    /// rotating, corners sweep arcs, which stay within their distance to
+    /// the origin of the object, scaled, around its translated origin.
+    fn bounding_box(&self, interval: Interval) -> Option<AABB> {
+        let local = self.object.bounding_box(interval)?;
+
+        self.track
+            .pieces(interval)
+            .iter()
+            .map(|piece| {
+                let ends = local
+                    .transformed(&piece.start.transform())
+                    .surrounding_box(&local.transformed(&piece.end.transform()));
+
+                if !piece.rotates {
+                    return ends;
+                }
+
+                let largest = |a: f64, b: f64| a.abs().max(b.abs());
+                let scale = Vec3(
+                    largest(piece.start.scale.x(), piece.end.scale.x()),
+                    largest(piece.start.scale.y(), piece.end.scale.y()),
+                    largest(piece.start.scale.z(), piece.end.scale.z()),
+                );
+
+                let radius = local
+                    .corners()
+                    .iter()
+                    .map(|corner| (scale * (*corner - Point3::zero())).length())
+                    .fold(0.0, f64::max);
+
+                let origins = AABB::from_points(&[piece.start.translation, piece.end.translation]);
+                let reach = Vec3(radius, radius, radius);
+
+                ends.surrounding_box(&AABB::new(origins.min() - reach, origins.max() + reach))
+            })
+            .reduce(|a, b| a.surrounding_box(&b))
+    }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::Animated(&self.track, &self.object)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::Quaternion;
+    use crate::core::math::easing::Easing;
+    use crate::core::time::{Keyframe, Pose};
+    use crate::scene::object::Cuboid;
+    use crate::testing::{grey_material, EPSILON};
+
+    /// Box 4 long along x and 0.2 thick, around the origin
+    fn bar() -> Cuboid {
+        let material = grey_material();
+
+        Cuboid::new(Point3(-2.0, -0.1, -0.1), Point3(2.0, 0.1, 0.1), material)
+    }
+
+    fn spinning_bar() -> Animated<Cuboid> {
+        let pose = |degrees: f64| Pose {
+            rotation: Quaternion::from_axis_angle(Vec3(0.0, 0.0, 1.0), degrees),
+            ..Pose::default()
+        };
+
+        let track = TransformTrack::new(vec![
+            Keyframe::new(0.0, pose(0.0)),
+            Keyframe::new(1.0, pose(90.0)).with_easing(Easing::EaseInOut),
+            Keyframe::new(2.0, pose(90.0)),
+        ])
+        .unwrap();
+
+        Animated::new(bar(), track)
+    }
+
+    #[test]
+    fn must_hit_at_the_time_of_rays() {
+        let bar = spinning_bar();
+
+        let ray = |time: f64| TimeRay3::new(Point3(1.2, 1.2, -5.0), Vec3(0.0, 0.0, 1.0), time);
+
+        // Along the diagonal halfway through the turn
+        let hit = bar.hit(ray(0.5), 0.001, f64::INFINITY).unwrap();
+
+        assert!((hit.point() - Point3(1.2, 1.2, -0.1)).length() < EPSILON);
+        assert!((hit.normal() - Vec3(0.0, 0.0, -1.0)).length() < EPSILON);
+
+        assert!(bar.hit(ray(0.0), 0.001, f64::INFINITY).is_none());
+        assert!(bar.hit(ray(1.5), 0.001, f64::INFINITY).is_none());
+    }
+
+    #[test]
+    fn must_bound_the_whole_motion() {
+        let bar = spinning_bar();
+        let aabb = bar.bounding_box(Interval::new(0.0, 1.0)).unwrap();
+
+        // Sampled poses all fit in the box
+        for step in 0..=100 {
+            let transform = bar.track().at(step as f64 / 100.0);
+
+            for corner in bar
+                .object()
+                .bounding_box(Interval::new(0.0, 1.0))
+                .unwrap()
+                .corners()
+                .iter()
+            {
+                let point = transform.point(*corner);
+
+                for axis in 0..3 {
+                    assert!(aabb.min()[axis] - EPSILON <= point[axis]);
+                    assert!(point[axis] <= aabb.max()[axis] + EPSILON);
+                }
+            }
+        }
+
+        // Once the rotation stops, the bounds are tight
+        let still = bar.bounding_box(Interval::new(1.0, 2.0)).unwrap();
+
+        assert!((still.min() - Point3(-0.1, -2.0, -0.1)).length() < EPSILON);
+        assert!((still.max() - Point3(0.1, 2.0, 0.1)).length() < EPSILON);
+    }
+}
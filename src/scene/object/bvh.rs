@@ -1,116 +1,577 @@
+use crate::core::geometry::Point3;
 use crate::core::math::rand::rand_between;
 use crate::core::time::{Interval, TimeRay3};
-use crate::scene::object::AABB;
-use crate::scene::{Hit, MaterialHitRecord};
-use std::cmp::Ordering;
-use std::rc::Rc;
+use crate::scene::object::{InverseRay, AABB};
+use crate::scene::{Hit, HitKind, MaterialHitRecord};
+use std::fmt;
+use std::sync::Arc;
 
-pub struct BVH {
-    left: Rc<dyn Hit>,
-    right: Rc<dyn Hit>,
+/// Cost of visiting a node, relative to the cost of intersecting an object.
+const TRAVERSAL_COST: f64 = 0.125;
+const INTERSECTION_COST: f64 = 1.0;
+
+/// How the objects of a node are divided between its two children.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SplitMethod {
+    /// Surface area heuristic, evaluated at the boundaries of `bins` buckets
+    /// along the longest axis of the centroids
+    Sah { bins: usize },
+    /// Median of the centroids along their longest axis
+    Median,
+    /// Median of the box minimums along a random axis
+    Random,
+}
+
+impl SplitMethod {
+    /// Names accepted by [`SplitMethod::from_name`].
+    pub const NAMES: [&'static str; 3] = ["sah", "median", "random"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "sah" => Some(Self::Sah { bins: 12 }),
+            "median" => Some(Self::Median),
+            "random" => Some(Self::Random),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BvhOptions {
+    pub split: SplitMethod,
+    /// Largest number of objects kept in a leaf
+    pub max_leaf_size: usize,
+}
+
+impl Default for BvhOptions {
+    fn default() -> Self {
+        Self {
+            split: SplitMethod::from_name("sah").unwrap(),
+            max_leaf_size: 4,
+        }
+    }
+}
+
+/// Shape of a [`BVH`], to compare builders.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BvhStats {
+    pub nodes: usize,
+    pub leaves: usize,
+    /// Number of nodes on the longest path from the root, which is 1 for a
+    /// single leaf
+    pub depth: usize,
+    pub objects: usize,
+    /// Expected cost of intersecting a random ray with the tree, relative to
+    /// the cost of intersecting one object
+    pub sah_cost: f64,
+}
+
+impl fmt::Display for BvhStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} nodes, {} leaves, depth {}, SAH cost {:.2}",
+            self.nodes, self.leaves, self.depth, self.sah_cost
+        )
+    }
+}
+
+/// Node of the flattened hierarchy, stored in depth-first order so that the
+/// first child of an interior node directly follows it.
+///
+/// Bounds are rounded outwards to `f32` to fit a node in 32 bytes.
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+struct LinearNode {
+    min: [f32; 3],
+    max: [f32; 3],
+    /// Index of the first object of a leaf, or of the second child of an
+    /// interior node
+    offset: u32,
+    /// Number of objects of a leaf, zero for interior nodes
+    count: u16,
+    /// Axis along which the children were split
+    axis: u8,
+    _padding: u8,
+}
+
+impl LinearNode {
+    fn new(bounding_box: &AABB) -> Self {
+        let (min, max) = (bounding_box.min(), bounding_box.max());
+
+        Self {
+            min: [0, 1, 2].map(|axis| round_down(min[axis])),
+            max: [0, 1, 2].map(|axis| round_up(max[axis])),
+            offset: 0,
+            count: 0,
+            axis: 0,
+            _padding: 0,
+        }
+    }
+
+    const fn is_leaf(&self) -> bool {
+        self.count > 0
+    }
+
+    fn bounding_box(&self) -> AABB {
+        let point = |values: [f32; 3]| {
+            Point3(
+                f64::from(values[0]),
+                f64::from(values[1]),
+                f64::from(values[2]),
+            )
+        };
+
+        AABB::new(point(self.min), point(self.max))
+    }
+
+    fn hit(&self, ray: &InverseRay, t_min: f64, t_max: f64) -> bool {
+        ray.hit_slabs(
+            self.min.map(f64::from),
+            self.max.map(f64::from),
+            t_min,
+            t_max,
+        )
+    }
+}
+
+fn round_down(value: f64) -> f32 {
+    let rounded = value as f32;
+
+    if f64::from(rounded) > value {
+        previous(rounded)
+    } else {
+        rounded
+    }
+}
+
+fn round_up(value: f64) -> f32 {
+    let rounded = value as f32;
+
+    if f64::from(rounded) < value {
+        next(rounded)
+    } else {
+        rounded
+    }
+}
+
+/// Closest `f32` below `value`, which must not be the lowest one nor NaN.
+fn previous(value: f32) -> f32 {
+    if value == 0.0 {
+        -f32::from_bits(1)
+    } else if value > 0.0 {
+        f32::from_bits(value.to_bits() - 1)
+    } else {
+        f32::from_bits(value.to_bits() + 1)
+    }
+}
+
+/// Closest `f32` above `value`, which must not be the highest one nor NaN.
+fn next(value: f32) -> f32 {
+    -previous(-value)
+}
+
+/// Object with its bounds, computed once while building.
+struct Primitive {
+    object: Arc<dyn Hit>,
     bounding_box: AABB,
+    centroid: Point3,
 }
 
-#[allow(dead_code)]
-impl BVH {
-    pub fn from_objects(source_objects: &[Rc<(dyn Hit)>], interval: Interval) -> Self {
-        let left: Rc<dyn Hit>;
-        let right: Rc<dyn Hit>;
+fn surrounding(primitives: &[Primitive]) -> AABB {
+    primitives[1..]
+        .iter()
+        .fold(primitives[0].bounding_box, |aabb, primitive| {
+            aabb.surrounding_box(&primitive.bounding_box)
+        })
+}
+
+fn centroid_bounds(primitives: &[Primitive]) -> AABB {
+    let points: Vec<_> = primitives
+        .iter()
+        .map(|primitive| primitive.centroid)
+        .collect();
 
-        let axis = rand_between(0, 3);
+    AABB::from_points(&points)
+}
+
+struct Builder {
+    options: BvhOptions,
+    nodes: Vec<LinearNode>,
+    objects: Vec<Arc<dyn Hit>>,
+}
 
-        let object_span = source_objects.len();
+impl Builder {
+    /// Appends the subtree of `primitives` to the nodes.
+    fn build(&mut self, mut primitives: Vec<Primitive>, depth: usize) {
+        let bounding_box = surrounding(&primitives);
+        let index = self.nodes.len();
 
-        match object_span {
-            1 => {
-                left = source_objects[0].clone();
-                right = source_objects[0].clone();
+        self.nodes.push(LinearNode::new(&bounding_box));
+
+        // Leaves count their objects with 16 bits
+        let max_leaf_size = self.options.max_leaf_size.clamp(1, u16::MAX as usize);
+        let leaf_fits = primitives.len() <= max_leaf_size;
+
+        let split = match self.options.split {
+            _ if primitives.len() <= 1 => None,
+            _ if depth >= MEDIAN_DEPTH && leaf_fits => None,
+            _ if depth >= MEDIAN_DEPTH => Some(split_median(&mut primitives)),
+            SplitMethod::Sah { bins } => {
+                self.split_sah(&mut primitives, &bounding_box, bins, max_leaf_size)
             }
-            2 => {
-                let compare =
-                    Hit::box_compare(source_objects[0].as_ref(), source_objects[1].as_ref(), axis);
+            _ if leaf_fits => None,
+            SplitMethod::Median => Some(split_median(&mut primitives)),
+            SplitMethod::Random => Some(split_random(&mut primitives)),
+        };
 
-                if compare == Ordering::Less {
-                    left = source_objects[0].clone();
-                    right = source_objects[1].clone();
-                } else {
-                    left = source_objects[1].clone();
-                    right = source_objects[0].clone();
-                }
+        match split {
+            None => {
+                let node = &mut self.nodes[index];
+
+                node.offset = self.objects.len() as u32;
+                node.count = primitives.len() as u16;
+
+                self.objects
+                    .extend(primitives.into_iter().map(|primitive| primitive.object));
             }
-            _ => {
-                let mut vec = Vec::from(source_objects);
+            Some((mid, axis)) => {
+                let right = primitives.split_off(mid);
 
-                vec.sort_by(|a, b| Hit::box_compare(a.as_ref(), b.as_ref(), axis));
+                self.build(primitives, depth + 1);
 
-                let mid = vec.len() / 2;
+                self.nodes[index].offset = self.nodes.len() as u32;
+                self.nodes[index].axis = axis as u8;
 
-                left = Rc::new(Self::from_objects(&vec[..mid], interval));
-                right = Rc::new(Self::from_objects(&vec[mid..], interval));
+                self.build(right, depth + 1);
             }
         }
+    }
 
-        let box_left: AABB;
-        let box_right: AABB;
+    /// Index where the primitives are divided and the axis of the split, or
+    /// `None` when keeping them in a leaf is cheaper.
+    fn split_sah(
+        &self,
+        primitives: &mut [Primitive],
+        bounding_box: &AABB,
+        bins: usize,
+        max_leaf_size: usize,
+    ) -> Option<(usize, usize)> {
+        let count = primitives.len();
+        let bounds = centroid_bounds(primitives);
+        let axis = bounds.longest_axis();
+        let (low, high) = (bounds.min()[axis], bounds.max()[axis]);
 
-        if let (Some(l), Some(r)) = (left.bounding_box(interval), right.bounding_box(interval)) {
-            box_left = l;
-            box_right = r;
-        } else {
-            panic!("No bounding box in BVH constructor");
+        if high <= low {
+            // All the centroids coincide: no split separates them
+            return if count <= max_leaf_size {
+                None
+            } else {
+                Some((count / 2, axis))
+            };
         }
 
-        Self {
-            left,
-            right,
-            bounding_box: AABB::surrounding_box(&box_left, &box_right),
+        let bins = bins.max(2);
+        let bin_of = |primitive: &Primitive| {
+            let offset = (primitive.centroid[axis] - low) / (high - low);
+
+            ((offset * bins as f64) as usize).min(bins - 1)
+        };
+
+        let mut counts = vec![0; bins];
+        let mut boxes: Vec<Option<AABB>> = vec![None; bins];
+
+        for primitive in primitives.iter() {
+            let bin = bin_of(primitive);
+
+            counts[bin] += 1;
+            boxes[bin] = Some(match boxes[bin] {
+                Some(aabb) => aabb.surrounding_box(&primitive.bounding_box),
+                None => primitive.bounding_box,
+            });
         }
+
+        // Area and count on each side of the boundary after each bin
+        let sweep = |order: &mut dyn Iterator<Item = usize>| {
+            let mut aabb: Option<AABB> = None;
+            let mut total = 0;
+
+            order
+                .map(|bin| {
+                    if let Some(bin_box) = boxes[bin] {
+                        aabb = Some(aabb.map_or(bin_box, |aabb| aabb.surrounding_box(&bin_box)));
+                    }
+
+                    total += counts[bin];
+
+                    (aabb.map_or(0.0, |aabb| aabb.surface_area()), total)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let below = sweep(&mut (0..bins - 1));
+        let mut above = sweep(&mut (1..bins).rev());
+        above.reverse();
+
+        let area = bounding_box.surface_area();
+
+        let (best_bin, best_cost) = below
+            .iter()
+            .zip(&above)
+            .map(|(&(area_below, count_below), &(area_above, count_above))| {
+                let weighted = area_below * count_below as f64 + area_above * count_above as f64;
+
+                if area > 0.0 {
+                    TRAVERSAL_COST + INTERSECTION_COST * weighted / area
+                } else {
+                    TRAVERSAL_COST + INTERSECTION_COST * count as f64
+                }
+            })
+            .enumerate()
+            .fold((0, f64::INFINITY), |best, (bin, cost)| {
+                if cost < best.1 {
+                    (bin, cost)
+                } else {
+                    best
+                }
+            });
+
+        if count <= max_leaf_size && INTERSECTION_COST * count as f64 <= best_cost {
+            return None;
+        }
+
+        let mid = partition(primitives, |primitive| bin_of(primitive) <= best_bin);
+
+        if mid == 0 || mid == count {
+            return Some(split_median(primitives));
+        }
+
+        Some((mid, axis))
+    }
+}
+
+/// Moves the primitives matching `predicate` first, returning their count.
+fn partition<F: Fn(&Primitive) -> bool>(primitives: &mut [Primitive], predicate: F) -> usize {
+    let mut mid = 0;
+
+    for index in 0..primitives.len() {
+        if predicate(&primitives[index]) {
+            primitives.swap(mid, index);
+            mid += 1;
+        }
+    }
+
+    mid
+}
+
+fn split_median(primitives: &mut [Primitive]) -> (usize, usize) {
+    let axis = centroid_bounds(primitives).longest_axis();
+    let mid = primitives.len() / 2;
+
+    primitives.select_nth_unstable_by(mid, |a, b| a.centroid[axis].total_cmp(&b.centroid[axis]));
+
+    (mid, axis)
+}
+
+fn split_random(primitives: &mut [Primitive]) -> (usize, usize) {
+    let axis = rand_between(0, 3);
+
+    primitives.sort_by(|a, b| Hit::box_compare(a.object.as_ref(), b.object.as_ref(), axis));
+
+    (primitives.len() / 2, axis)
+}
+
+/// Bounding volume hierarchy over a set of objects, flattened into an
+/// array of nodes.
+#[allow(clippy::upper_case_acronyms)]
+pub struct BVH {
+    nodes: Vec<LinearNode>,
+    /// Objects of the leaves, each leaf referencing a contiguous range
+    objects: Vec<Arc<dyn Hit>>,
+    bounding_box: AABB,
+}
+
+/// Depth of the traversal stack, which holds one node per level
+const STACK_SIZE: usize = 64;
+
+/// Below this depth the builder only splits at the median, which halves the
+/// objects at each level and keeps the tree within [`STACK_SIZE`] levels.
+const MEDIAN_DEPTH: usize = 30;
+
+#[allow(dead_code)]
+impl BVH {
+    /// Builds the hierarchy with the default [`BvhOptions`].
+    pub fn from_objects(source_objects: &[Arc<dyn Hit>], interval: Interval) -> Self {
+        Self::build(source_objects, interval, &BvhOptions::default())
     }
 
-    pub fn left(&self) -> &Rc<dyn Hit> {
-        &self.left
+    pub fn build(
+        source_objects: &[Arc<dyn Hit>],
+        interval: Interval,
+        options: &BvhOptions,
+    ) -> Self {
+        assert!(!source_objects.is_empty(), "No object in BVH constructor");
+
+        let primitives: Vec<Primitive> = source_objects
+            .iter()
+            .map(|object| {
+                let bounding_box = object
+                    .bounding_box(interval)
+                    .expect("No bounding box in BVH constructor");
+
+                Primitive {
+                    object: object.clone(),
+                    bounding_box,
+                    centroid: bounding_box.centroid(),
+                }
+            })
+            .collect();
+
+        let bounding_box = surrounding(&primitives);
+
+        let mut builder = Builder {
+            options: *options,
+            nodes: Vec::with_capacity(2 * primitives.len()),
+            objects: Vec::with_capacity(primitives.len()),
+        };
+
+        builder.build(primitives, 0);
+
+        Self {
+            nodes: builder.nodes,
+            objects: builder.objects,
+            bounding_box,
+        }
     }
 
-    pub fn right(&self) -> &Rc<dyn Hit> {
-        &self.right
+    pub fn objects(&self) -> &[Arc<dyn Hit>] {
+        &self.objects
     }
 
     pub const fn bounding_box(&self) -> &AABB {
         &self.bounding_box
     }
+
+    pub fn stats(&self) -> BvhStats {
+        let mut stats = BvhStats {
+            nodes: 0,
+            leaves: 0,
+            depth: 0,
+            objects: 0,
+            sah_cost: 0.0,
+        };
+
+        self.add_stats(
+            0,
+            1,
+            self.nodes[0].bounding_box().surface_area(),
+            &mut stats,
+        );
+
+        stats
+    }
+
+    fn add_stats(&self, index: usize, depth: usize, root_area: f64, stats: &mut BvhStats) {
+        let node = &self.nodes[index];
+
+        let probability = if root_area > 0.0 {
+            node.bounding_box().surface_area() / root_area
+        } else {
+            1.0
+        };
+
+        stats.nodes += 1;
+        stats.depth = stats.depth.max(depth);
+
+        if node.is_leaf() {
+            stats.leaves += 1;
+            stats.objects += node.count as usize;
+            stats.sah_cost += probability * node.count as f64 * INTERSECTION_COST;
+        } else {
+            stats.sah_cost += probability * TRAVERSAL_COST;
+
+            self.add_stats(index + 1, depth + 1, root_area, stats);
+            self.add_stats(node.offset as usize, depth + 1, root_area, stats);
+        }
+    }
 }
 
 impl Hit for BVH {
     fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
-        if !self.bounding_box.hit(&ray, t_min, t_max) {
-            return None;
-        }
+        let inverse_ray = InverseRay::new(&ray);
+
+        let mut closest = t_max;
+        let mut result = None;
+
+        let mut stack = [0; STACK_SIZE];
+        let mut stack_size = 0;
+        let mut index = 0;
+
+        loop {
+            let node = &self.nodes[index];
+
+            if node.hit(&inverse_ray, t_min, closest) {
+                if node.is_leaf() {
+                    let first = node.offset as usize;
 
-        let hit_left = self.left.hit(ray, t_min, t_max);
+                    for object in &self.objects[first..first + node.count as usize] {
+                        if let Some(hit) = object.hit(ray, t_min, closest) {
+                            closest = hit.t();
+                            result = Some(hit);
+                        }
+                    }
+                } else {
+                    let first = index + 1;
+                    let second = node.offset as usize;
+
+                    // Visit first the child on the side the ray comes from,
+                    // so that hits found there cull the other one
+                    let (near, far) = if inverse_ray.is_negative(node.axis as usize) {
+                        (second, first)
+                    } else {
+                        (first, second)
+                    };
+
+                    stack[stack_size] = far;
+                    stack_size += 1;
+
+                    index = near;
+                    continue;
+                }
+            }
 
-        match hit_left {
-            None => self.right.hit(ray, t_min, t_max),
-            Some(hit) => self.right.hit(ray, t_min, hit.t()),
+            if stack_size == 0 {
+                return result;
+            }
+
+            stack_size -= 1;
+            index = stack[stack_size];
         }
     }
 
     fn bounding_box(&self, _interval: Interval) -> Option<AABB> {
         Some(self.bounding_box)
     }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::Bvh(self)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
     use crate::core::color::Color;
-    use crate::core::geometry::{Point3, Vec3};
+    use crate::core::geometry::Vec3;
+    use crate::core::math::rand::{rand, seed};
     use crate::core::time::TimeRay3;
     use crate::materials::Lambertian;
     use crate::scene::object::sphere::Sphere;
     use crate::scene::{Hit, HitList};
-    use std::rc::Rc;
+    use std::sync::Arc;
 
     fn make_static_scene() -> HitList {
-        let material = Rc::new(Lambertian::from_color(Color(0.6, 0.6, 0.6)));
+        let material = Arc::new(Lambertian::from_color(Color(0.6, 0.6, 0.6)));
 
         let sphere1 = Sphere::new(Point3(0.0, 1.0, 0.0), 1.0, material.clone());
         let sphere2 = Sphere::new(Point3(0.0, -1.0, 0.0), 1.0, material);
@@ -122,6 +583,27 @@ mod tests {
         list
     }
 
+    fn make_spheres(count: usize) -> Vec<Arc<dyn Hit>> {
+        let material = Arc::new(Lambertian::from_color(Color(0.6, 0.6, 0.6)));
+
+        seed(3);
+
+        (0..count)
+            .map(|_| {
+                let center = Point3(20.0 * rand() - 10.0, 4.0 * rand(), 20.0 * rand() - 10.0);
+
+                Arc::new(Sphere::new(center, 0.2, material.clone())) as Arc<dyn Hit>
+            })
+            .collect()
+    }
+
+    fn options(split: SplitMethod) -> BvhOptions {
+        BvhOptions {
+            split,
+            ..BvhOptions::default()
+        }
+    }
+
     #[test]
     fn must_hit_something() {
         let scene = make_static_scene();
@@ -132,4 +614,86 @@ mod tests {
 
         assert!(hit.is_some());
     }
+
+    #[test]
+    fn must_find_the_closest_hit_like_a_list() {
+        let spheres = make_spheres(200);
+        let interval = Interval::new(0.0, 1.0);
+
+        for split in [
+            SplitMethod::Sah { bins: 12 },
+            SplitMethod::Median,
+            SplitMethod::Random,
+        ] {
+            let bvh = BVH::build(&spheres, interval, &options(split));
+
+            for _ in 0..200 {
+                let origin = Point3(30.0 * rand() - 15.0, 2.0, 30.0 * rand() - 15.0);
+                let direction = Vec3(rand() - 0.5, rand() - 0.5, rand() - 0.5);
+                let ray = TimeRay3::new(origin, direction, 0.0);
+
+                let expected = spheres
+                    .iter()
+                    .filter_map(|sphere| sphere.hit(ray, 0.001, f64::INFINITY))
+                    .map(|hit| hit.t())
+                    .reduce(f64::min);
+                let found = bvh.hit(ray, 0.001, f64::INFINITY).map(|hit| hit.t());
+
+                assert_eq!(expected, found, "{:?}", split);
+            }
+        }
+    }
+
+    #[test]
+    fn must_fit_nodes_in_32_bytes() {
+        assert_eq!(32, std::mem::size_of::<LinearNode>());
+    }
+
+    #[test]
+    fn must_round_bounds_outwards() {
+        for aabb in &[
+            AABB::new(Point3(0.1, -0.1, 1e-10), Point3(0.3, 1e40, 2.0)),
+            AABB::new(Point3(-1e-50, -1e-50, -0.3), Point3(1e-50, -1e-60, -0.1)),
+        ] {
+            let node = LinearNode::new(aabb);
+            let rounded = node.bounding_box();
+
+            for axis in 0..3 {
+                assert!(rounded.min()[axis] <= aabb.min()[axis]);
+                assert!(rounded.max()[axis] >= aabb.max()[axis]);
+            }
+        }
+    }
+
+    #[test]
+    fn must_respect_the_leaf_size() {
+        let spheres = make_spheres(100);
+        let options = BvhOptions {
+            split: SplitMethod::Median,
+            max_leaf_size: 3,
+        };
+
+        let stats = BVH::build(&spheres, Interval::new(0.0, 1.0), &options).stats();
+
+        assert_eq!(100, stats.objects);
+        assert_eq!(2 * stats.leaves - 1, stats.nodes);
+        assert!(stats.leaves >= 34);
+    }
+
+    #[test]
+    fn must_build_cheaper_trees_with_sah() {
+        let spheres = make_spheres(500);
+        let interval = Interval::new(0.0, 1.0);
+
+        let sah = BVH::build(&spheres, interval, &BvhOptions::default()).stats();
+        let random = BVH::build(&spheres, interval, &options(SplitMethod::Random)).stats();
+
+        assert!(sah.sah_cost < random.sah_cost, "{} vs {}", sah, random);
+
+        // Without randomness, builds are reproducible
+        assert_eq!(
+            sah,
+            BVH::build(&spheres, interval, &BvhOptions::default()).stats()
+        );
+    }
 }
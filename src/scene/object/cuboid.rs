@@ -0,0 +1,119 @@
+use crate::core::geometry::Point3;
+use crate::materials::Material;
+use crate::scene::{Hit, HitKind, HitList, MaterialHitRecord};
+
+use crate::core::time::{Interval, TimeRay3};
+use crate::scene::object::{AxisRect, AABB};
+use std::sync::Arc;
+
+/// Axis-aligned box made of six rectangles, with normals pointing outwards.
+pub struct Cuboid {
+    min: Point3,
+    max: Point3,
+    sides: HitList,
+    material: Arc<dyn Material>,
+}
+
+#[allow(dead_code)]
+impl Cuboid {
+    pub fn new(p0: Point3, p1: Point3, material: Arc<dyn Material>) -> Self {
+        let min = Point3(p0.x().min(p1.x()), p0.y().min(p1.y()), p0.z().min(p1.z()));
+        let max = Point3(p0.x().max(p1.x()), p0.y().max(p1.y()), p0.z().max(p1.z()));
+
+        let x = (min.x(), max.x());
+        let y = (min.y(), max.y());
+        let z = (min.z(), max.z());
+
+        let mut sides = HitList::new();
+
+        sides.add(Box::new(AxisRect::xy(x, y, max.z(), material.clone())));
+        sides.add(Box::new(
+            AxisRect::xy(x, y, min.z(), material.clone()).flipped(),
+        ));
+        sides.add(Box::new(AxisRect::xz(x, z, max.y(), material.clone())));
+        sides.add(Box::new(
+            AxisRect::xz(x, z, min.y(), material.clone()).flipped(),
+        ));
+        sides.add(Box::new(AxisRect::yz(y, z, max.x(), material.clone())));
+        sides.add(Box::new(
+            AxisRect::yz(y, z, min.x(), material.clone()).flipped(),
+        ));
+
+        Self {
+            min,
+            max,
+            sides,
+            material,
+        }
+    }
+
+    pub const fn min(&self) -> Point3 {
+        self.min
+    }
+
+    pub const fn max(&self) -> Point3 {
+        self.max
+    }
+
+    pub fn material(&self) -> Arc<dyn Material> {
+        self.material.clone()
+    }
+}
+
+impl Hit for Cuboid {
+    fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
+        self.sides.hit(ray, t_min, t_max)
+    }
+
+    fn bounding_box(&self, _interval: Interval) -> Option<AABB> {
+        Some(AABB::new(self.min, self.max).padded(AABB::PADDING))
+    }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::Cuboid(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::Vec3;
+    use crate::testing::grey_material;
+
+    fn make_cuboid() -> Cuboid {
+        let material = grey_material();
+
+        Cuboid::new(Point3(1.0, 1.0, 1.0), Point3(-1.0, 0.0, -1.0), material)
+    }
+
+    #[test]
+    fn must_face_outwards_on_every_side() {
+        let cuboid = make_cuboid();
+
+        let directions = [
+            Vec3(1.0, 0.0, 0.0),
+            Vec3(-1.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 0.0),
+            Vec3(0.0, -1.0, 0.0),
+            Vec3(0.0, 0.0, 1.0),
+            Vec3(0.0, 0.0, -1.0),
+        ];
+
+        for &direction in &directions {
+            let center = Point3(0.0, 0.5, 0.0);
+
+            let outside = TimeRay3::new(center + 5.0 * direction, -direction, 0.0);
+            let inside = TimeRay3::new(center, direction, 0.0);
+
+            let hit = cuboid.hit(outside, 0.0, 10.0).unwrap();
+
+            assert!(hit.front_face());
+            assert_eq!(direction, hit.normal());
+
+            let hit = cuboid.hit(inside, 0.0, 10.0).unwrap();
+
+            assert!(!hit.front_face());
+            assert_eq!(-direction, hit.normal());
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::core::geometry::Transform;
+use crate::core::time::{Interval, TimeRay3};
+use crate::materials::Material;
+use crate::scene::object::{Transformed, AABB};
+use crate::scene::{Hit, HitKind, MaterialHitRecord};
+use std::sync::Arc;
+
+/// Placement of a shared object, usually the [`BVH`] of a mesh, in the
+/// scene.
+///
+/// Rays are brought into the space of the object, so that the same object
+/// can be instanced many times at the memory cost of a transform. Putting
+/// the instances in a [`BVH`] gives a two-level hierarchy.
+///
+/// [`BVH`]: super::BVH
+pub struct Instance {
+    object: Transformed<Arc<dyn Hit>>,
+    /// Replaces the materials of the object when set
+    material: Option<Arc<dyn Material>>,
+}
+
+#[allow(dead_code)]
+impl Instance {
+    pub fn new(object: Arc<dyn Hit>, transform: Transform) -> Self {
+        Self {
+            object: Transformed::new(object, transform),
+            material: None,
+        }
+    }
+
+    pub fn with_material(self, material: Arc<dyn Material>) -> Self {
+        Self {
+            material: Some(material),
+            ..self
+        }
+    }
+
+    pub const fn object(&self) -> &Arc<dyn Hit> {
+        self.object.object()
+    }
+
+    pub const fn transform(&self) -> &Transform {
+        self.object.transform()
+    }
+
+    /// Material replacing those of the object
+    pub const fn material(&self) -> Option<&Arc<dyn Material>> {
+        self.material.as_ref()
+    }
+}
+
+impl Hit for Instance {
+    fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
+        let hit = self.object.hit(ray, t_min, t_max)?;
+
+        match &self.material {
+            Some(material) => Some(MaterialHitRecord::from_hit(hit.hit(), material.clone())),
+            None => Some(hit),
+        }
+    }
+
+    fn bounding_box(&self, interval: Interval) -> Option<AABB> {
+        self.object.bounding_box(interval)
+    }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::Instance(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::color::Color;
+    use crate::core::geometry::{Point3, Vec3, Vector};
+    use crate::materials::Lambertian;
+    use crate::scene::object::sphere::Sphere;
+    use crate::scene::object::BVH;
+    use crate::testing::{grey_material, EPSILON};
+
+    fn unit_sphere() -> Arc<dyn Hit> {
+        let material = grey_material();
+
+        Arc::new(Sphere::new(Point3(0.0, 0.0, 0.0), 1.0, material))
+    }
+
+    #[test]
+    fn must_hit_transformed_objects() {
+        // Ellipsoid with radii 2, 1, 1 centered on (5, 0, 0)
+        let transform = Transform::scaling(Vec3(2.0, 1.0, 1.0))
+            .then(&Transform::translation(Vec3(5.0, 0.0, 0.0)));
+        let instance = Instance::new(unit_sphere(), transform);
+
+        let ray = TimeRay3::new(Point3(0.0, 0.0, 0.0), Vec3(1.0, 0.0, 0.0), 0.0);
+        let hit = instance.hit(ray, 0.001, f64::INFINITY).unwrap();
+
+        assert!((hit.t() - 3.0).abs() < EPSILON);
+        assert!((hit.point() - Point3(3.0, 0.0, 0.0)).length() < EPSILON);
+        assert!((hit.normal() - Vec3(-1.0, 0.0, 0.0)).length() < EPSILON);
+
+        // Normals use the inverse transpose: at 45 degrees on the ellipsoid
+        // surface, the normal leans towards the short axis
+        let ray = TimeRay3::new(Point3(5.0, 5.0, 0.0), Vec3(0.0, -1.0, 0.0), 0.0);
+        let hit = instance.hit(ray, 0.001, f64::INFINITY).unwrap();
+
+        assert!((hit.normal() - Vec3(0.0, 1.0, 0.0)).length() < EPSILON);
+
+        let aabb = instance.bounding_box(Interval::new(0.0, 1.0)).unwrap();
+
+        assert!((aabb.min() - Point3(3.0, -1.0, -1.0)).length() < EPSILON);
+        assert!((aabb.max() - Point3(7.0, 1.0, 1.0)).length() < EPSILON);
+    }
+
+    #[test]
+    fn must_override_materials() {
+        let red: Arc<dyn Material> = Arc::new(Lambertian::from_color(Color(1.0, 0.0, 0.0)));
+
+        let shared = unit_sphere();
+        let plain = Instance::new(shared.clone(), Transform::identity());
+        let painted = Instance::new(shared, Transform::identity()).with_material(red.clone());
+
+        let ray = TimeRay3::new(Point3(0.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0), 0.0);
+
+        let material = painted.hit(ray, 0.001, f64::INFINITY).unwrap().material();
+        assert!(Arc::ptr_eq(&red, &material));
+
+        let material = plain.hit(ray, 0.001, f64::INFINITY).unwrap().material();
+        assert!(!Arc::ptr_eq(&red, &material));
+    }
+
+    #[test]
+    fn must_build_two_level_hierarchies() {
+        let blas: Arc<dyn Hit> =
+            Arc::new(BVH::from_objects(&[unit_sphere()], Interval::new(0.0, 1.0)));
+
+        let instances: Vec<Arc<dyn Hit>> = (0..10)
+            .map(|index| {
+                let offset = Vec3(3.0 * index as f64, 0.0, 0.0);
+
+                Arc::new(Instance::new(blas.clone(), Transform::translation(offset)))
+                    as Arc<dyn Hit>
+            })
+            .collect();
+
+        let tlas = BVH::from_objects(&instances, Interval::new(0.0, 1.0));
+
+        let ray = TimeRay3::new(Point3(27.0, 0.0, -5.0), Vec3(0.0, 0.0, 1.0), 0.0);
+        let hit = tlas.hit(ray, 0.001, f64::INFINITY).unwrap();
+
+        assert!((hit.point() - Point3(27.0, 0.0, -1.0)).length() < EPSILON);
+
+        let ray = TimeRay3::new(Point3(28.5, 0.0, -5.0), Vec3(0.0, 0.0, 1.0), 0.0);
+
+        assert!(tlas.hit(ray, 0.001, f64::INFINITY).is_none());
+    }
+}
@@ -0,0 +1,342 @@
+use crate::core::color::Color;
+use crate::core::geometry::{Point2, Point3, Ray, Vec2, Vec3, Vector};
+use crate::materials::Material;
+use crate::scene::{BasicHitRecord, Hit, HitKind, MaterialHitRecord};
+
+use crate::core::time::{Interval, TimeRay3};
+use crate::scene::object::triangle::intersect;
+use crate::scene::object::{BvhOptions, AABB, BVH};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Indexed triangle mesh, with vertex attributes shared by its triangles.
+///
+/// Normals and texture coordinates are optional; when present they are
+/// given per vertex and interpolated over each triangle.
+pub struct TriangleMesh {
+    positions: Vec<Point3>,
+    normals: Option<Vec<Vec3>>,
+    texture_coords: Option<Vec<Vec2>>,
+    colors: Option<Vec<Color>>,
+    indices: Vec<[usize; 3]>,
+    material: Arc<dyn Material>,
+    /// File the mesh was read from
+    source: Option<PathBuf>,
+}
+
+/// Triangle of a [`TriangleMesh`], referenced by its index.
+pub struct MeshTriangle {
+    mesh: Arc<TriangleMesh>,
+    index: usize,
+}
+
+#[allow(dead_code)]
+impl TriangleMesh {
+    /// Builds a mesh, panicking if any index is out of bounds or if the
+    /// optional attributes do not match the number of positions.
+    pub fn new(
+        positions: Vec<Point3>,
+        indices: Vec<[usize; 3]>,
+        normals: Option<Vec<Vec3>>,
+        texture_coords: Option<Vec<Vec2>>,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        let count = positions.len();
+
+        assert!(
+            indices.iter().flatten().all(|&index| index < count),
+            "Triangle index out of bounds"
+        );
+        assert!(
+            normals
+                .as_ref()
+                .map_or(true, |normals| normals.len() == count),
+            "Expected one normal per vertex"
+        );
+        assert!(
+            texture_coords
+                .as_ref()
+                .map_or(true, |coords| coords.len() == count),
+            "Expected one texture coordinate per vertex"
+        );
+
+        Self {
+            positions,
+            normals,
+            texture_coords,
+            colors: None,
+            indices,
+            material,
+            source: None,
+        }
+    }
+
+    /// Records the file the mesh was read from, which describes it in scene
+    /// files.
+    pub fn with_source<P: Into<PathBuf>>(mut self, source: P) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Replaces the material of every triangle.
+    pub fn with_material(mut self, material: Arc<dyn Material>) -> Self {
+        self.material = material;
+        self
+    }
+
+    /// Adds one color per vertex, read by the [`VertexColor`] texture.
+    ///
+    /// [`VertexColor`]: crate::textures::VertexColor
+    pub fn with_colors(mut self, colors: Vec<Color>) -> Self {
+        assert_eq!(
+            self.positions.len(),
+            colors.len(),
+            "Expected one color per vertex"
+        );
+
+        self.colors = Some(colors);
+        self
+    }
+
+    pub fn positions(&self) -> &[Point3] {
+        &self.positions
+    }
+
+    pub fn normals(&self) -> Option<&[Vec3]> {
+        self.normals.as_deref()
+    }
+
+    pub fn texture_coords(&self) -> Option<&[Vec2]> {
+        self.texture_coords.as_deref()
+    }
+
+    pub fn colors(&self) -> Option<&[Color]> {
+        self.colors.as_deref()
+    }
+
+    pub fn indices(&self) -> &[[usize; 3]] {
+        &self.indices
+    }
+
+    pub fn material(&self) -> Arc<dyn Material> {
+        self.material.clone()
+    }
+
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub fn vertices(&self, triangle: usize) -> [Point3; 3] {
+        let [i0, i1, i2] = self.indices[triangle];
+
+        [self.positions[i0], self.positions[i1], self.positions[i2]]
+    }
+
+    /// Splits the mesh into its triangles, ready to be put in a [`BVH`].
+    pub fn triangles(self: &Arc<Self>) -> Vec<Arc<dyn Hit>> {
+        (0..self.len())
+            .map(|index| {
+                Arc::new(MeshTriangle {
+                    mesh: self.clone(),
+                    index,
+                }) as Arc<dyn Hit>
+            })
+            .collect()
+    }
+
+    pub fn into_bvh(self, interval: Interval, options: &BvhOptions) -> BVH {
+        BVH::build(&Arc::new(self).triangles(), interval, options)
+    }
+}
+
+#[allow(dead_code)]
+impl MeshTriangle {
+    pub fn mesh(&self) -> &Arc<TriangleMesh> {
+        &self.mesh
+    }
+
+    pub const fn index(&self) -> usize {
+        self.index
+    }
+}
+
+impl Hit for MeshTriangle {
+    fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
+        let mesh = &self.mesh;
+
+        let vertices = mesh.vertices(self.index);
+        let intersection = intersect(ray, vertices, t_min, t_max)?;
+
+        let [b0, b1, b2] = intersection.barycentric;
+        let [i0, i1, i2] = mesh.indices[self.index];
+
+        let interpolate = |a: Vec3, b: Vec3, c: Vec3| (b0 * a) + (b1 * b) + (b2 * c);
+
+        let [p0, p1, p2] = vertices;
+        let geometric_normal = (p1 - p0).cross(p2 - p0).normalized();
+
+        let shading_normal = mesh.normals.as_ref().map_or(geometric_normal, |normals| {
+            interpolate(normals[i0], normals[i1], normals[i2]).normalized()
+        });
+
+        let texture_coord = mesh
+            .texture_coords
+            .as_ref()
+            .map_or(Point2(b1, b2), |coords| {
+                let (uv0, uv1, uv2) = (coords[i0], coords[i1], coords[i2]);
+
+                Point2(
+                    b0 * uv0.x() + b1 * uv1.x() + b2 * uv2.x(),
+                    b0 * uv0.y() + b1 * uv1.y() + b2 * uv2.y(),
+                )
+            });
+
+        let hit = BasicHitRecord::with_shading_normal(
+            ray.at(intersection.t),
+            intersection.t,
+            texture_coord,
+            ray.to_ray(),
+            geometric_normal,
+            shading_normal,
+        );
+
+        let hit = match &mesh.colors {
+            Some(colors) => hit.with_vertex_color(interpolate(colors[i0], colors[i1], colors[i2])),
+            None => hit,
+        };
+
+        Some(MaterialHitRecord::from_hit(hit, mesh.material.clone()))
+    }
+
+    fn bounding_box(&self, _interval: Interval) -> Option<AABB> {
+        Some(AABB::from_points(&self.mesh.vertices(self.index)).padded(AABB::PADDING))
+    }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::MeshTriangle(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::color::Color;
+    use crate::testing::{grey_material, EPSILON};
+
+    // Unit square on the z = 0 plane, split along its diagonal
+    fn make_square(normals: Option<Vec<Vec3>>, texture_coords: Option<Vec<Vec2>>) -> TriangleMesh {
+        let material = grey_material();
+
+        TriangleMesh::new(
+            vec![
+                Point3(0.0, 0.0, 0.0),
+                Point3(1.0, 0.0, 0.0),
+                Point3(1.0, 1.0, 0.0),
+                Point3(0.0, 1.0, 0.0),
+            ],
+            vec![[0, 1, 2], [0, 2, 3]],
+            normals,
+            texture_coords,
+            material,
+        )
+    }
+
+    #[test]
+    fn must_interpolate_texture_coordinates() {
+        let coords = vec![
+            Vec2(0.0, 0.0),
+            Vec2(2.0, 0.0),
+            Vec2(2.0, 2.0),
+            Vec2(0.0, 2.0),
+        ];
+
+        let bvh = make_square(None, Some(coords))
+            .into_bvh(Interval::new(0.0, 1.0), &BvhOptions::default());
+
+        let ray = TimeRay3::new(Point3(0.25, 0.75, 1.0), Vec3(0.0, 0.0, -1.0), 0.0);
+
+        let hit = bvh.hit(ray, 0.0, 10.0).unwrap();
+        let uv = hit.hit().texture_coordinate();
+
+        assert!((uv.x() - 0.5).abs() < EPSILON);
+        assert!((uv.y() - 1.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn must_interpolate_vertex_colors() {
+        let colors = vec![
+            Color(1.0, 0.0, 0.0),
+            Color(0.0, 1.0, 0.0),
+            Color(0.0, 0.0, 1.0),
+            Color(0.0, 0.0, 1.0),
+        ];
+
+        let bvh = make_square(None, None)
+            .with_colors(colors)
+            .into_bvh(Interval::new(0.0, 1.0), &BvhOptions::default());
+
+        let ray = TimeRay3::new(Point3(0.5, 0.25, 1.0), Vec3(0.0, 0.0, -1.0), 0.0);
+
+        let color = bvh
+            .hit(ray, 0.0, 10.0)
+            .unwrap()
+            .hit()
+            .vertex_color()
+            .unwrap();
+
+        assert!((color.x() - 0.5).abs() < EPSILON);
+        assert!((color.y() - 0.25).abs() < EPSILON);
+        assert!((color.z() - 0.25).abs() < EPSILON);
+    }
+
+    #[test]
+    fn must_interpolate_shading_normals() {
+        let normals = vec![
+            Vec3(-1.0, 0.0, 1.0).normalized(),
+            Vec3(1.0, 0.0, 1.0).normalized(),
+            Vec3(1.0, 0.0, 1.0).normalized(),
+            Vec3(-1.0, 0.0, 1.0).normalized(),
+        ];
+
+        let mesh = Arc::new(make_square(Some(normals), None));
+        let triangles = mesh.triangles();
+
+        // Below the mesh, looking up: the shading normal must face the ray
+        let ray = TimeRay3::new(Point3(0.5, 0.25, -1.0), Vec3(0.0, 0.0, 1.0), 0.0);
+
+        let hit = triangles[0].hit(ray, 0.0, 10.0).unwrap();
+
+        assert!(!hit.front_face());
+        assert!(hit.normal().x().abs() < EPSILON);
+        assert!((hit.normal().z() + 1.0).abs() < EPSILON);
+
+        let ray = TimeRay3::new(Point3(0.75, 0.25, 1.0), Vec3(0.0, 0.0, -1.0), 0.0);
+
+        let hit = triangles[0].hit(ray, 0.0, 10.0).unwrap();
+
+        assert!(hit.front_face());
+        assert!(hit.normal().x() > 0.0 && hit.normal().z() > 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Triangle index out of bounds")]
+    fn must_reject_invalid_indices() {
+        let material = grey_material();
+
+        TriangleMesh::new(
+            vec![Point3(0.0, 0.0, 0.0)],
+            vec![[0, 1, 2]],
+            None,
+            None,
+            material,
+        );
+    }
+}
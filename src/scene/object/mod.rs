@@ -1,7 +1,26 @@
 mod aabb;
+mod animated;
 mod bvh;
+mod cuboid;
+mod instance;
+mod mesh;
 pub mod movingsphere;
+mod quad;
+mod rect;
 pub mod sphere;
+mod transformed;
+mod triangle;
 
 pub use aabb::*;
+pub use animated::*;
+#[allow(unused_imports)]
 pub use bvh::*;
+pub use cuboid::*;
+pub use instance::*;
+#[allow(unused_imports)]
+pub use mesh::*;
+pub use quad::*;
+pub use rect::*;
+pub use transformed::*;
+#[allow(unused_imports)]
+pub use triangle::*;
@@ -1,17 +1,17 @@
 use crate::core::geometry::{Point3, Ray, Vec2, Vec3, Vector};
 use crate::materials::Material;
-use crate::scene::{Hit, MaterialHitRecord};
+use crate::scene::{Hit, HitKind, MaterialHitRecord};
 
 use crate::core::time::{Interval, TimeRay3, Timestamp};
 use crate::scene::object::AABB;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub struct MovingSphere {
     start_center: Point3,
     end_center: Point3,
     time_interval: Interval,
     radius: f64,
-    material: Rc<dyn Material>,
+    material: Arc<dyn Material>,
 }
 
 #[allow(dead_code)]
@@ -21,7 +21,7 @@ impl MovingSphere {
         end_center: Point3,
         time_interval: Interval,
         radius: f64,
-        material: Rc<dyn Material>,
+        material: Arc<dyn Material>,
     ) -> Self {
         Self {
             start_center,
@@ -32,6 +32,23 @@ impl MovingSphere {
         }
     }
 
+    pub const fn start_center(&self) -> Point3 {
+        self.start_center
+    }
+
+    pub const fn end_center(&self) -> Point3 {
+        self.end_center
+    }
+
+    /// Times at which the sphere is at its start and end centers
+    pub const fn time_interval(&self) -> Interval {
+        self.time_interval
+    }
+
+    pub fn material(&self) -> Arc<dyn Material> {
+        self.material.clone()
+    }
+
     pub const fn radius(&self) -> f64 {
         self.radius
     }
@@ -101,4 +118,8 @@ impl Hit for MovingSphere {
 
         Some(box0.surrounding_box(&box1))
     }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::MovingSphere(self)
+    }
 }
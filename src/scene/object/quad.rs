@@ -0,0 +1,156 @@
+use crate::core::geometry::{Point2, Point3, Ray, Vec3, Vector};
+use crate::materials::Material;
+use crate::scene::{Hit, HitKind, MaterialHitRecord};
+
+use crate::core::time::{Interval, TimeRay3};
+use crate::scene::object::AABB;
+use std::sync::Arc;
+
+/// Parallelogram with a corner at `origin` and sides `u` and `v`. The
+/// outward normal follows the right-hand rule, `u x v`.
+pub struct Quad {
+    origin: Point3,
+    u: Vec3,
+    v: Vec3,
+    normal: Vec3,
+    // Plane equation: normal . p = d
+    d: f64,
+    // Projects points on the plane to the (u, v) basis
+    w: Vec3,
+    material: Arc<dyn Material>,
+}
+
+#[allow(dead_code)]
+impl Quad {
+    pub fn new(origin: Point3, u: Vec3, v: Vec3, material: Arc<dyn Material>) -> Self {
+        let n = u.cross(v);
+        let normal = n.normalized();
+
+        Self {
+            origin,
+            u,
+            v,
+            normal,
+            d: normal.dot(origin),
+            w: n / n.dot(n),
+            material,
+        }
+    }
+
+    pub const fn origin(&self) -> Point3 {
+        self.origin
+    }
+
+    pub const fn u(&self) -> Vec3 {
+        self.u
+    }
+
+    pub const fn v(&self) -> Vec3 {
+        self.v
+    }
+
+    pub fn material(&self) -> Arc<dyn Material> {
+        self.material.clone()
+    }
+}
+
+impl Hit for Quad {
+    fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
+        let denominator = self.normal.dot(ray.direction());
+
+        // Parallel to the plane
+        if denominator.abs() < 1e-8 {
+            return None;
+        }
+
+        let t = (self.d - self.normal.dot(ray.origin())) / denominator;
+
+        if !(t_min < t && t < t_max) {
+            return None;
+        }
+
+        let point = ray.at(t);
+        let planar = point - self.origin;
+
+        let alpha = self.w.dot(planar.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar));
+
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        Some(MaterialHitRecord::new(
+            point,
+            t,
+            ray.to_ray(),
+            Point2(alpha, beta),
+            self.normal,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self, _interval: Interval) -> Option<AABB> {
+        let corners = [
+            self.origin,
+            self.origin + self.u,
+            self.origin + self.v,
+            self.origin + self.u + self.v,
+        ];
+
+        Some(AABB::from_points(&corners).padded(AABB::PADDING))
+    }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::Quad(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::grey_material;
+
+    fn make_quad() -> Quad {
+        let material = grey_material();
+
+        // Slanted quad, spanning x in [0, 2] and rising along z
+        Quad::new(
+            Point3(0.0, 0.0, 0.0),
+            Vec3(2.0, 0.0, 0.0),
+            Vec3(0.0, 1.0, 1.0),
+            material,
+        )
+    }
+
+    #[test]
+    fn must_hit_inside_with_uv() {
+        let quad = make_quad();
+
+        let ray = TimeRay3::new(Point3(0.5, 0.5, 5.0), Vec3(0.0, 0.0, -1.0), 0.0);
+
+        let hit = quad.hit(ray, 0.0, 10.0).unwrap();
+        let uv = hit.hit().texture_coordinate();
+
+        assert!((hit.t() - 4.5).abs() < 1e-9);
+        assert!((uv.x() - 0.25).abs() < 1e-9);
+        assert!((uv.y() - 0.5).abs() < 1e-9);
+        assert!(hit.front_face());
+    }
+
+    #[test]
+    fn must_miss_outside() {
+        let quad = make_quad();
+
+        let ray = TimeRay3::new(Point3(2.5, 0.5, 5.0), Vec3(0.0, 0.0, -1.0), 0.0);
+
+        assert!(quad.hit(ray, 0.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn must_bound_every_corner() {
+        let aabb = make_quad().bounding_box(Interval::new(0.0, 1.0)).unwrap();
+
+        assert_eq!(Point3(0.0, 0.0, 0.0), aabb.min());
+        assert_eq!(Point3(2.0, 1.0, 1.0), aabb.max());
+    }
+}
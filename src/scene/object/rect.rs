@@ -0,0 +1,239 @@
+use crate::core::geometry::{Point2, Point3, Ray, Vec3};
+use crate::materials::Material;
+use crate::scene::{Hit, HitKind, MaterialHitRecord};
+
+use crate::core::time::{Interval, TimeRay3};
+use crate::scene::object::AABB;
+use std::sync::Arc;
+
+/// Plane holding an axis-aligned rectangle.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Plane {
+    XY,
+    XZ,
+    YZ,
+}
+
+impl Plane {
+    /// Indices of the two axes spanning the plane and of the normal axis.
+    const fn axes(self) -> (usize, usize, usize) {
+        match self {
+            Self::XY => (0, 1, 2),
+            Self::XZ => (0, 2, 1),
+            Self::YZ => (1, 2, 0),
+        }
+    }
+}
+
+/// Rectangle `[a0, a1] x [b0, b1]` lying on an axis-aligned plane at
+/// coordinate `k` of the normal axis. Its outward normal points to the
+/// positive side of that axis, unless the rectangle is flipped.
+pub struct AxisRect {
+    plane: Plane,
+    a0: f64,
+    a1: f64,
+    b0: f64,
+    b1: f64,
+    k: f64,
+    normal_sign: f64,
+    material: Arc<dyn Material>,
+}
+
+#[allow(dead_code)]
+impl AxisRect {
+    pub fn new(
+        plane: Plane,
+        (a0, a1): (f64, f64),
+        (b0, b1): (f64, f64),
+        k: f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        Self {
+            plane,
+            a0: a0.min(a1),
+            a1: a0.max(a1),
+            b0: b0.min(b1),
+            b1: b0.max(b1),
+            k,
+            normal_sign: 1.0,
+            material,
+        }
+    }
+
+    /// Makes the outward normal point to the negative side of the axis.
+    pub fn flipped(mut self) -> Self {
+        self.normal_sign = -self.normal_sign;
+        self
+    }
+
+    pub fn xy(x: (f64, f64), y: (f64, f64), z: f64, material: Arc<dyn Material>) -> Self {
+        Self::new(Plane::XY, x, y, z, material)
+    }
+
+    pub fn xz(x: (f64, f64), z: (f64, f64), y: f64, material: Arc<dyn Material>) -> Self {
+        Self::new(Plane::XZ, x, z, y, material)
+    }
+
+    pub fn yz(y: (f64, f64), z: (f64, f64), x: f64, material: Arc<dyn Material>) -> Self {
+        Self::new(Plane::YZ, y, z, x, material)
+    }
+
+    pub const fn plane(&self) -> Plane {
+        self.plane
+    }
+
+    /// Bounds along the first axis of the plane
+    pub const fn a(&self) -> (f64, f64) {
+        (self.a0, self.a1)
+    }
+
+    /// Bounds along the second axis of the plane
+    pub const fn b(&self) -> (f64, f64) {
+        (self.b0, self.b1)
+    }
+
+    /// Position along the axis normal to the plane
+    pub const fn k(&self) -> f64 {
+        self.k
+    }
+
+    /// Whether the normal points towards the negative side of the axis
+    pub fn is_flipped(&self) -> bool {
+        self.normal_sign < 0.0
+    }
+
+    pub fn material(&self) -> Arc<dyn Material> {
+        self.material.clone()
+    }
+}
+
+impl Hit for AxisRect {
+    fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
+        let (a_axis, b_axis, k_axis) = self.plane.axes();
+
+        let t = (self.k - ray.origin()[k_axis]) / ray.direction()[k_axis];
+
+        if !(t_min < t && t < t_max) {
+            return None;
+        }
+
+        let point = ray.at(t);
+
+        let a = point[a_axis];
+        let b = point[b_axis];
+
+        if a < self.a0 || a > self.a1 || b < self.b0 || b > self.b1 {
+            return None;
+        }
+
+        let text_coord = Point2(
+            (a - self.a0) / (self.a1 - self.a0),
+            (b - self.b0) / (self.b1 - self.b0),
+        );
+
+        let mut outward_normal = Vec3(0.0, 0.0, 0.0);
+        outward_normal[k_axis] = self.normal_sign;
+
+        Some(MaterialHitRecord::new(
+            point,
+            t,
+            ray.to_ray(),
+            text_coord,
+            outward_normal,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self, _interval: Interval) -> Option<AABB> {
+        let (a_axis, b_axis, k_axis) = self.plane.axes();
+
+        let mut min = Point3(0.0, 0.0, 0.0);
+        let mut max = Point3(0.0, 0.0, 0.0);
+
+        min[a_axis] = self.a0;
+        max[a_axis] = self.a1;
+        min[b_axis] = self.b0;
+        max[b_axis] = self.b1;
+        min[k_axis] = self.k;
+        max[k_axis] = self.k;
+
+        Some(AABB::new(min, max).padded(AABB::PADDING))
+    }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::AxisRect(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::grey_material;
+
+    fn make_rect(plane: Plane) -> AxisRect {
+        let material = grey_material();
+
+        AxisRect::new(plane, (0.0, 2.0), (0.0, 4.0), 1.0, material)
+    }
+
+    #[test]
+    fn must_hit_with_uv_and_normal() {
+        let rect = make_rect(Plane::XZ);
+
+        let ray = TimeRay3::new(Point3(1.0, 3.0, 1.0), Vec3(0.0, -1.0, 0.0), 0.0);
+
+        let hit = rect.hit(ray, 0.0, 10.0).unwrap();
+        let uv = hit.hit().texture_coordinate();
+
+        assert!((hit.t() - 2.0).abs() < 1e-9);
+        assert!((uv.x() - 0.5).abs() < 1e-9);
+        assert!((uv.y() - 0.25).abs() < 1e-9);
+        assert!(hit.front_face());
+        assert_eq!(Vec3(0.0, 1.0, 0.0), hit.normal());
+    }
+
+    #[test]
+    fn must_flip_normal_from_behind() {
+        let rect = make_rect(Plane::YZ);
+
+        let ray = TimeRay3::new(Point3(0.0, 1.0, 1.0), Vec3(1.0, 0.0, 0.0), 0.0);
+
+        let hit = rect.hit(ray, 0.0, 10.0).unwrap();
+
+        assert!(!hit.front_face());
+        assert_eq!(Vec3(-1.0, 0.0, 0.0), hit.normal());
+    }
+
+    #[test]
+    fn must_point_flipped_normal_backwards() {
+        let rect = make_rect(Plane::YZ).flipped();
+
+        let ray = TimeRay3::new(Point3(0.0, 1.0, 1.0), Vec3(1.0, 0.0, 0.0), 0.0);
+
+        let hit = rect.hit(ray, 0.0, 10.0).unwrap();
+
+        assert!(hit.front_face());
+        assert_eq!(Vec3(-1.0, 0.0, 0.0), hit.normal());
+    }
+
+    #[test]
+    fn must_miss_outside_and_parallel() {
+        let rect = make_rect(Plane::XY);
+
+        let outside = TimeRay3::new(Point3(3.0, 1.0, 0.0), Vec3(0.0, 0.0, 1.0), 0.0);
+        let parallel = TimeRay3::new(Point3(1.0, 1.0, 0.0), Vec3(1.0, 0.0, 0.0), 0.0);
+
+        assert!(rect.hit(outside, 0.0, 10.0).is_none());
+        assert!(rect.hit(parallel, 0.0, 10.0).is_none());
+    }
+
+    #[test]
+    fn must_pad_flat_bounding_box() {
+        let aabb = make_rect(Plane::XY)
+            .bounding_box(Interval::new(0.0, 1.0))
+            .unwrap();
+
+        assert!(aabb.max().z() - aabb.min().z() > 0.0);
+        assert!((aabb.max().x() - 2.0).abs() < 1e-9);
+    }
+}
@@ -1,21 +1,21 @@
 use crate::core::geometry::{Point2, Point3, Ray, Vector};
 use crate::materials::Material;
-use crate::scene::{Hit, MaterialHitRecord};
+use crate::scene::{Hit, HitKind, MaterialHitRecord};
 
 use crate::core::math::constants::PI;
 use crate::core::time::{Interval, TimeRay3};
 use crate::scene::object::AABB;
-use std::rc::Rc;
+use std::sync::Arc;
 
 pub struct Sphere {
     center: Point3,
     radius: f64,
-    material: Rc<dyn Material>,
+    material: Arc<dyn Material>,
 }
 
 #[allow(dead_code)]
 impl Sphere {
-    pub fn new(center: Point3, radius: f64, material: Rc<dyn Material>) -> Self {
+    pub fn new(center: Point3, radius: f64, material: Arc<dyn Material>) -> Self {
         Self {
             center,
             radius,
@@ -41,7 +41,7 @@ impl Sphere {
         self.radius
     }
 
-    pub fn material(&self) -> Rc<dyn Material> {
+    pub fn material(&self) -> Arc<dyn Material> {
         self.material.clone()
     }
 }
@@ -98,4 +98,8 @@ impl Hit for Sphere {
             self.center + radius_vec,
         ))
     }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::Sphere(self)
+    }
 }
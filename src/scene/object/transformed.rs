@@ -0,0 +1,116 @@
+use crate::core::geometry::Transform;
+use crate::core::time::{Interval, TimeRay3};
+use crate::scene::object::AABB;
+use crate::scene::{Hit, HitKind, MaterialHitRecord};
+
+/// Object moved, rotated or scaled by a transform.
+///
+/// Rays are brought into the space of the object and hits are brought back,
+/// with normals transformed by the inverse transpose.
+pub struct Transformed<H: Hit> {
+    object: H,
+    transform: Transform,
+}
+
+#[allow(dead_code)]
+impl<H: Hit> Transformed<H> {
+    pub const fn new(object: H, transform: Transform) -> Self {
+        Self { object, transform }
+    }
+
+    pub const fn object(&self) -> &H {
+        &self.object
+    }
+
+    pub const fn transform(&self) -> &Transform {
+        &self.transform
+    }
+}
+
+impl<H: Hit> Hit for Transformed<H> {
+    fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
+        // Distances along the ray are kept, so the range applies as is
+        let local_ray = ray.inverse_transformed(&self.transform);
+
+        let hit = self.object.hit(local_ray, t_min, t_max)?;
+
+        let world_hit = hit.hit().transformed(
+            self.transform.point(hit.point()),
+            self.transform.normal(hit.normal()),
+        );
+
+        Some(MaterialHitRecord::from_hit(world_hit, hit.material()))
+    }
+
+    fn bounding_box(&self, interval: Interval) -> Option<AABB> {
+        self.object
+            .bounding_box(interval)
+            .map(|aabb| aabb.transformed(&self.transform))
+    }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::Transformed(&self.transform, &self.object)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::{Point3, Vec3, Vector};
+    use crate::scene::object::Cuboid;
+    use crate::testing::{grey_material, EPSILON};
+
+    fn unit_cube() -> Cuboid {
+        let material = grey_material();
+
+        Cuboid::new(Point3(0.0, 0.0, 0.0), Point3(1.0, 1.0, 1.0), material)
+    }
+
+    #[test]
+    fn must_hit_with_transformed_normals() {
+        // Cube rotated by 45 degrees around y, with a corner towards -z
+        let transform = Transform::rotation(Vec3(0.0, 1.0, 0.0), 45.0)
+            .then(&Transform::translation(Vec3(0.0, 0.0, 2.0)));
+        let cube = Transformed::new(unit_cube(), transform);
+
+        let ray = TimeRay3::new(Point3(0.2, 0.5, -5.0), Vec3(0.0, 0.0, 1.0), 0.0);
+        let hit = cube.hit(ray, 0.001, f64::INFINITY).unwrap();
+
+        let half = 0.5_f64.sqrt();
+
+        // The face hit is the x = 0 face, whose normal was -x
+        assert!((hit.normal() - Vec3(-half, 0.0, -half)).length() < EPSILON);
+        assert!((hit.point().z() - (2.0 - 0.2)).abs() < EPSILON);
+        assert!((hit.t() - (7.0 - 0.2)).abs() < EPSILON);
+    }
+
+    #[test]
+    fn must_bound_transformed_objects() {
+        let transform = Transform::scaling(Vec3(1.0, 2.0, 1.0))
+            .then(&Transform::rotation(Vec3(0.0, 0.0, 1.0), 90.0));
+        let cube = Transformed::new(unit_cube(), transform);
+
+        let aabb = cube.bounding_box(Interval::new(0.0, 1.0)).unwrap();
+
+        assert!((aabb.min() - Point3(-2.0, 0.0, 0.0)).length() < 1e-3);
+        assert!((aabb.max() - Point3(0.0, 1.0, 1.0)).length() < 1e-3);
+    }
+
+    #[test]
+    fn must_move_rays_both_ways() {
+        let transform = Transform::scaling(Vec3(2.0, 3.0, 4.0))
+            .then(&Transform::translation(Vec3(1.0, 1.0, 1.0)));
+        let ray = TimeRay3::new(Point3(1.0, 2.0, 3.0), Vec3(0.0, 1.0, -1.0), 0.25);
+
+        let moved = ray.transformed(&transform);
+
+        assert!((moved.origin() - Point3(3.0, 7.0, 13.0)).length() < EPSILON);
+        assert!((moved.direction() - Vec3(0.0, 3.0, -4.0)).length() < EPSILON);
+
+        let back = moved.inverse_transformed(&transform);
+
+        assert!((back.origin() - ray.origin()).length() < EPSILON);
+        assert!((back.direction() - ray.direction()).length() < EPSILON);
+        assert_eq!(ray.time(), back.time());
+    }
+}
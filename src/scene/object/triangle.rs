@@ -0,0 +1,255 @@
+use crate::core::geometry::{Point2, Point3, Ray, Vec3, Vector};
+use crate::materials::Material;
+use crate::scene::{Hit, HitKind, MaterialHitRecord};
+
+use crate::core::time::{Interval, TimeRay3};
+use crate::scene::object::AABB;
+use std::sync::Arc;
+
+/// Single triangle, with the outward normal given by the counter-clockwise
+/// order of its vertices.
+pub struct Triangle {
+    vertices: [Point3; 3],
+    material: Arc<dyn Material>,
+}
+
+/// Distance and barycentric coordinates of a ray-triangle intersection.
+#[derive(Copy, Clone, Debug)]
+pub struct TriangleIntersection {
+    pub t: f64,
+    pub barycentric: [f64; 3],
+}
+
+#[allow(dead_code)]
+impl Triangle {
+    pub fn new(vertices: [Point3; 3], material: Arc<dyn Material>) -> Self {
+        Self { vertices, material }
+    }
+
+    pub const fn vertices(&self) -> [Point3; 3] {
+        self.vertices
+    }
+
+    pub fn material(&self) -> Arc<dyn Material> {
+        self.material.clone()
+    }
+}
+
+impl Hit for Triangle {
+    fn hit(&self, ray: TimeRay3, t_min: f64, t_max: f64) -> Option<MaterialHitRecord> {
+        let intersection = intersect(ray, self.vertices, t_min, t_max)?;
+        let [_, b1, b2] = intersection.barycentric;
+
+        let [p0, p1, p2] = self.vertices;
+        let outward_normal = (p1 - p0).cross(p2 - p0).normalized();
+
+        Some(MaterialHitRecord::new(
+            ray.at(intersection.t),
+            intersection.t,
+            ray.to_ray(),
+            Point2(b1, b2),
+            outward_normal,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self, _interval: Interval) -> Option<AABB> {
+        Some(AABB::from_points(&self.vertices).padded(AABB::PADDING))
+    }
+
+    fn kind(&self) -> HitKind<'_> {
+        HitKind::Triangle(self)
+    }
+}
+
+fn max_dimension(vec: Vec3) -> usize {
+    if vec.x() > vec.y() {
+        if vec.x() > vec.z() {
+            0
+        } else {
+            2
+        }
+    } else if vec.y() > vec.z() {
+        1
+    } else {
+        2
+    }
+}
+
+fn permute(vec: Vec3, x: usize, y: usize, z: usize) -> Vec3 {
+    Vec3(vec[x], vec[y], vec[z])
+}
+
+/// Watertight ray-triangle intersection (Woop, Benthin and Wald, 2013).
+///
+/// The triangle is moved to a space where the ray starts at the origin and
+/// goes along +z, so the edge tests become 2D and rays through shared edges
+/// or vertices never slip between neighbouring triangles.
+pub fn intersect(
+    ray: TimeRay3,
+    vertices: [Point3; 3],
+    t_min: f64,
+    t_max: f64,
+) -> Option<TriangleIntersection> {
+    let direction = ray.direction();
+
+    // Translate the vertices to the ray origin
+    let [p0, p1, p2] = vertices;
+    let (p0, p1, p2) = (p0 - ray.origin(), p1 - ray.origin(), p2 - ray.origin());
+
+    // Make the largest direction component be z
+    let abs_direction = Vec3(
+        direction.x().abs(),
+        direction.y().abs(),
+        direction.z().abs(),
+    );
+
+    let kz = max_dimension(abs_direction);
+    let kx = (kz + 1) % 3;
+    let ky = (kx + 1) % 3;
+
+    let direction = permute(direction, kx, ky, kz);
+    let mut p0 = permute(p0, kx, ky, kz);
+    let mut p1 = permute(p1, kx, ky, kz);
+    let mut p2 = permute(p2, kx, ky, kz);
+
+    // Shear the direction to +z
+    let shear_x = -direction.x() / direction.z();
+    let shear_y = -direction.y() / direction.z();
+    let shear_z = 1.0 / direction.z();
+
+    for p in [&mut p0, &mut p1, &mut p2].iter_mut() {
+        p.0 = shear_x.mul_add(p.2, p.0);
+        p.1 = shear_y.mul_add(p.2, p.1);
+    }
+
+    // Edge functions
+    let e0 = p1.x() * p2.y() - p1.y() * p2.x();
+    let e1 = p2.x() * p0.y() - p2.y() * p0.x();
+    let e2 = p0.x() * p1.y() - p0.y() * p1.x();
+
+    if (e0 < 0.0 || e1 < 0.0 || e2 < 0.0) && (e0 > 0.0 || e1 > 0.0 || e2 > 0.0) {
+        return None;
+    }
+
+    let determinant = e0 + e1 + e2;
+
+    if determinant == 0.0 {
+        return None;
+    }
+
+    // Scaled distance, compared against the scaled range to delay the
+    // division until a hit is certain
+    let t_scaled = e0 * p0.z() * shear_z + e1 * p1.z() * shear_z + e2 * p2.z() * shear_z;
+
+    if determinant < 0.0 && (t_scaled >= t_min * determinant || t_scaled <= t_max * determinant) {
+        return None;
+    }
+
+    if determinant > 0.0 && (t_scaled <= t_min * determinant || t_scaled >= t_max * determinant) {
+        return None;
+    }
+
+    let inverse = 1.0 / determinant;
+
+    Some(TriangleIntersection {
+        t: t_scaled * inverse,
+        barycentric: [e0 * inverse, e1 * inverse, e2 * inverse],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{grey_material, EPSILON};
+
+    fn make_triangle() -> Triangle {
+        let material = grey_material();
+
+        Triangle::new(
+            [
+                Point3(0.0, 0.0, 0.0),
+                Point3(1.0, 0.0, 0.0),
+                Point3(0.0, 1.0, 0.0),
+            ],
+            material,
+        )
+    }
+
+    #[test]
+    fn must_hit_with_barycentric_coordinates() {
+        let ray = TimeRay3::new(Point3(0.25, 0.5, 2.0), Vec3(0.0, 0.0, -1.0), 0.0);
+
+        let intersection = intersect(ray, make_triangle().vertices(), 0.0, 10.0).unwrap();
+        let [b0, b1, b2] = intersection.barycentric;
+
+        assert!((intersection.t - 2.0).abs() < EPSILON);
+        assert!((b0 - 0.25).abs() < EPSILON);
+        assert!((b1 - 0.25).abs() < EPSILON);
+        assert!((b2 - 0.5).abs() < EPSILON);
+    }
+
+    #[test]
+    fn must_set_front_face_from_winding() {
+        let triangle = make_triangle();
+
+        let front = TimeRay3::new(Point3(0.2, 0.2, 1.0), Vec3(0.0, 0.0, -1.0), 0.0);
+        let back = TimeRay3::new(Point3(0.2, 0.2, -1.0), Vec3(0.0, 0.0, 1.0), 0.0);
+
+        let hit = triangle.hit(front, 0.0, 10.0).unwrap();
+        assert!(hit.front_face());
+        assert_eq!(Vec3(0.0, 0.0, 1.0), hit.normal());
+
+        let hit = triangle.hit(back, 0.0, 10.0).unwrap();
+        assert!(!hit.front_face());
+        assert_eq!(Vec3(0.0, 0.0, -1.0), hit.normal());
+    }
+
+    #[test]
+    fn must_respect_distance_range() {
+        let triangle = make_triangle();
+
+        let ray = TimeRay3::new(Point3(0.2, 0.2, 1.0), Vec3(0.0, 0.0, -1.0), 0.0);
+
+        assert!(triangle.hit(ray, 0.0, 0.5).is_none());
+        assert!(triangle.hit(ray, 1.5, 10.0).is_none());
+    }
+
+    #[test]
+    fn must_not_leak_through_shared_edge() {
+        let second = [
+            Point3(1.0, 0.0, 0.0),
+            Point3(1.0, 1.0, 0.0),
+            Point3(0.0, 1.0, 0.0),
+        ];
+
+        // Rays crossing the diagonal shared by both triangles
+        for step in 1..100 {
+            let s = step as f64 / 100.0;
+            let origin = Point3(s, 1.0 - s, 3.0);
+            let ray = TimeRay3::new(
+                origin,
+                Vec3(0.1, 0.3, -1.0) * 1e-3 + Vec3(0.0, 0.0, -1.0),
+                0.0,
+            );
+
+            let first_hit = intersect(ray, make_triangle().vertices(), 0.0, 10.0);
+            let second_hit = intersect(ray, second, 0.0, 10.0);
+
+            let inside = |hit: &Option<TriangleIntersection>| hit.is_some();
+
+            assert!(inside(&first_hit) || inside(&second_hit), "Leak at {}", s);
+        }
+    }
+
+    #[test]
+    fn must_miss_outside_and_parallel() {
+        let triangle = make_triangle();
+
+        let outside = TimeRay3::new(Point3(0.8, 0.8, 1.0), Vec3(0.0, 0.0, -1.0), 0.0);
+        let parallel = TimeRay3::new(Point3(-1.0, 0.2, 0.0), Vec3(1.0, 0.0, 0.0), 0.0);
+
+        assert!(triangle.hit(outside, 0.0, 10.0).is_none());
+        assert!(triangle.hit(parallel, 0.0, 10.0).is_none());
+    }
+}
@@ -0,0 +1,288 @@
+use crate::core::time::{Interval, Timestamp};
+
+/// How far the shutter is open over the exposure, which weights the times
+/// of the rays.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum ShutterCurve {
+    /// Opens and closes instantly
+    #[default]
+    Box,
+    /// Opens linearly until the middle of the exposure, then closes
+    Triangle,
+    /// Opens linearly during the fraction `open` of the exposure and closes
+    /// linearly during the fraction `close`
+    Trapezoid { open: f64, close: f64 },
+    /// Openings at evenly spaced times, from the start to the end of the
+    /// exposure, linearly interpolated
+    Tabulated(Vec<f64>),
+}
+
+impl ShutterCurve {
+    /// Names of the curves, as used in scene files.
+    pub const NAMES: [&'static str; 4] = ["box", "triangle", "trapezoid", "tabulated"];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Box => "box",
+            Self::Triangle => "triangle",
+            Self::Trapezoid { .. } => "trapezoid",
+            Self::Tabulated(_) => "tabulated",
+        }
+    }
+
+    /// Checks the parameters of the curve, returning the reason it is
+    /// invalid.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        match self {
+            Self::Box | Self::Triangle => Ok(()),
+            Self::Trapezoid { open, close } => {
+                if !(*open >= 0.0 && *close >= 0.0 && open + close <= 1.0) {
+                    Err("the opening and closing fractions must be non-negative and add up to at most 1")
+                } else {
+                    Ok(())
+                }
+            }
+            Self::Tabulated(values) => {
+                if values.len() < 2 {
+                    Err("expected at least 2 values")
+                } else if values
+                    .iter()
+                    .any(|value| !(value.is_finite() && *value >= 0.0))
+                {
+                    Err("the values must be non-negative")
+                } else if values.iter().all(|value| *value == 0.0) {
+                    Err("the shutter must open at some point")
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+
+    /// Corners of the piecewise linear curve, as (fraction of the exposure,
+    /// opening) pairs.
+    fn points(&self) -> Vec<(f64, f64)> {
+        match self {
+            Self::Box => vec![(0.0, 1.0), (1.0, 1.0)],
+            Self::Triangle => vec![(0.0, 0.0), (0.5, 1.0), (1.0, 0.0)],
+            Self::Trapezoid { open, close } => {
+                // Instant openings and closings start or end fully open
+                let mut points = if *open > 0.0 {
+                    vec![(0.0, 0.0), (*open, 1.0)]
+                } else {
+                    vec![(0.0, 1.0)]
+                };
+
+                if *close > 0.0 {
+                    points.extend_from_slice(&[(1.0 - close, 1.0), (1.0, 0.0)]);
+                } else {
+                    points.push((1.0, 1.0));
+                }
+
+                // Without a fully open part, the ramps meet at a single point
+                points.dedup();
+                points
+            }
+            Self::Tabulated(values) => {
+                let last = (values.len() - 1) as f64;
+
+                values
+                    .iter()
+                    .enumerate()
+                    .map(|(index, value)| (index as f64 / last, *value))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Shutter of a camera: when each ray is traced within the exposure.
+///
+/// Times follow the opening of the [`ShutterCurve`], sampled by inverting
+/// its cumulative distribution, so every ray carries the same weight. With a
+/// rolling shutter, the rows of the image are exposed one after the other,
+/// from the top, like the sensors of most video cameras.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shutter {
+    curve: ShutterCurve,
+    /// Fraction of the exposure spent reading the rows out
+    readout: f64,
+    points: Vec<(f64, f64)>,
+    /// Area under the curve before each point
+    cdf: Vec<f64>,
+}
+
+#[allow(dead_code)]
+impl Shutter {
+    /// Global shutter following `curve`, which must be valid.
+    pub fn new(curve: ShutterCurve) -> Result<Self, &'static str> {
+        curve.validate()?;
+
+        let points = curve.points();
+
+        let mut cdf: Vec<f64> = vec![0.0];
+
+        for pair in points.windows(2) {
+            let area = 0.5 * (pair[0].1 + pair[1].1) * (pair[1].0 - pair[0].0);
+
+            cdf.push(cdf.last().unwrap() + area);
+        }
+
+        Ok(Self {
+            curve,
+            readout: 0.0,
+            points,
+            cdf,
+        })
+    }
+
+    /// Rolling shutter spending the fraction `readout` of the exposure,
+    /// between 0 and 1, on reading the rows out. Each row stays exposed for
+    /// the rest of the exposure.
+    pub fn with_readout(self, readout: f64) -> Self {
+        Self { readout, ..self }
+    }
+
+    pub const fn curve(&self) -> &ShutterCurve {
+        &self.curve
+    }
+
+    pub const fn readout(&self) -> f64 {
+        self.readout
+    }
+
+    /// Fraction of the exposure following the shutter curve, for a uniform
+    /// random number `u` between 0 and 1.
+    pub fn sample(&self, u: f64) -> f64 {
+        let target = u.clamp(0.0, 1.0) * self.cdf.last().unwrap();
+
+        // First point past the target, skipping segments of zero area
+        let next = self
+            .cdf
+            .partition_point(|value| *value <= target)
+            .max(1)
+            .min(self.cdf.len() - 1);
+
+        let (x0, y0) = self.points[next - 1];
+        let (x1, y1) = self.points[next];
+        let width = x1 - x0;
+
+        let area = target - self.cdf[next - 1];
+
+        // Solves y0 d + slope d² / 2 = area for the distance d into the
+        // segment, in a form that is stable for flat segments
+        let slope = (y1 - y0) / width;
+        let discriminant = (y0 * y0 + 2.0 * slope * area).max(0.0);
+        let root = y0 + discriminant.sqrt();
+
+        let distance = if root > 0.0 { 2.0 * area / root } else { 0.0 };
+
+        x0 + distance.clamp(0.0, width)
+    }
+
+    /// Time of a ray through the image at the height `row`, 0 at the bottom
+    /// and 1 at the top, for a uniform random number `u`.
+    pub fn time(&self, interval: Interval, row: f64, u: f64) -> Timestamp {
+        // Rows are read from the top
+        let delay = self.readout * (1.0 - row).clamp(0.0, 1.0);
+        let fraction = delay + (1.0 - self.readout) * self.sample(u);
+
+        interval.start() + fraction * (interval.end() - interval.start())
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.curve == ShutterCurve::Box && self.readout == 0.0
+    }
+}
+
+impl Default for Shutter {
+    fn default() -> Self {
+        Self::new(ShutterCurve::Box).expect("the box curve is valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fraction of `count` evenly spread samples falling in each of `bins`
+    /// bins of the exposure.
+    fn histogram(shutter: &Shutter, bins: usize, count: usize) -> Vec<f64> {
+        let mut histogram = vec![0.0; bins];
+
+        for index in 0..count {
+            let fraction = shutter.sample((index as f64 + 0.5) / count as f64);
+            let bin = ((fraction * bins as f64) as usize).min(bins - 1);
+
+            histogram[bin] += 1.0 / count as f64;
+        }
+
+        histogram
+    }
+
+    #[test]
+    fn must_sample_by_importance() {
+        let triangle = Shutter::new(ShutterCurve::Triangle).unwrap();
+
+        // The density of a triangle is 4x, then 4(1 - x)
+        let expected = [0.125, 0.375, 0.375, 0.125];
+
+        for (expected, actual) in expected.iter().zip(histogram(&triangle, 4, 10_000)) {
+            assert!((expected - actual).abs() < 1e-3, "{} {}", expected, actual);
+        }
+
+        let trapezoid = Shutter::new(ShutterCurve::Trapezoid {
+            open: 0.5,
+            close: 0.0,
+        })
+        .unwrap();
+
+        // Area of 0.25 while opening, then 0.5 fully open, out of 0.75
+        let expected = [1.0 / 12.0, 3.0 / 12.0, 4.0 / 12.0, 4.0 / 12.0];
+
+        for (expected, actual) in expected.iter().zip(histogram(&trapezoid, 4, 10_000)) {
+            assert!((expected - actual).abs() < 1e-3, "{} {}", expected, actual);
+        }
+    }
+
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn must_skip_closed_parts() {
+        let shutter = Shutter::new(ShutterCurve::Tabulated(vec![0.0, 0.0, 1.0, 1.0, 0.0])).unwrap();
+
+        for index in 0..=100 {
+            let fraction = shutter.sample(index as f64 / 100.0);
+
+            assert!((0.25..=1.0).contains(&fraction), "{}", fraction);
+        }
+
+        assert_eq!(0.25, shutter.sample(0.0));
+        assert_eq!(1.0, shutter.sample(1.0));
+        assert_eq!(0.5, Shutter::default().sample(0.5));
+    }
+
+    #[test]
+    fn must_delay_lower_rows() {
+        let shutter = Shutter::default().with_readout(0.5);
+        let interval = Interval::new(2.0, 4.0);
+
+        // The top row is exposed during the first half, the bottom row
+        // during the second one
+        assert!((shutter.time(interval, 1.0, 0.0) - 2.0).abs() < 1e-12);
+        assert!((shutter.time(interval, 1.0, 1.0) - 3.0).abs() < 1e-12);
+        assert!((shutter.time(interval, 0.0, 0.0) - 3.0).abs() < 1e-12);
+        assert!((shutter.time(interval, 0.0, 1.0) - 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn must_reject_invalid_curves() {
+        assert!(Shutter::new(ShutterCurve::Tabulated(vec![0.0, 0.0])).is_err());
+        assert!(Shutter::new(ShutterCurve::Tabulated(vec![1.0])).is_err());
+        assert!(Shutter::new(ShutterCurve::Tabulated(vec![1.0, -1.0])).is_err());
+        assert!(Shutter::new(ShutterCurve::Trapezoid {
+            open: 0.6,
+            close: 0.6
+        })
+        .is_err());
+    }
+}
@@ -1,18 +1,419 @@
 use crate::core::color::Color;
-use crate::core::geometry::{Point3, Vec3, Vector};
+use crate::core::geometry::{Point3, Vec2, Vec3, Vector};
+use crate::core::math::easing::Easing;
 use crate::core::math::rand::{rand, rand_between};
-use crate::core::time::Interval;
-use crate::materials::{Dielectric, Lambertian, Material, Metal};
+use crate::core::time::{Interval, Timestamp};
+use crate::materials::{Dielectric, DiffuseLight, Lambertian, Material, Metal};
+use crate::scene::camera::{
+    Bokeh, Camera, CubemapCamera, EquirectangularCamera, Eye, FisheyeCamera, Focus, Options,
+    OrthographicCamera, PerspectiveCamera, Placement, Projection, RealisticCamera, StereoRig,
+};
 use crate::scene::object::movingsphere::MovingSphere;
 use crate::scene::object::sphere::Sphere;
-use crate::scene::{Hit, HitList};
+use crate::scene::object::{AxisRect, Cuboid, Quad};
+use crate::scene::{Background, Hit, HitList, Shutter};
 use crate::textures::Checker;
-use std::rc::Rc;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+/// Camera placement and lens of a scene.
+#[derive(Clone, Debug, PartialEq)]
+pub struct View {
+    pub look_from: Point3,
+    pub look_at: Point3,
+    pub up: Vec3,
+    pub vertical_fov: f64,
+    pub aperture: f64,
+    pub focus_distance: f64,
+    /// Tilt of the plane in focus, in degrees
+    pub tilt: f64,
+    /// Offset of the image, in image widths and heights
+    pub shift: Vec2,
+    /// The field of view, the aperture, the tilt and the shift only apply to perspective cameras,
+    /// and the focus distance to perspective and realistic ones
+    pub projection: Projection,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        Self {
+            look_from: Point3(13.0, 2.0, 3.0),
+            look_at: Point3(0.0, 0.0, 0.0),
+            up: Vec3(0.0, 1.0, 0.0),
+            vertical_fov: 20.0,
+            aperture: 0.1,
+            focus_distance: 10.0,
+            tilt: 0.0,
+            shift: Vec2(0.0, 0.0),
+            projection: Projection::Perspective,
+        }
+    }
+}
+
+impl View {
+    /// Interpolation from `self` at `t = 0` to `other` at `t = 1`.
+    pub fn interpolated(&self, other: &Self, t: f64) -> Self {
+        let lerp = |a: f64, b: f64| a + t * (b - a);
+
+        Self {
+            look_from: self.look_from + t * (other.look_from - self.look_from),
+            look_at: self.look_at + t * (other.look_at - self.look_at),
+            up: self.up + t * (other.up - self.up),
+            vertical_fov: lerp(self.vertical_fov, other.vertical_fov),
+            aperture: lerp(self.aperture, other.aperture),
+            focus_distance: lerp(self.focus_distance, other.focus_distance),
+            tilt: lerp(self.tilt, other.tilt),
+            shift: self.shift + t * (other.shift - self.shift),
+            projection: self.projection.clone(),
+        }
+    }
+
+    /// View focused on the `focus` of the `world` at `time`, keeping its
+    /// focus distance when there is nothing to focus on.
+    pub fn focused(self, focus: &Focus, world: &dyn Hit, time: Timestamp) -> Self {
+        match focus.distance(self.look_from, self.look_at, world, time) {
+            Some(focus_distance) => Self {
+                focus_distance,
+                ..self
+            },
+            None => self,
+        }
+    }
+
+    /// Camera of this view for images of the given aspect ratio, exposed
+    /// during `interval`.
+    pub fn camera(
+        &self,
+        aspect_ratio: f64,
+        interval: Interval,
+        shutter: Shutter,
+        bokeh: &Bokeh,
+    ) -> Box<dyn Camera> {
+        self.placed_camera(self.placement(interval, shutter), aspect_ratio, bokeh, 0.0)
+    }
+
+    /// Camera of the `eye` of a stereo `rig` centered on this view.
+    pub fn eye_camera(
+        &self,
+        rig: &StereoRig,
+        eye: Eye,
+        aspect_ratio: f64,
+        interval: Interval,
+        shutter: Shutter,
+        bokeh: &Bokeh,
+    ) -> Box<dyn Camera> {
+        let placement = rig.placement(&self.placement(interval, shutter), eye);
+
+        self.placed_camera(placement, aspect_ratio, bokeh, rig.eye_offset(eye))
+    }
+
+    fn placement(&self, interval: Interval, shutter: Shutter) -> Placement {
+        Placement::new(self.look_from, self.look_at, self.up, interval).with_shutter(shutter)
+    }
+
+    /// Camera of the projection of the view at `placement`, with the `bokeh`
+    /// of perspective cameras and the omni-directional stereo `eye_offset`
+    /// of equirectangular panoramas.
+    fn placed_camera(
+        &self,
+        placement: Placement,
+        aspect_ratio: f64,
+        bokeh: &Bokeh,
+        eye_offset: f64,
+    ) -> Box<dyn Camera> {
+        match &self.projection {
+            Projection::Perspective => Box::new(
+                PerspectiveCamera::new(
+                    placement,
+                    Options {
+                        vertical_fov: self.vertical_fov,
+                        aspect_ratio,
+                        aperture: self.aperture,
+                        focus_distance: self.focus_distance,
+                        tilt: self.tilt,
+                        shift: self.shift,
+                    },
+                )
+                .with_bokeh(bokeh.clone()),
+            ),
+            Projection::Orthographic { height } => {
+                Box::new(OrthographicCamera::new(placement, *height, aspect_ratio))
+            }
+            Projection::Fisheye { mapping, fov } => {
+                Box::new(FisheyeCamera::new(placement, *mapping, *fov, aspect_ratio))
+            }
+            Projection::Equirectangular => {
+                Box::new(EquirectangularCamera::new(placement).with_eye_offset(eye_offset))
+            }
+            Projection::Cubemap { face } => Box::new(CubemapCamera::new(placement, *face)),
+            Projection::Realistic(lens) => Box::new(RealisticCamera::new(
+                placement,
+                lens.clone(),
+                self.focus_distance,
+                aspect_ratio,
+            )),
+        }
+    }
+}
+
+/// View of the camera at a given time of an animation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ViewKeyframe {
+    pub time: Timestamp,
+    pub view: View,
+    /// Easing of the motion towards the next keyframe
+    pub easing: Easing,
+}
+
+/// Frames of an animation and the shutter of each frame.
+///
+/// The shutter of frame `n` opens at `n / fps` and stays open for the
+/// fraction `shutter_angle / 360` of the frame duration.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Animation {
+    pub first_frame: u32,
+    pub last_frame: u32,
+    pub fps: f64,
+    /// Between 0 and 360 degrees; 180 is the usual film look and 360 blurs
+    /// the whole frame duration
+    pub shutter_angle: f64,
+}
+
+impl Animation {
+    pub fn frames(&self) -> RangeInclusive<u32> {
+        self.first_frame..=self.last_frame
+    }
+
+    pub fn frame_interval(&self, frame: u32) -> Interval {
+        let start = frame as f64 / self.fps;
+
+        Interval::new(start, start + self.shutter_angle / 360.0 / self.fps)
+    }
+
+    /// Interval from the opening of the first shutter to the closing of the
+    /// last one.
+    pub fn span(&self) -> Interval {
+        Interval::new(
+            self.frame_interval(self.first_frame).start(),
+            self.frame_interval(self.last_frame).end(),
+        )
+    }
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self {
+            first_frame: 1,
+            last_frame: 1,
+            fps: 24.0,
+            shutter_angle: 180.0,
+        }
+    }
+}
+
+/// Options overriding the animation of a scene, usually from the command
+/// line.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct AnimationOptions {
+    pub frames: Option<(u32, u32)>,
+    pub fps: Option<f64>,
+    pub shutter_angle: Option<f64>,
+}
+
+impl AnimationOptions {
+    /// Applies the options over the animation of the scene. Without frames,
+    /// from either side, there is no animation.
+    pub fn apply(&self, scene: Option<Animation>) -> Option<Animation> {
+        let scene = match (self.frames, scene) {
+            (None, None) => return None,
+            (_, scene) => scene.unwrap_or_default(),
+        };
+
+        let (first_frame, last_frame) =
+            self.frames.unwrap_or((scene.first_frame, scene.last_frame));
+
+        Some(Animation {
+            first_frame,
+            last_frame,
+            fps: self.fps.unwrap_or(scene.fps),
+            shutter_angle: self.shutter_angle.unwrap_or(scene.shutter_angle),
+        })
+    }
+}
+
+/// Size and sampling of the rendered image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ImageSettings {
+    pub width: u32,
+    pub height: u32,
+    pub samples_per_pixel: u32,
+    pub max_depth: u32,
+}
+
+impl ImageSettings {
+    /// Changes the width, keeping the aspect ratio unless `height` is given.
+    pub fn with_size(self, width: u32, height: Option<u32>) -> Self {
+        let height = height
+            .unwrap_or_else(|| ((width as f64) / self.aspect_ratio()).round().max(2.0) as u32);
+
+        Self {
+            width,
+            height,
+            ..self
+        }
+    }
+
+    pub fn aspect_ratio(&self) -> f64 {
+        self.width as f64 / self.height as f64
+    }
+}
+
+impl Default for ImageSettings {
+    fn default() -> Self {
+        Self {
+            width: 1280,
+            height: 720,
+            samples_per_pixel: 500,
+            max_depth: 50,
+        }
+    }
+}
+
+pub struct Scene {
+    pub world: HitList,
+    pub background: Background,
+    pub view: View,
+    pub image: ImageSettings,
+    /// Shutter interval of the camera, or of the whole animation
+    pub time: Interval,
+    pub animation: Option<Animation>,
+    /// Motion of the camera, sorted by time; `view` is used when empty
+    pub camera_keyframes: Vec<ViewKeyframe>,
+    pub shutter: Shutter,
+    pub bokeh: Bokeh,
+    /// Pair of cameras of stereo images, around the view
+    pub stereo: Option<StereoRig>,
+    /// What the camera focuses on, instead of the focus distance of the view
+    pub focus: Option<Focus>,
+}
+
+impl Scene {
+    /// Interval over which the objects are bounded: the whole animation when
+    /// there is one.
+    pub fn bounds(&self) -> Interval {
+        self.animation
+            .map_or(self.time, |animation| animation.span())
+    }
+
+    /// View of the camera at `time`, held before the first keyframe and
+    /// after the last one.
+    pub fn view_at(&self, time: Timestamp) -> View {
+        let keyframes = &self.camera_keyframes;
+        let next = keyframes.partition_point(|keyframe| keyframe.time <= time);
+
+        match (
+            next.checked_sub(1).map(|index| &keyframes[index]),
+            keyframes.get(next),
+        ) {
+            (Some(previous), Some(next)) => {
+                let progress = (time - previous.time) / (next.time - previous.time);
+
+                previous
+                    .view
+                    .interpolated(&next.view, previous.easing.apply(progress))
+            }
+            (Some(previous), None) => previous.view.clone(),
+            (None, Some(next)) => next.view.clone(),
+            (None, None) => self.view.clone(),
+        }
+    }
+}
+
+/// Names accepted by [`generate_scene`].
+pub const SCENE_NAMES: [&str; 4] = ["random", "two-spheres", "simple-light", "cornell-box"];
+
+/// Builds the scene `name`, animated over the frames of `animation` if any.
+pub fn generate_scene(name: &str, animation: &AnimationOptions) -> Option<Scene> {
+    let animation = animation.apply(None);
+    let time = animation.map_or(Interval::new(0.0, 1.0), |animation| animation.span());
+
+    let scene = match name {
+        "random" => Scene {
+            world: generate_random_scene(),
+            background: Background::sky(),
+            view: View::default(),
+            image: ImageSettings::default(),
+            time,
+            animation,
+            camera_keyframes: Vec::new(),
+            shutter: Shutter::default(),
+            bokeh: Bokeh::default(),
+            stereo: None,
+            focus: None,
+        },
+        "two-spheres" => Scene {
+            world: generate_scene_two_spheres(),
+            background: Background::sky(),
+            view: View {
+                aperture: 0.0,
+                ..View::default()
+            },
+            image: ImageSettings::default(),
+            time,
+            animation,
+            camera_keyframes: Vec::new(),
+            shutter: Shutter::default(),
+            bokeh: Bokeh::default(),
+            stereo: None,
+            focus: None,
+        },
+        "simple-light" => Scene {
+            world: generate_scene_simple_light(),
+            background: Background::Solid(Color(0.0, 0.0, 0.0)),
+            view: View {
+                look_from: Point3(26.0, 3.0, 6.0),
+                look_at: Point3(0.0, 2.0, 0.0),
+                aperture: 0.0,
+                ..View::default()
+            },
+            image: ImageSettings::default(),
+            time,
+            animation,
+            camera_keyframes: Vec::new(),
+            shutter: Shutter::default(),
+            bokeh: Bokeh::default(),
+            stereo: None,
+            focus: None,
+        },
+        "cornell-box" => Scene {
+            world: generate_cornell_box(),
+            background: Background::Solid(Color(0.0, 0.0, 0.0)),
+            view: View {
+                look_from: Point3(278.0, 278.0, -800.0),
+                look_at: Point3(278.0, 278.0, 0.0),
+                vertical_fov: 40.0,
+                aperture: 0.0,
+                ..View::default()
+            },
+            image: ImageSettings::default(),
+            time,
+            animation,
+            camera_keyframes: Vec::new(),
+            shutter: Shutter::default(),
+            bokeh: Bokeh::default(),
+            stereo: None,
+            focus: None,
+        },
+        _ => return None,
+    };
+
+    Some(scene)
+}
 
 pub fn generate_random_scene() -> HitList {
     let mut world = HitList::new();
 
-    let ground_material = Rc::new(Lambertian::new(Rc::new(Checker::from_color(
+    let ground_material = Arc::new(Lambertian::new(Arc::new(Checker::from_color(
         Color(0.2, 0.3, 0.1),
         Color(0.9, 0.9, 0.9),
     ))));
@@ -40,13 +441,13 @@ pub fn generate_random_scene() -> HitList {
             let object: Box<dyn Hit>;
 
             if (center - Point3(4.0, 0.2, 0.0)).length() > 0.9 {
-                let sphere_material: Rc<dyn Material>;
+                let sphere_material: Arc<dyn Material>;
 
                 if choose_material < 0.8 {
                     // Diffuse
                     let albedo = Color::rand() * Color::rand();
 
-                    sphere_material = Rc::new(Lambertian::from_color(albedo));
+                    sphere_material = Arc::new(Lambertian::from_color(albedo));
 
                     let center2 = center + Vec3(0.0, rand_between(0.0, 0.5), 0.0);
                     object = Box::new(MovingSphere::new(
@@ -61,12 +462,12 @@ pub fn generate_random_scene() -> HitList {
                     let albedo = Color::rand_between(0.5, 1.0);
                     let fuzz = rand_between(0.0, 0.5);
 
-                    sphere_material = Rc::new(Metal::new(albedo, fuzz));
+                    sphere_material = Arc::new(Metal::new(albedo, fuzz));
 
                     object = Box::new(Sphere::new(center, 0.2, sphere_material));
                 } else {
                     // Glass
-                    sphere_material = Rc::new(Dielectric::new(1.5));
+                    sphere_material = Arc::new(Dielectric::new(1.5));
 
                     object = Box::new(Sphere::new(center, 0.2, sphere_material));
                 }
@@ -76,9 +477,9 @@ pub fn generate_random_scene() -> HitList {
         }
     }
 
-    let material1 = Rc::new(Dielectric::new(1.5));
-    let material2 = Rc::new(Lambertian::from_color(Color(0.4, 0.4, 0.1)));
-    let material3 = Rc::new(Metal::new(Color(0.7, 0.6, 0.5), 0.0));
+    let material1 = Arc::new(Dielectric::new(1.5));
+    let material2 = Arc::new(Lambertian::from_color(Color(0.4, 0.4, 0.1)));
+    let material3 = Arc::new(Metal::new(Color(0.7, 0.6, 0.5), 0.0));
 
     world.add(Box::new(Sphere::new(Point3(0.0, 1.0, 0.0), 1.0, material1)));
 
@@ -92,15 +493,14 @@ pub fn generate_random_scene() -> HitList {
     world
 }
 
-#[allow(dead_code)]
 pub fn generate_scene_two_spheres() -> HitList {
-    let checker_texture = Rc::new(Checker::from_color(
+    let checker_texture = Arc::new(Checker::from_color(
         Color(0.2, 0.3, 0.1),
         Color(0.9, 0.9, 0.9),
     ));
 
     let mut hitlist = HitList::new();
-    let lambertian = Rc::new(Lambertian::new(checker_texture));
+    let lambertian = Arc::new(Lambertian::new(checker_texture));
 
     hitlist.add(Box::new(Sphere::new(
         Point3(0.0, -10.0, 0.0),
@@ -115,3 +515,90 @@ pub fn generate_scene_two_spheres() -> HitList {
 
     hitlist
 }
+
+pub fn generate_scene_simple_light() -> HitList {
+    let mut hitlist = HitList::new();
+
+    let checker = Arc::new(Lambertian::new(Arc::new(Checker::from_color(
+        Color(0.2, 0.3, 0.1),
+        Color(0.9, 0.9, 0.9),
+    ))));
+    let marble = Arc::new(Lambertian::from_color(Color(0.8, 0.6, 0.4)));
+
+    hitlist.add(Box::new(Sphere::new(
+        Point3(0.0, -1000.0, 0.0),
+        1000.0,
+        checker,
+    )));
+    hitlist.add(Box::new(Sphere::new(Point3(0.0, 2.0, 0.0), 2.0, marble)));
+
+    let light = Arc::new(DiffuseLight::from_color(Color(4.0, 4.0, 4.0)));
+
+    hitlist.add(Box::new(Sphere::new(
+        Point3(0.0, 7.0, 0.0),
+        2.0,
+        light.clone(),
+    )));
+    hitlist.add(Box::new(Quad::new(
+        Point3(3.0, 1.0, -2.0),
+        Vec3(2.0, 0.0, 0.0),
+        Vec3(0.0, 2.0, 0.0),
+        light,
+    )));
+
+    hitlist
+}
+
+pub fn generate_cornell_box() -> HitList {
+    let mut hitlist = HitList::new();
+
+    let red = Arc::new(Lambertian::from_color(Color(0.65, 0.05, 0.05)));
+    let white = Arc::new(Lambertian::from_color(Color(0.73, 0.73, 0.73)));
+    let green = Arc::new(Lambertian::from_color(Color(0.12, 0.45, 0.15)));
+    let light = Arc::new(DiffuseLight::from_color(Color(15.0, 15.0, 15.0)));
+
+    hitlist.add(Box::new(AxisRect::yz(
+        (0.0, 555.0),
+        (0.0, 555.0),
+        555.0,
+        green,
+    )));
+    hitlist.add(Box::new(AxisRect::yz((0.0, 555.0), (0.0, 555.0), 0.0, red)));
+    hitlist.add(Box::new(AxisRect::xz(
+        (213.0, 343.0),
+        (227.0, 332.0),
+        554.0,
+        light,
+    )));
+    hitlist.add(Box::new(AxisRect::xz(
+        (0.0, 555.0),
+        (0.0, 555.0),
+        0.0,
+        white.clone(),
+    )));
+    hitlist.add(Box::new(AxisRect::xz(
+        (0.0, 555.0),
+        (0.0, 555.0),
+        555.0,
+        white.clone(),
+    )));
+    hitlist.add(Box::new(AxisRect::xy(
+        (0.0, 555.0),
+        (0.0, 555.0),
+        555.0,
+        white.clone(),
+    )));
+
+    hitlist.add(Box::new(Cuboid::new(
+        Point3(130.0, 0.0, 65.0),
+        Point3(295.0, 165.0, 230.0),
+        white.clone(),
+    )));
+    hitlist.add(Box::new(Cuboid::new(
+        Point3(265.0, 0.0, 295.0),
+        Point3(430.0, 330.0, 460.0),
+        white,
+    )));
+
+    hitlist
+}
@@ -0,0 +1,22 @@
+use std::sync::Arc;
+
+use crate::core::color::Color;
+use crate::core::geometry::{Vec3, Vector};
+use crate::materials::{Lambertian, Material};
+
+/// Tolerance of the geometric tests.
+pub const EPSILON: f64 = 1e-9;
+
+/// Grey diffuse material, for tests that do not depend on the material.
+pub fn grey_material() -> Arc<dyn Material> {
+    Arc::new(Lambertian::from_color(Color(0.5, 0.5, 0.5)))
+}
+
+pub fn assert_close(expected: Vec3, actual: Vec3) {
+    assert!(
+        (expected - actual).length() < EPSILON,
+        "expected {:?}, found {:?}",
+        expected,
+        actual
+    );
+}
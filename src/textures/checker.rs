@@ -1,35 +1,57 @@
 use crate::core::color::Color;
 use crate::core::geometry::{Vec2, Vec3};
-use crate::textures::{SolidColor, Texture};
-use std::rc::Rc;
+use crate::scene::BasicHitRecord;
+use crate::textures::{SolidColor, Texture, TextureKind};
+use std::sync::Arc;
 
 pub struct Checker {
-    odd: Rc<dyn Texture>,
-    even: Rc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+    even: Arc<dyn Texture>,
 }
 
 impl Checker {
     #[allow(dead_code)]
-    pub fn new(odd: Rc<dyn Texture>, even: Rc<dyn Texture>) -> Self {
+    pub fn new(odd: Arc<dyn Texture>, even: Arc<dyn Texture>) -> Self {
         Self { odd, even }
     }
 
     pub fn from_color(odd: Color, even: Color) -> Self {
         Self {
-            odd: Rc::new(SolidColor::new(odd)),
-            even: Rc::new(SolidColor::new(even)),
+            odd: Arc::new(SolidColor::new(odd)),
+            even: Arc::new(SolidColor::new(even)),
         }
     }
-}
 
-impl Texture for Checker {
-    fn value(&self, coord: Vec2, point: Vec3) -> Vec3 {
+    pub fn odd(&self) -> &Arc<dyn Texture> {
+        &self.odd
+    }
+
+    pub fn even(&self) -> &Arc<dyn Texture> {
+        &self.even
+    }
+
+    /// Texture of the square containing `point`.
+    fn texture(&self, point: Vec3) -> &Arc<dyn Texture> {
         let sines = (10.0 * point.x()).sin() * (10.0 * point.y()).sin() * (10.0 * point.z()).sin();
 
         if sines < 0.0 {
-            self.odd.value(coord, point)
+            &self.odd
         } else {
-            self.even.value(coord, point)
+            &self.even
         }
     }
 }
+
+impl Texture for Checker {
+    fn value(&self, coord: Vec2, point: Vec3) -> Vec3 {
+        self.texture(point).value(coord, point)
+    }
+
+    fn hit_value(&self, hit: &BasicHitRecord) -> Color {
+        self.texture(hit.point()).hit_value(hit)
+    }
+
+    fn kind(&self) -> TextureKind<'_> {
+        TextureKind::Checker(self)
+    }
+}
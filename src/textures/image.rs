@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+
+use crate::core::color::Color;
+use crate::core::geometry::{Point3, Vec2};
+use crate::image::Framebuffer;
+use crate::textures::{Texture, TextureKind};
+
+/// Texture looked up from an image, repeating it outside of [0, 1].
+pub struct ImageTexture {
+    image: Framebuffer,
+    /// File the image was read from, needed to export the texture
+    source: Option<PathBuf>,
+}
+
+#[allow(dead_code)]
+impl ImageTexture {
+    pub const fn new(image: Framebuffer) -> Self {
+        Self {
+            image,
+            source: None,
+        }
+    }
+
+    pub fn with_source<P: AsRef<Path>>(self, path: P) -> Self {
+        Self {
+            source: Some(path.as_ref().to_path_buf()),
+            ..self
+        }
+    }
+
+    pub const fn image(&self) -> &Framebuffer {
+        &self.image
+    }
+
+    pub fn source(&self) -> Option<&Path> {
+        self.source.as_deref()
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, coord: Vec2, _point: Point3) -> Color {
+        let width = self.image.width();
+        let height = self.image.height();
+
+        if width == 0 || height == 0 {
+            return Color(0.0, 1.0, 1.0);
+        }
+
+        // Images are stored from the top, while v grows upwards
+        let u = coord.x().rem_euclid(1.0);
+        let v = 1.0 - coord.y().rem_euclid(1.0);
+
+        let x = ((u * width as f64) as u32).min(width - 1);
+        let y = ((v * height as f64) as u32).min(height - 1);
+
+        self.image.pixel(x, y)
+    }
+
+    fn kind(&self) -> TextureKind<'_> {
+        TextureKind::Image(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::geometry::Vector;
+
+    #[test]
+    fn must_flip_and_repeat_coordinates() {
+        let mut image = Framebuffer::new(2, 2);
+
+        image.set_pixel(0, 0, Color(1.0, 0.0, 0.0));
+        image.set_pixel(1, 1, Color(0.0, 0.0, 1.0));
+
+        let texture = ImageTexture::new(image);
+
+        assert_eq!(
+            Color(1.0, 0.0, 0.0),
+            texture.value(Vec2(0.25, 0.75), Point3::zero())
+        );
+        assert_eq!(
+            Color(0.0, 0.0, 1.0),
+            texture.value(Vec2(0.75, 0.25), Point3::zero())
+        );
+        assert_eq!(
+            Color(0.0, 0.0, 1.0),
+            texture.value(Vec2(1.75, -0.75), Point3::zero())
+        );
+    }
+}
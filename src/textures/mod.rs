@@ -1,7 +1,11 @@
 mod checker;
+mod image;
 mod solidcolor;
 mod texture;
+mod vertexcolor;
 
 pub use checker::*;
+pub use image::*;
 pub use solidcolor::*;
 pub use texture::*;
+pub use vertexcolor::*;
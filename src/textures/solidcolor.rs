@@ -1,6 +1,6 @@
 use crate::core::color::Color;
 use crate::core::geometry::{Vec2, Vec3};
-use crate::textures::Texture;
+use crate::textures::{Texture, TextureKind};
 
 pub struct SolidColor {
     color: Color,
@@ -10,10 +10,18 @@ impl SolidColor {
     pub const fn new(color: Color) -> Self {
         Self { color }
     }
+
+    pub const fn color(&self) -> Color {
+        self.color
+    }
 }
 
 impl Texture for SolidColor {
     fn value(&self, _coord: Vec2, _point: Vec3) -> Color {
         self.color
     }
+
+    fn kind(&self) -> TextureKind<'_> {
+        TextureKind::Solid(self)
+    }
 }
@@ -1,6 +1,33 @@
 use crate::core::color::Color;
 use crate::core::geometry::{Point3, Vec2};
+use crate::scene::BasicHitRecord;
+use crate::textures::{Checker, ImageTexture, SolidColor, VertexColor};
+use std::any::type_name;
 
-pub trait Texture {
+pub trait Texture: Send + Sync {
     fn value(&self, coord: Vec2, point: Point3) -> Color;
+
+    /// Color at a surface hit. Textures that depend on more than the texture
+    /// coordinate and the point, such as [`VertexColor`], override it.
+    ///
+    /// [`VertexColor`]: super::VertexColor
+    fn hit_value(&self, hit: &BasicHitRecord) -> Color {
+        self.value(hit.texture_coordinate(), hit.point())
+    }
+
+    /// What the texture is, for code handling each kind of texture on its
+    /// own, such as the export of scene files.
+    fn kind(&self) -> TextureKind<'_> {
+        TextureKind::Other(type_name::<Self>())
+    }
+}
+
+/// Concrete texture behind a [`Texture`].
+pub enum TextureKind<'a> {
+    Solid(&'a SolidColor),
+    Checker(&'a Checker),
+    Image(&'a ImageTexture),
+    VertexColor(&'a VertexColor),
+    /// Any other texture, named by its type
+    Other(&'static str),
 }
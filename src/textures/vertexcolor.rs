@@ -0,0 +1,35 @@
+use crate::core::color::Color;
+use crate::core::geometry::{Point3, Vec2};
+use crate::scene::BasicHitRecord;
+use crate::textures::{Texture, TextureKind};
+
+/// Color interpolated from the vertices of the mesh that was hit, or a
+/// fallback color for surfaces without vertex colors.
+pub struct VertexColor {
+    fallback: Color,
+}
+
+impl VertexColor {
+    pub const fn new(fallback: Color) -> Self {
+        Self { fallback }
+    }
+
+    /// Color of the meshes without vertex colors
+    pub const fn fallback(&self) -> Color {
+        self.fallback
+    }
+}
+
+impl Texture for VertexColor {
+    fn value(&self, _coord: Vec2, _point: Point3) -> Color {
+        self.fallback
+    }
+
+    fn hit_value(&self, hit: &BasicHitRecord) -> Color {
+        hit.vertex_color().unwrap_or(self.fallback)
+    }
+
+    fn kind(&self) -> TextureKind<'_> {
+        TextureKind::VertexColor(self)
+    }
+}